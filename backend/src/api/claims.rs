@@ -0,0 +1,313 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, ModelTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::sync::OnceLock;
+
+use crate::entity::{phone_verifications, sales, prelude::*};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+
+const CODE_TTL_MINUTES: i64 = 10;
+const CODE_RESEND_SECONDS: i64 = 60;
+const MAX_CODE_ATTEMPTS: i32 = 5;
+/// Fewer digits than this cannot identify a phone line.
+const MIN_PHONE_DIGITS: usize = 7;
+
+#[derive(Deserialize)]
+pub struct ClaimByTokenRequest {
+    pub tracking_token: String,
+}
+
+#[derive(Deserialize)]
+pub struct PhoneCodeRequest {
+    pub phone: String,
+}
+
+#[derive(Deserialize)]
+pub struct ClaimByPhoneRequest {
+    pub phone: String,
+    pub code: String,
+}
+
+#[derive(Serialize)]
+pub struct ClaimResponse {
+    pub claimed: usize,
+    pub sale_ids: Vec<i32>,
+}
+
+/// Digits only, so "+591 700-12345" and "59170012345" compare equal.
+fn normalize_phone(phone: &str) -> String {
+    phone.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+/// Bolivia's calling code, used when `PHONE_COUNTRY_CODE` is not set.
+const DEFAULT_COUNTRY_CODE: &str = "591";
+
+/// Calling code of the country where the kermesses take place, from `PHONE_COUNTRY_CODE`.
+fn country_code() -> &'static str {
+    static CODE: OnceLock<String> = OnceLock::new();
+    CODE.get_or_init(|| {
+        env::var("PHONE_COUNTRY_CODE")
+            .ok()
+            .map(|value| normalize_phone(&value))
+            .filter(|code| !code.is_empty())
+            .unwrap_or_else(|| DEFAULT_COUNTRY_CODE.to_string())
+    })
+}
+
+/// The number without the country code (written as "591..." or "00591..."), if it has it.
+fn local_number(phone: &str, country_code: &str) -> String {
+    let digits = normalize_phone(phone);
+    let international = digits.strip_prefix("00").unwrap_or(&digits);
+    match international.strip_prefix(country_code) {
+        Some(local) if local.len() >= MIN_PHONE_DIGITS => local.to_string(),
+        _ => digits,
+    }
+}
+
+/// Same full number once the country code is dropped; any other prefix must match too.
+fn same_phone(a: &str, b: &str) -> bool {
+    same_phone_in(a, b, country_code())
+}
+
+fn same_phone_in(a: &str, b: &str, country_code: &str) -> bool {
+    let (a, b) = (local_number(a, country_code), local_number(b, country_code));
+    a.len() >= MIN_PHONE_DIGITS && a == b
+}
+
+fn code_hash(user_id: i32, phone: &str, code: &str) -> String {
+    hex::encode(Sha256::digest(format!("{}:{}:{}", user_id, phone, code.trim())))
+}
+
+/// Attaches guest orders (no `buyer_id`) to the user; orders of other accounts are never moved.
+async fn attach_guest_sales(
+    data: &AppState,
+    user_id: i32,
+    sale_ids: Vec<i32>,
+) -> Result<ClaimResponse, sea_orm::DbErr> {
+    if sale_ids.is_empty() {
+        return Ok(ClaimResponse { claimed: 0, sale_ids });
+    }
+
+    let res = Sales::update_many()
+        .col_expr(sales::Column::BuyerId, Expr::value(user_id))
+        .filter(sales::Column::Id.is_in(sale_ids.clone()))
+        .filter(sales::Column::BuyerId.is_null())
+        .exec(&data.conn)
+        .await?;
+
+    Ok(ClaimResponse { claimed: res.rows_affected as usize, sale_ids })
+}
+
+/// The tracking link is only handed to whoever placed the order, so holding it proves ownership.
+pub async fn claim_by_token(
+    req: web::Json<ClaimByTokenRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    // Accept the whole link as well as the bare token
+    let token = req.tracking_token.trim().trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+
+    let sale = match Sales::find()
+        .filter(sales::Column::TrackingToken.eq(token))
+        .one(&data.conn)
+        .await
+    {
+        Ok(Some(s)) => s,
+        Ok(None) => return HttpResponse::NotFound().body("Order not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    match sale.buyer_id {
+        Some(id) if id == user.id => return HttpResponse::Ok().json(ClaimResponse { claimed: 0, sale_ids: vec![sale.id] }),
+        Some(_) => return HttpResponse::Conflict().body("Order already belongs to another account"),
+        None => {}
+    }
+
+    match attach_guest_sales(&data, user.id, vec![sale.id]).await {
+        Ok(res) => HttpResponse::Ok().json(res),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to claim order"),
+    }
+}
+
+pub async fn request_phone_code(
+    req: web::Json<PhoneCodeRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let conn = &data.conn;
+    if !data.sms.is_configured() {
+        return HttpResponse::ServiceUnavailable().body("Phone verification is not available");
+    }
+
+    let phone = normalize_phone(&req.phone);
+    if phone.len() < MIN_PHONE_DIGITS {
+        return HttpResponse::BadRequest().body("Invalid phone number");
+    }
+
+    let now = chrono::Utc::now().fixed_offset();
+    let last = PhoneVerifications::find()
+        .filter(phone_verifications::Column::UserId.eq(user.id))
+        .order_by_desc(phone_verifications::Column::CreatedAt)
+        .one(conn)
+        .await;
+    match last {
+        Ok(Some(v)) if now - v.created_at < chrono::Duration::seconds(CODE_RESEND_SECONDS) => {
+            return HttpResponse::TooManyRequests().body("Wait a minute before requesting another code");
+        }
+        Ok(_) => {}
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    }
+
+    let code = format!("{:06}", uuid::Uuid::new_v4().as_u128() % 1_000_000);
+    let verification = phone_verifications::ActiveModel {
+        user_id: Set(user.id),
+        phone: Set(phone.clone()),
+        code_hash: Set(code_hash(user.id, &phone, &code)),
+        attempts: Set(0),
+        expires_at: Set(now + chrono::Duration::minutes(CODE_TTL_MINUTES)),
+        ..Default::default()
+    };
+
+    let verification = match verification.insert(conn).await {
+        Ok(v) => v,
+        Err(_) => return HttpResponse::InternalServerError().body("Failed to create verification code"),
+    };
+
+    let message = format!(
+        "Kermesse Solidaria: tu código de verificación es {}. Vence en {} minutos.",
+        code, CODE_TTL_MINUTES
+    );
+    match data.sms.send(&phone, &message).await {
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({"status": "sent", "expires_in_minutes": CODE_TTL_MINUTES})),
+        Err(e) => {
+            // Never log the message itself, it holds the code
+            log::warn!("Failed to send verification SMS: {}", e);
+            // The code never arrived, so it must not block asking for another one
+            let _ = verification.delete(conn).await;
+            HttpResponse::BadGateway().body("Failed to send verification code")
+        }
+    }
+}
+
+/// Checks the SMS code and claims every guest order placed with that phone.
+pub async fn claim_by_phone(
+    req: web::Json<ClaimByPhoneRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let conn = &data.conn;
+    let phone = normalize_phone(&req.phone);
+    let now = chrono::Utc::now().fixed_offset();
+
+    let verification = match PhoneVerifications::find()
+        .filter(phone_verifications::Column::UserId.eq(user.id))
+        .filter(phone_verifications::Column::Phone.eq(phone.clone()))
+        .filter(phone_verifications::Column::VerifiedAt.is_null())
+        .filter(phone_verifications::Column::ExpiresAt.gt(now))
+        .order_by_desc(phone_verifications::Column::CreatedAt)
+        .one(conn)
+        .await
+    {
+        Ok(Some(v)) => v,
+        Ok(None) => return HttpResponse::BadRequest().body("No pending code for this phone, request a new one"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if verification.attempts >= MAX_CODE_ATTEMPTS {
+        return HttpResponse::TooManyRequests().body("Too many attempts, request a new code");
+    }
+
+    if verification.code_hash != code_hash(user.id, &phone, &req.code) {
+        let _ = PhoneVerifications::update_many()
+            .col_expr(phone_verifications::Column::Attempts, Expr::col(phone_verifications::Column::Attempts).add(1))
+            .filter(phone_verifications::Column::Id.eq(verification.id))
+            .exec(conn)
+            .await;
+        return HttpResponse::BadRequest().body("Invalid code");
+    }
+
+    let mut verification: phone_verifications::ActiveModel = verification.into();
+    verification.verified_at = Set(Some(now));
+    if verification.update(conn).await.is_err() {
+        return HttpResponse::InternalServerError().body("Failed to verify phone");
+    }
+
+    // Narrow down in SQL by the last digits, then compare the full numbers
+    let suffix: String = phone.chars().skip(phone.len().saturating_sub(MIN_PHONE_DIGITS)).collect();
+    let candidates = match Sales::find()
+        .filter(sales::Column::BuyerId.is_null())
+        .filter(sales::Column::ContactPhone.contains(suffix.as_str()))
+        .all(conn)
+        .await
+    {
+        Ok(s) => s,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let sale_ids = candidates
+        .into_iter()
+        .filter(|s| s.contact_phone.as_deref().map(|p| same_phone(p, &phone)).unwrap_or(false))
+        .map(|s| s.id)
+        .collect();
+
+    match attach_guest_sales(&data, user.id, sale_ids).await {
+        Ok(res) => HttpResponse::Ok().json(res),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to claim orders"),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/my-orders/claim")
+            .route(web::post().to(claim_by_token)),
+    )
+    .service(
+        web::resource("/my-orders/claim/phone/code")
+            .route(web::post().to(request_phone_code)),
+    )
+    .service(
+        web::resource("/my-orders/claim/phone")
+            .route(web::post().to(claim_by_phone)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_phone_ignores_formatting() {
+        assert!(same_phone_in("+591 712-34567", "59171234567", "591"));
+        assert!(same_phone_in("712 34 567", "71234567", "591"));
+    }
+
+    #[test]
+    fn same_phone_tolerates_a_missing_country_code() {
+        assert!(same_phone_in("+591 71234567", "71234567", "591"));
+        assert!(same_phone_in("71234567", "59171234567", "591"));
+        assert!(same_phone_in("0059171234567", "71234567", "591"));
+        assert!(same_phone_in("+54 9 1123456789", "91123456789", "54"));
+    }
+
+    #[test]
+    fn same_phone_rejects_an_arbitrary_prefix() {
+        assert!(!same_phone_in("71234567", "171234567", "591"));
+        assert!(!same_phone_in("71234567", "4471234567", "591"));
+        assert!(!same_phone_in("+54 71234567", "71234567", "591"));
+    }
+
+    #[test]
+    fn same_phone_rejects_partial_matches() {
+        // Same last digits but a different subscriber number
+        assert!(!same_phone_in("71234567", "34567", "591"));
+        assert!(!same_phone_in("71234567", "6671234567999", "591"));
+        assert!(!same_phone_in("71234567", "71234568", "591"));
+        assert!(!same_phone_in("1234", "1234", "591"));
+        assert!(!same_phone_in("", "", "591"));
+    }
+}
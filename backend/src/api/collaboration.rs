@@ -1,8 +1,9 @@
 use actix_web::{web, HttpResponse, Responder};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QuerySelect, Set,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::entity::{collaborators, ingredient_donations, ingredients, kermesses, users, prelude::*};
 use crate::state::AppState;
@@ -10,6 +11,24 @@ use crate::utils::auth::AuthenticatedUser;
 
 // ===== Collaborator Requests =====
 
+/// Role of the user if they are an ACCEPTED collaborator of the kermesse.
+pub async fn collaborator_role<C: ConnectionTrait>(conn: &C, kermesse_id: i32, user_id: i32) -> Option<String> {
+    Collaborators::find()
+        .filter(collaborators::Column::KermesseId.eq(kermesse_id))
+        .filter(collaborators::Column::UserId.eq(user_id))
+        .filter(collaborators::Column::Status.eq("ACCEPTED"))
+        .one(conn)
+        .await
+        .ok()
+        .flatten()
+        .map(|c| c.role)
+}
+
+/// Organizer or any accepted collaborator of the kermesse.
+pub async fn is_staff<C: ConnectionTrait>(conn: &C, kermesse: &kermesses::Model, user_id: i32) -> bool {
+    kermesse.organizer_id == user_id || collaborator_role(conn, kermesse.id, user_id).await.is_some()
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct RequestCollaborationRequest {
     pub proposed_role: String, // "KITCHEN", "SELLER", "DELIVERY", "INGREDIENT_GETTER"
@@ -173,47 +192,58 @@ pub struct IngredientWithProgress {
     pub quantity_donated: rust_decimal::Decimal,
 }
 
-pub async fn get_ingredients_with_progress(
-    path: web::Path<i32>,
-    data: web::Data<AppState>,
-) -> impl Responder {
-    let kermesse_id = path.into_inner();
-    let conn = &data.conn;
-
-    let ingredients_list = match Ingredients::find()
+/// Quantity donated so far for each ingredient of a kermesse.
+pub async fn donated_per_ingredient<C: ConnectionTrait>(
+    conn: &C,
+    kermesse_id: i32,
+) -> Result<HashMap<i32, rust_decimal::Decimal>, DbErr> {
+    Ok(IngredientDonations::find()
+        .inner_join(Ingredients)
         .filter(ingredients::Column::KermesseId.eq(kermesse_id))
+        .select_only()
+        .column(ingredient_donations::Column::IngredientId)
+        .column_as(ingredient_donations::Column::QuantityDonated.sum(), "donated")
+        .group_by(ingredient_donations::Column::IngredientId)
+        .into_tuple::<(i32, rust_decimal::Decimal)>()
         .all(conn)
-        .await
-    {
-        Ok(i) => i,
-        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
-    };
+        .await?
+        .into_iter()
+        .collect())
+}
 
-    let mut result = Vec::new();
-
-    for ingredient in ingredients_list {
-        // Get sum of donations for this ingredient
-        let donations_sum = match IngredientDonations::find()
-            .filter(ingredient_donations::Column::IngredientId.eq(ingredient.id))
-            .all(conn)
-            .await
-        {
-            Ok(donations) => donations
-                .iter()
-                .fold(rust_decimal::Decimal::ZERO, |acc, d| acc + d.quantity_donated),
-            Err(_) => rust_decimal::Decimal::ZERO,
-        };
-
-        result.push(IngredientWithProgress {
+/// Ingredients of a kermesse with their donated quantity, in two queries.
+pub async fn ingredients_with_progress<C: ConnectionTrait>(
+    conn: &C,
+    kermesse_id: i32,
+) -> Result<Vec<IngredientWithProgress>, DbErr> {
+    let donated = donated_per_ingredient(conn, kermesse_id).await?;
+
+    Ok(Ingredients::find()
+        .filter(ingredients::Column::KermesseId.eq(kermesse_id))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|ingredient| IngredientWithProgress {
+            quantity_donated: donated.get(&ingredient.id).copied().unwrap_or(rust_decimal::Decimal::ZERO),
             id: ingredient.id,
             name: ingredient.name,
             quantity_needed: ingredient.quantity_needed,
             unit: ingredient.unit,
-            quantity_donated: donations_sum,
-        });
-    }
+        })
+        .collect())
+}
 
-    HttpResponse::Ok().json(result)
+pub async fn get_ingredients_with_progress(
+    path: web::Path<i32>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    match ingredients_with_progress(conn, kermesse_id).await {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
 }
 
 #[derive(Deserialize)]
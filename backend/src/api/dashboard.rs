@@ -1,23 +1,42 @@
 use actix_web::{web, HttpResponse, Responder};
 use sea_orm::{
-    ColumnTrait, EntityTrait, QueryFilter,
+    sea_query::Expr, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, JoinType, Order, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait,
 };
 use serde::Serialize;
+use std::collections::HashMap;
 
-use crate::entity::{ingredient_donations, ingredients, kermesses, sales, prelude::*};
+use crate::entity::{dishes, ingredients, kermesses, refunds, sale_items, sales, users, prelude::*};
+use crate::api::collaboration::donated_per_ingredient;
+use crate::api::donations::confirmed_by_kermesse;
+use crate::api::refunds::refunded_by_kermesse;
+use crate::api::sales::{COLLECTED_STATUSES, KEPT_STATUSES};
 use crate::state::AppState;
 use crate::utils::auth::AuthenticatedUser;
+use crate::utils::time;
 
 #[derive(Serialize)]
 pub struct DashboardStats {
     pub financial_goal: Option<rust_decimal::Decimal>,
     pub total_raised: rust_decimal::Decimal,
+    pub total_refunded: rust_decimal::Decimal,
+    pub sales_revenue: rust_decimal::Decimal,
+    pub donations_total: rust_decimal::Decimal,
     pub progress_percentage: f64,
     pub total_orders: i64,
     pub pending_orders: i64,
     pub paid_orders: i64,
     pub delivered_orders: i64,
     pub ingredient_coverage_percentage: f64,
+    pub seller_totals: Vec<SellerTotal>,
+}
+
+#[derive(Serialize)]
+pub struct SellerTotal {
+    pub seller_id: i32,
+    pub seller_name: String,
+    pub sales_count: i64,
+    pub total: rust_decimal::Decimal,
 }
 
 pub async fn get_dashboard_stats(
@@ -39,26 +58,143 @@ pub async fn get_dashboard_stats(
         return HttpResponse::Forbidden().body("Only organizer can view dashboard");
     }
 
-    // Get all sales for this kermesse
-    let sales_list = match Sales::find()
+    match dashboard_stats(conn, &kermesse).await {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+/// Sales, refund and donation sums of a kermesse, all aggregated by the database.
+#[derive(Clone, Default)]
+pub struct KermesseTotals {
+    /// Amount charged on collected sales, before refunds.
+    pub gross_collected: rust_decimal::Decimal,
+    /// Part of `gross_collected` that buyers added as a donation on top of their order,
+    /// leaving out fully refunded sales.
+    pub extra_donations: rust_decimal::Decimal,
+    pub refunded: rust_decimal::Decimal,
+    /// Confirmed standalone donations.
+    pub donations: rust_decimal::Decimal,
+    pub orders_by_status: HashMap<String, i64>,
+}
+
+impl KermesseTotals {
+    pub fn total_orders(&self) -> i64 {
+        self.orders_by_status.values().sum()
+    }
+
+    pub fn orders(&self, status: &str) -> i64 {
+        self.orders_by_status.get(status).copied().unwrap_or(0)
+    }
+
+    /// Collected orders that were not fully refunded.
+    pub fn kept_orders(&self) -> i64 {
+        KEPT_STATUSES.iter().map(|s| self.orders(s)).sum()
+    }
+
+    /// Adds the sales of one status, as aggregated by the database.
+    fn add_sales(&mut self, status: String, orders: i64, total: rust_decimal::Decimal, extra_donation: rust_decimal::Decimal) {
+        if COLLECTED_STATUSES.contains(&status.as_str()) {
+            self.gross_collected += total;
+        }
+        // A full refund returns the donation too, so it is no longer part of what was raised
+        if KEPT_STATUSES.contains(&status.as_str()) {
+            self.extra_donations += extra_donation;
+        }
+        self.orders_by_status.insert(status, orders);
+    }
+
+    /// Sales net of refunds plus standalone donations.
+    pub fn total_raised(&self) -> rust_decimal::Decimal {
+        self.gross_collected - self.refunded + self.donations
+    }
+}
+
+/// Totals for several kermesses in a fixed number of queries, whatever the number of
+/// kermesses or sales. Kermesses without any activity are absent from the map.
+pub async fn kermesse_totals<C: ConnectionTrait>(
+    conn: &C,
+    kermesse_ids: &[i32],
+) -> Result<HashMap<i32, KermesseTotals>, DbErr> {
+    let mut totals: HashMap<i32, KermesseTotals> = HashMap::new();
+    if kermesse_ids.is_empty() {
+        return Ok(totals);
+    }
+
+    let per_status = Sales::find()
+        .filter(sales::Column::KermesseId.is_in(kermesse_ids.iter().copied()))
+        .select_only()
+        .column(sales::Column::KermesseId)
+        .column(sales::Column::Status)
+        .column_as(sales::Column::Id.count(), "orders")
+        .column_as(sales::Column::TotalAmount.sum(), "total")
+        .column_as(sales::Column::ExtraDonation.sum(), "extra_donation")
+        .group_by(sales::Column::KermesseId)
+        .group_by(sales::Column::Status)
+        .into_tuple::<(i32, String, i64, rust_decimal::Decimal, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?;
+    for (kermesse_id, status, orders, total, extra_donation) in per_status {
+        totals.entry(kermesse_id).or_default().add_sales(status, orders, total, extra_donation);
+    }
+
+    for (kermesse_id, refunded) in refunded_by_kermesse(conn, kermesse_ids).await? {
+        totals.entry(kermesse_id).or_default().refunded = refunded;
+    }
+    for (kermesse_id, donations) in confirmed_by_kermesse(conn, kermesse_ids).await? {
+        totals.entry(kermesse_id).or_default().donations = donations;
+    }
+
+    Ok(totals)
+}
+
+/// Collected sales per seller, best seller first. Online orders are credited to the
+/// organizer, POS sales to whoever rang them up.
+async fn seller_totals<C: ConnectionTrait>(conn: &C, kermesse_id: i32) -> Result<Vec<SellerTotal>, DbErr> {
+    let per_seller = Sales::find()
         .filter(sales::Column::KermesseId.eq(kermesse_id))
+        .filter(sales::Column::Status.is_in(COLLECTED_STATUSES))
+        .select_only()
+        .column(sales::Column::SellerId)
+        .column_as(sales::Column::Id.count(), "orders")
+        .column_as(sales::Column::TotalAmount.sum(), "revenue")
+        .group_by(sales::Column::SellerId)
+        .into_tuple::<(i32, i64, rust_decimal::Decimal)>()
         .all(conn)
-        .await
-    {
-        Ok(s) => s,
-        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
-    };
+        .await?;
+    let seller_names: HashMap<i32, String> = Users::find()
+        .filter(users::Column::Id.is_in(per_seller.iter().map(|s| s.0)))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u.full_name))
+        .collect();
+    let mut sellers: Vec<SellerTotal> = per_seller
+        .into_iter()
+        .map(|(seller_id, sales_count, total)| SellerTotal {
+            seller_id,
+            seller_name: seller_names.get(&seller_id).cloned().unwrap_or_default(),
+            sales_count,
+            total,
+        })
+        .collect();
+    sellers.sort_by_key(|s| std::cmp::Reverse(s.total));
+    Ok(sellers)
+}
 
-    // Calculate financial stats
-    let total_raised = sales_list
-        .iter()
-        .filter(|s| s.status == "PAID" || s.status == "DELIVERED")
-        .fold(rust_decimal::Decimal::ZERO, |acc, s| acc + s.total_amount);
+/// Fundraising totals for a kermesse, shared by the dashboard and the closing report.
+pub async fn dashboard_stats<C: ConnectionTrait>(conn: &C, kermesse: &kermesses::Model) -> Result<DashboardStats, DbErr> {
+    let kermesse_id = kermesse.id;
 
-    let total_orders = sales_list.len() as i64;
-    let pending_orders = sales_list.iter().filter(|s| s.status == "PENDING").count() as i64;
-    let paid_orders = sales_list.iter().filter(|s| s.status == "PAID").count() as i64;
-    let delivered_orders = sales_list.iter().filter(|s| s.status == "DELIVERED").count() as i64;
+    let totals = kermesse_totals(conn, &[kermesse_id])
+        .await?
+        .remove(&kermesse_id)
+        .unwrap_or_default();
+
+    let sales_net = totals.gross_collected - totals.refunded;
+    let sales_revenue = sales_net - totals.extra_donations;
+    let donations_total = totals.extra_donations + totals.donations;
+    let total_raised = totals.total_raised();
 
     let progress_percentage = if let Some(goal) = kermesse.financial_goal {
         if goal > rust_decimal::Decimal::ZERO {
@@ -72,34 +208,19 @@ pub async fn get_dashboard_stats(
     };
 
     // Calculate ingredient coverage
-    let ingredients_list = match Ingredients::find()
+    let total_needed = Ingredients::find()
         .filter(ingredients::Column::KermesseId.eq(kermesse_id))
-        .all(conn)
-        .await
-    {
-        Ok(i) => i,
-        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
-    };
-
-    let mut total_needed = rust_decimal::Decimal::ZERO;
-    let mut total_donated = rust_decimal::Decimal::ZERO;
-
-    for ingredient in ingredients_list {
-        total_needed += ingredient.quantity_needed;
-
-        let donations_sum = match IngredientDonations::find()
-            .filter(ingredient_donations::Column::IngredientId.eq(ingredient.id))
-            .all(conn)
-            .await
-        {
-            Ok(donations) => donations
-                .iter()
-                .fold(rust_decimal::Decimal::ZERO, |acc, d| acc + d.quantity_donated),
-            Err(_) => rust_decimal::Decimal::ZERO,
-        };
-
-        total_donated += donations_sum;
-    }
+        .select_only()
+        .column_as(ingredients::Column::QuantityNeeded.sum(), "needed")
+        .into_tuple::<Option<rust_decimal::Decimal>>()
+        .one(conn)
+        .await?
+        .flatten()
+        .unwrap_or(rust_decimal::Decimal::ZERO);
+    let total_donated = donated_per_ingredient(conn, kermesse_id)
+        .await?
+        .values()
+        .fold(rust_decimal::Decimal::ZERO, |acc, d| acc + d);
 
     let ingredient_coverage_percentage = if total_needed > rust_decimal::Decimal::ZERO {
         let ratio: f64 = (total_donated / total_needed).try_into().unwrap_or(0.0);
@@ -108,15 +229,232 @@ pub async fn get_dashboard_stats(
         100.0 // If no ingredients needed, consider it 100% covered
     };
 
-    HttpResponse::Ok().json(DashboardStats {
+    Ok(DashboardStats {
         financial_goal: kermesse.financial_goal,
         total_raised,
+        total_refunded: totals.refunded,
+        sales_revenue,
+        donations_total,
         progress_percentage,
-        total_orders,
-        pending_orders,
-        paid_orders,
-        delivered_orders,
+        total_orders: totals.total_orders(),
+        pending_orders: totals.orders("PENDING"),
+        paid_orders: totals.orders("PAID"),
+        delivered_orders: totals.orders("DELIVERED"),
         ingredient_coverage_percentage,
+        seller_totals: seller_totals(conn, kermesse_id).await?,
+    })
+}
+
+#[derive(Serialize)]
+pub struct SalesAnalytics {
+    pub dishes: Vec<DishAnalytics>,
+    pub hourly: Vec<HourlySales>,
+    pub payment_methods: Vec<MethodBreakdown>,
+    pub delivery_methods: Vec<MethodBreakdown>,
+    pub sellers: Vec<SellerTotal>,
+}
+
+#[derive(Serialize)]
+pub struct DishAnalytics {
+    pub dish_id: i32,
+    pub name: String,
+    /// Units and revenue net of refunds.
+    pub units_sold: i64,
+    pub revenue: rust_decimal::Decimal,
+    pub stock_remaining: i32,
+    pub sell_through_percentage: f64,
+}
+
+#[derive(Serialize)]
+pub struct HourlySales {
+    pub hour: chrono::DateTime<chrono::FixedOffset>,
+    pub orders: i64,
+    pub revenue: rust_decimal::Decimal,
+}
+
+#[derive(Serialize)]
+pub struct MethodBreakdown {
+    pub method: String,
+    /// Orders not fully refunded and revenue net of refunds.
+    pub orders: i64,
+    pub revenue: rust_decimal::Decimal,
+}
+
+pub async fn get_sales_analytics(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can view analytics");
+    }
+
+    match sales_analytics(conn, kermesse_id).await {
+        Ok(analytics) => HttpResponse::Ok().json(analytics),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+/// Collected sales grouped by `method` (payment or delivery), net of refunds. Fully
+/// refunded orders are not counted, but their money is, so refunds cancel it out.
+async fn method_breakdown<C: ConnectionTrait>(
+    conn: &C,
+    kermesse_id: i32,
+    method: sales::Column,
+) -> Result<Vec<MethodBreakdown>, DbErr> {
+    let per_status = Sales::find()
+        .filter(sales::Column::KermesseId.eq(kermesse_id))
+        .filter(sales::Column::Status.is_in(COLLECTED_STATUSES))
+        .select_only()
+        .column(method)
+        .column(sales::Column::Status)
+        .column_as(sales::Column::Id.count(), "orders")
+        .column_as(sales::Column::TotalAmount.sum(), "revenue")
+        .group_by(method)
+        .group_by(sales::Column::Status)
+        .into_tuple::<(String, String, i64, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?;
+    let refunded: HashMap<String, rust_decimal::Decimal> = Refunds::find()
+        .inner_join(Sales)
+        .filter(sales::Column::KermesseId.eq(kermesse_id))
+        .select_only()
+        .column(method)
+        .column_as(refunds::Column::Amount.sum(), "refunded")
+        .group_by(method)
+        .into_tuple::<(String, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut per_method: HashMap<String, (i64, rust_decimal::Decimal)> = HashMap::new();
+    for (name, status, orders, revenue) in per_status {
+        let entry = per_method.entry(name).or_default();
+        if KEPT_STATUSES.contains(&status.as_str()) {
+            entry.0 += orders;
+        }
+        entry.1 += revenue;
+    }
+    let mut breakdown: Vec<MethodBreakdown> = per_method
+        .into_iter()
+        .map(|(name, (orders, gross))| MethodBreakdown {
+            revenue: gross - refunded.get(&name).copied().unwrap_or_default(),
+            method: name,
+            orders,
+        })
+        .collect();
+    breakdown.sort_by_key(|m| std::cmp::Reverse(m.revenue));
+    Ok(breakdown)
+}
+
+/// Breakdowns of collected sales. Every figure is aggregated by the database, so the
+/// cost does not grow with the number of orders loaded into memory.
+pub async fn sales_analytics<C: ConnectionTrait>(conn: &C, kermesse_id: i32) -> Result<SalesAnalytics, DbErr> {
+    let collected = || {
+        Sales::find()
+            .filter(sales::Column::KermesseId.eq(kermesse_id))
+            .filter(sales::Column::Status.is_in(COLLECTED_STATUSES))
+    };
+
+    let per_dish: HashMap<i32, (i64, rust_decimal::Decimal)> = SaleItems::find()
+        .inner_join(Sales)
+        .filter(sales::Column::KermesseId.eq(kermesse_id))
+        .filter(sales::Column::Status.is_in(KEPT_STATUSES))
+        .select_only()
+        .column(sale_items::Column::DishId)
+        .column_as(sale_items::Column::Quantity.sum(), "units")
+        .column_as(sale_items::Column::Subtotal.sum(), "revenue")
+        .group_by(sale_items::Column::DishId)
+        .into_tuple::<(i32, i64, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|(dish_id, units, revenue)| (dish_id, (units, revenue)))
+        .collect();
+
+    // Units given back on partial refunds, which may have been restocked
+    let refunded_per_dish: HashMap<i32, (i64, rust_decimal::Decimal)> = Refunds::find()
+        .inner_join(SaleItems)
+        .join(JoinType::InnerJoin, sale_items::Relation::Sales.def())
+        .filter(sales::Column::KermesseId.eq(kermesse_id))
+        .filter(sales::Column::Status.is_in(KEPT_STATUSES))
+        .select_only()
+        .column(sale_items::Column::DishId)
+        .column_as(refunds::Column::Quantity.sum(), "units")
+        .column_as(refunds::Column::Amount.sum(), "amount")
+        .group_by(sale_items::Column::DishId)
+        .into_tuple::<(i32, Option<i64>, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|(dish_id, units, amount)| (dish_id, (units.unwrap_or(0), amount)))
+        .collect();
+
+    // Sell-through = units sold / (units sold + units still on hand). Refunded units are not
+    // sold, and counting them would add restocked units to both sides.
+    let mut dish_rows: Vec<DishAnalytics> = Dishes::find()
+        .filter(dishes::Column::KermesseId.eq(kermesse_id))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|dish| {
+            let (units, gross) = per_dish.get(&dish.id).copied().unwrap_or((0, rust_decimal::Decimal::ZERO));
+            let (refunded_units, refunded) =
+                refunded_per_dish.get(&dish.id).copied().unwrap_or((0, rust_decimal::Decimal::ZERO));
+            let (units_sold, revenue) = (units - refunded_units, gross - refunded);
+            let initial = units_sold + i64::from(dish.quantity_available.max(0));
+            let sell_through_percentage = if initial > 0 {
+                units_sold as f64 / initial as f64 * 100.0
+            } else {
+                0.0
+            };
+            DishAnalytics {
+                dish_id: dish.id,
+                name: dish.name,
+                units_sold,
+                revenue,
+                stock_remaining: dish.quantity_available,
+                sell_through_percentage,
+            }
+        })
+        .collect();
+    dish_rows.sort_by_key(|d| std::cmp::Reverse(d.revenue));
+
+    let hour = Expr::cust("date_trunc('hour', \"sales\".\"created_at\")");
+    let hourly = collected()
+        .select_only()
+        .column_as(hour.clone(), "hour")
+        .column_as(sales::Column::Id.count(), "orders")
+        .column_as(sales::Column::TotalAmount.sum(), "revenue")
+        .group_by(hour.clone())
+        .order_by(hour, Order::Asc)
+        .into_tuple::<(sea_orm::prelude::DateTimeWithTimeZone, i64, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|(hour, orders, revenue)| HourlySales {
+            hour: hour.with_timezone(&time::kermesse_offset()),
+            orders,
+            revenue,
+        })
+        .collect();
+
+    Ok(SalesAnalytics {
+        dishes: dish_rows,
+        hourly,
+        payment_methods: method_breakdown(conn, kermesse_id, sales::Column::PaymentMethod).await?,
+        delivery_methods: method_breakdown(conn, kermesse_id, sales::Column::DeliveryMethod).await?,
+        sellers: seller_totals(conn, kermesse_id).await?,
     })
 }
 
@@ -124,5 +462,146 @@ pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::resource("/kermesses/{id}/dashboard/stats")
             .route(web::get().to(get_dashboard_stats)),
+    )
+    .service(
+        web::resource("/kermesses/{id}/dashboard/analytics")
+            .route(web::get().to(get_sales_analytics)),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::collaboration::ingredients_with_progress;
+    use sea_orm::{ConnectOptions, Database, DatabaseTransaction, Statement, TransactionTrait};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    async fn insert_returning_id(txn: &DatabaseTransaction, sql: String) -> i32 {
+        txn.query_one(Statement::from_string(txn.get_database_backend(), sql))
+            .await
+            .unwrap()
+            .unwrap()
+            .try_get::<i32>("", "id")
+            .unwrap()
+    }
+
+    /// A kermesse with `sales` orders (a third still pending), one refund every 50 sales,
+    /// as many standalone donations as sales and `ingredients` ingredients with ten donations each.
+    async fn seed_kermesse(txn: &DatabaseTransaction, organizer_id: i32, sales: i32, ingredients: i32) -> kermesses::Model {
+        let slug = format!("bench-{}", uuid::Uuid::new_v4());
+        let kermesse_id = insert_returning_id(txn, format!(
+            "INSERT INTO kermesses (name, slug, description, beneficiary_name, beneficiary_reason, event_date, organizer_id, financial_goal) \
+             VALUES ('Bench', '{slug}', '', 'Bench', 'Bench', CURRENT_DATE, {organizer_id}, 1000) RETURNING id"
+        ))
+        .await;
+        let dish_id = insert_returning_id(txn, format!(
+            "INSERT INTO dishes (kermesse_id, name, description, price, quantity_available) \
+             VALUES ({kermesse_id}, 'Plato', '', 10, 0) RETURNING id"
+        ))
+        .await;
+        txn.execute_unprepared(&format!(
+            "INSERT INTO sales (kermesse_id, seller_id, customer_name, total_amount, extra_donation, status) \
+               SELECT {kermesse_id}, {organizer_id}, 'Cliente ' || g, 21, 1, CASE WHEN g % 3 = 0 THEN 'PENDING' ELSE 'PAID' END \
+               FROM generate_series(1, {sales}) g;
+             INSERT INTO sale_items (sale_id, dish_id, quantity, subtotal) \
+               SELECT id, {dish_id}, 2, 20 FROM sales WHERE kermesse_id = {kermesse_id};
+             INSERT INTO refunds (sale_id, amount, reason, refund_method, restocked, created_by) \
+               SELECT id, 5, 'bench', 'CASH', false, {organizer_id} FROM sales \
+               WHERE kermesse_id = {kermesse_id} AND status = 'PAID' AND id % 50 = 0;
+             INSERT INTO donations (kermesse_id, amount, donor_name, payment_method, status) \
+               SELECT {kermesse_id}, 3, 'Donante', 'QR', 'CONFIRMED' FROM generate_series(1, {sales});
+             INSERT INTO ingredients (kermesse_id, name, quantity_needed, unit) \
+               SELECT {kermesse_id}, 'Insumo ' || g, 100, 'kg' FROM generate_series(1, {ingredients}) g;
+             INSERT INTO ingredient_donations (ingredient_id, user_id, quantity_donated) \
+               SELECT i.id, {organizer_id}, 1 FROM ingredients i, generate_series(1, 10) \
+               WHERE i.kermesse_id = {kermesse_id};"
+        ))
+        .await
+        .unwrap();
+
+        Kermesses::find_by_id(kermesse_id).one(txn).await.unwrap().unwrap()
+    }
+
+    #[test]
+    fn refunded_sales_keep_no_extra_donation() {
+        let mut totals = KermesseTotals::default();
+        // A paid order of 21 with 1 donated, and a fully refunded one of 32 with 2 donated
+        totals.add_sales("PAID".to_string(), 1, rust_decimal::Decimal::from(21), rust_decimal::Decimal::ONE);
+        totals.add_sales("REFUNDED".to_string(), 1, rust_decimal::Decimal::from(32), rust_decimal::Decimal::TWO);
+        totals.refunded = rust_decimal::Decimal::from(32);
+
+        let sales_revenue = totals.gross_collected - totals.refunded - totals.extra_donations;
+        assert_eq!(totals.extra_donations, rust_decimal::Decimal::ONE);
+        assert_eq!(sales_revenue, rust_decimal::Decimal::from(20));
+        assert_eq!(totals.total_raised(), rust_decimal::Decimal::from(21));
+        assert_eq!(totals.kept_orders(), 1);
+    }
+
+    /// Needs a migrated Postgres database in `TEST_DATABASE_URL`; everything it writes is rolled back.
+    #[actix_rt::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn aggregates_issue_constant_query_count() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must be set");
+        let mut options = ConnectOptions::new(url);
+        options.sqlx_logging(false);
+        let mut db = Database::connect(options).await.unwrap();
+        let queries = Arc::new(AtomicUsize::new(0));
+        let counter = queries.clone();
+        db.set_metric_callback(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let txn = db.begin().await.unwrap();
+        let organizer_id = insert_returning_id(&txn, format!(
+            "INSERT INTO users (username, email, full_name, phone, password_hash) \
+             VALUES ('{0}', '{0}@example.com', 'Bench', '0', 'x') RETURNING id",
+            uuid::Uuid::new_v4()
+        ))
+        .await;
+        let small = seed_kermesse(&txn, organizer_id, 10, 2).await;
+        let large = seed_kermesse(&txn, organizer_id, 5000, 40).await;
+
+        let mut per_kermesse = Vec::new();
+        for kermesse in [&small, &large] {
+            let before = queries.load(Ordering::SeqCst);
+            let stats = dashboard_stats(&txn, kermesse).await.unwrap();
+            let dashboard = queries.load(Ordering::SeqCst) - before;
+
+            let before = queries.load(Ordering::SeqCst);
+            let ingredients = ingredients_with_progress(&txn, kermesse.id).await.unwrap();
+            let progress = queries.load(Ordering::SeqCst) - before;
+
+            assert!(ingredients.iter().all(|i| i.quantity_donated == rust_decimal::Decimal::from(10)));
+            assert_eq!(stats.ingredient_coverage_percentage, 10.0);
+            per_kermesse.push((stats, dashboard, progress));
+        }
+
+        let (small_stats, small_dashboard, small_progress) = &per_kermesse[0];
+        let (large_stats, large_dashboard, large_progress) = &per_kermesse[1];
+        assert_eq!(small_dashboard, large_dashboard);
+        assert_eq!(small_progress, large_progress);
+
+        // 3334 paid orders of 21 (1 of it an extra donation) and 5000 standalone donations of 3
+        assert_eq!(large_stats.total_orders, 5000);
+        assert_eq!(large_stats.pending_orders, 1666);
+        assert_eq!(large_stats.paid_orders, 3334);
+        assert_eq!(large_stats.donations_total, rust_decimal::Decimal::from(3334 + 15000));
+        assert_eq!(large_stats.sales_revenue + large_stats.total_refunded, rust_decimal::Decimal::from(3334 * 20));
+        assert!(large_stats.total_refunded > rust_decimal::Decimal::ZERO);
+        assert_eq!(small_stats.total_orders, 10);
+        assert_eq!(ingredients_with_progress(&txn, large.id).await.unwrap().len(), 40);
+
+        // `my_kermesses` covers any number of kermesses with the same queries as a single one
+        let before = queries.load(Ordering::SeqCst);
+        kermesse_totals(&txn, &[small.id]).await.unwrap();
+        let one = queries.load(Ordering::SeqCst) - before;
+        let before = queries.load(Ordering::SeqCst);
+        let totals = kermesse_totals(&txn, &[small.id, large.id]).await.unwrap();
+        let both = queries.load(Ordering::SeqCst) - before;
+        assert_eq!(one, both);
+        assert_eq!(totals[&large.id].total_raised(), large_stats.total_raised);
+
+        txn.rollback().await.unwrap();
+    }
+}
@@ -0,0 +1,368 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::api::collaboration::{collaborator_role, is_staff};
+use crate::api::sales::{progressed_status, record_status};
+use crate::entity::{collaborators, dishes, kermesses, sale_items, sales, prelude::*};
+use crate::events::{EventKind, KermesseEvent};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+
+#[derive(Deserialize)]
+pub struct AssignDeliveryRequest {
+    pub delivery_person_id: Option<i32>, // None to unassign
+}
+
+#[derive(Deserialize)]
+pub struct UpdateDeliveryStatusRequest {
+    pub status: String, // "OUT_FOR_DELIVERY" or "DELIVERED"
+}
+
+#[derive(Deserialize)]
+pub struct RouteSheetQuery {
+    pub delivery_person_id: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct DeliveryPerson {
+    pub user_id: i32,
+    pub full_name: String,
+    pub phone: String,
+}
+
+#[derive(Serialize)]
+pub struct DeliveryStop {
+    pub sale_id: i32,
+    pub kermesse_id: i32,
+    pub kermesse_name: String,
+    pub customer_name: String,
+    pub contact_phone: Option<String>,
+    pub delivery_address: Option<String>,
+    pub delivery_neighborhood: Option<String>,
+    pub delivery_city: Option<String>,
+    pub delivery_person_id: Option<i32>,
+    pub status: String,
+    pub payment_method: String,
+    pub total_amount: rust_decimal::Decimal,
+    pub out_for_delivery_at: Option<String>,
+    pub delivered_at: Option<String>,
+    pub items: Vec<String>, // "2 x Sopa"
+}
+
+#[derive(Serialize)]
+pub struct RouteSheetGroup {
+    pub city: String,
+    pub neighborhood: String,
+    pub stops: Vec<DeliveryStop>,
+}
+
+/// Builds the stop list for the given DELIVERY sales, loading their items in one query.
+async fn build_stops(
+    conn: &sea_orm::DatabaseConnection,
+    sales_list: Vec<(sales::Model, Option<kermesses::Model>)>,
+) -> Result<Vec<DeliveryStop>, sea_orm::DbErr> {
+    let items = SaleItems::find()
+        .filter(sale_items::Column::SaleId.is_in(sales_list.iter().map(|(s, _)| s.id)))
+        .find_also_related(dishes::Entity)
+        .all(conn)
+        .await?;
+
+    Ok(sales_list
+        .into_iter()
+        .map(|(sale, kermesse)| DeliveryStop {
+            sale_id: sale.id,
+            kermesse_id: sale.kermesse_id,
+            kermesse_name: kermesse.map(|k| k.name).unwrap_or_default(),
+            customer_name: sale.customer_name,
+            contact_phone: sale.contact_phone,
+            delivery_address: sale.delivery_address,
+            delivery_neighborhood: sale.delivery_neighborhood,
+            delivery_city: sale.delivery_city,
+            delivery_person_id: sale.delivery_person_id,
+            status: sale.status,
+            payment_method: sale.payment_method,
+            total_amount: sale.total_amount,
+            out_for_delivery_at: sale.out_for_delivery_at.map(|t| t.to_string()),
+            delivered_at: sale.delivered_at.map(|t| t.to_string()),
+            items: items
+                .iter()
+                .filter(|(item, _)| item.sale_id == sale.id)
+                .map(|(item, dish)| format!("{} x {}", item.quantity, dish.as_ref().map(|d| d.name.as_str()).unwrap_or("?")))
+                .collect(),
+        })
+        .collect())
+}
+
+pub async fn list_delivery_people(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can view delivery staff");
+    }
+
+    let people = match Collaborators::find()
+        .filter(collaborators::Column::KermesseId.eq(kermesse_id))
+        .filter(collaborators::Column::Status.eq("ACCEPTED"))
+        .filter(collaborators::Column::Role.eq("DELIVERY"))
+        .find_also_related(Users)
+        .all(conn)
+        .await
+    {
+        Ok(p) => p,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let response: Vec<DeliveryPerson> = people
+        .into_iter()
+        .filter_map(|(_, u)| u.map(|u| DeliveryPerson { user_id: u.id, full_name: u.full_name, phone: u.phone }))
+        .collect();
+
+    HttpResponse::Ok().json(response)
+}
+
+pub async fn assign_delivery(
+    path: web::Path<i32>,
+    req: web::Json<AssignDeliveryRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let sale_id = path.into_inner();
+    let conn = &data.conn;
+
+    let sale = match Sales::find_by_id(sale_id).one(conn).await {
+        Ok(Some(s)) => s,
+        Ok(None) => return HttpResponse::NotFound().body("Sale not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let kermesse = match Kermesses::find_by_id(sale.kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        _ => return HttpResponse::InternalServerError().body("Kermesse lookup error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can assign deliveries");
+    }
+
+    if sale.delivery_method != "DELIVERY" {
+        return HttpResponse::BadRequest().body("Only delivery orders can be assigned");
+    }
+
+    if let Some(person_id) = req.delivery_person_id {
+        if collaborator_role(conn, kermesse.id, person_id).await.as_deref() != Some("DELIVERY") {
+            return HttpResponse::BadRequest().body("User is not an accepted delivery collaborator");
+        }
+    }
+
+    let mut sale: sales::ActiveModel = sale.into();
+    sale.delivery_person_id = Set(req.delivery_person_id);
+
+    match sale.update(conn).await {
+        Ok(s) => HttpResponse::Ok().json(serde_json::json!({"sale_id": s.id, "delivery_person_id": s.delivery_person_id})),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to assign delivery"),
+    }
+}
+
+pub async fn list_my_deliveries(
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let conn = &data.conn;
+
+    let assigned = match Sales::find()
+        .filter(sales::Column::DeliveryPersonId.eq(user.id))
+        .filter(sales::Column::Status.is_not_in(["CANCELLED", "REFUNDED"]))
+        .find_also_related(kermesses::Entity)
+        .order_by_asc(sales::Column::CreatedAt)
+        .all(conn)
+        .await
+    {
+        Ok(s) => s,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    match build_stops(conn, assigned).await {
+        Ok(stops) => HttpResponse::Ok().json(stops),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+pub async fn update_delivery_status(
+    path: web::Path<i32>,
+    req: web::Json<UpdateDeliveryStatusRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let sale_id = path.into_inner();
+    let conn = &data.conn;
+
+    let timestamp_column = match req.status.as_str() {
+        "OUT_FOR_DELIVERY" => sales::Column::OutForDeliveryAt,
+        "DELIVERED" => sales::Column::DeliveredAt,
+        _ => return HttpResponse::BadRequest().body("Status must be OUT_FOR_DELIVERY or DELIVERED"),
+    };
+
+    let sale = match Sales::find_by_id(sale_id).one(conn).await {
+        Ok(Some(s)) => s,
+        Ok(None) => return HttpResponse::NotFound().body("Sale not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let kermesse = match Kermesses::find_by_id(sale.kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        _ => return HttpResponse::InternalServerError().body("Kermesse lookup error"),
+    };
+
+    if sale.delivery_person_id != Some(user.id) && kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only the assigned delivery person or organizer can update this delivery");
+    }
+
+    if sale.delivery_method != "DELIVERY" {
+        return HttpResponse::BadRequest().body("Sale is not a delivery order");
+    }
+
+    // Cash on delivery: the driver collects the money at the door, so an unpaid
+    // cash order can only be closed as delivered, never sent out as collected.
+    let cash_on_delivery = sale.status == "PENDING" && sale.payment_method == "CASH";
+    // A partially refunded sale keeps its status, so its progress is read from the timestamps
+    let allowed = match (req.status.as_str(), sale.status.as_str()) {
+        (_, "PARTIALLY_REFUNDED") if sale.delivered_at.is_some() => false,
+        ("OUT_FOR_DELIVERY", "PARTIALLY_REFUNDED") => sale.out_for_delivery_at.is_none(),
+        ("OUT_FOR_DELIVERY", status) => status == "PAID",
+        (_, status) => cash_on_delivery || matches!(status, "PAID" | "PARTIALLY_REFUNDED" | "OUT_FOR_DELIVERY"),
+    };
+    if !allowed {
+        return HttpResponse::BadRequest().body(format!("Cannot mark a sale in status {} as {}", sale.status, req.status));
+    }
+
+    let new_status = progressed_status(&sale.status, &req.status).to_string();
+    let mut update = Sales::update_many()
+        .col_expr(sales::Column::Status, Expr::value(new_status.clone()))
+        .col_expr(timestamp_column, Expr::current_timestamp().into());
+    if cash_on_delivery {
+        update = update.col_expr(sales::Column::PaidAt, Expr::current_timestamp().into());
+    }
+    // Only apply it if nobody changed the sale in the meantime
+    update = update
+        .filter(sales::Column::Id.eq(sale.id))
+        .filter(sales::Column::Status.eq(sale.status.clone()));
+    if new_status == sale.status {
+        update = update.filter(timestamp_column.is_null());
+    }
+    let result = update.exec(conn).await;
+
+    match result {
+        Ok(res) if res.rows_affected == 0 => HttpResponse::Conflict().body("Sale status changed, reload and try again"),
+        Ok(_) => {
+            let _ = record_status(conn, sale.id, &req.status, Some(user.id)).await;
+            data.events.publish(KermesseEvent::new(
+                sale.kermesse_id,
+                sale.buyer_id,
+                EventKind::SaleStatusChanged { sale_id: sale.id, status: new_status.clone() },
+            ));
+            HttpResponse::Ok().json(serde_json::json!({"status": new_status}))
+        }
+        Err(_) => HttpResponse::InternalServerError().body("Failed to update delivery"),
+    }
+}
+
+/// Pending delivery orders grouped by city and neighborhood, for printing.
+pub async fn get_route_sheet(
+    path: web::Path<i32>,
+    query: web::Query<RouteSheetQuery>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if !is_staff(conn, &kermesse, user.id).await {
+        return HttpResponse::Forbidden().body("Access denied");
+    }
+
+    let mut select = Sales::find()
+        .filter(sales::Column::KermesseId.eq(kermesse_id))
+        .filter(sales::Column::DeliveryMethod.eq("DELIVERY"))
+        .filter(sales::Column::Status.is_not_in(["CANCELLED", "REFUNDED", "DELIVERED"]))
+        .filter(sales::Column::DeliveredAt.is_null());
+    if let Some(person_id) = query.delivery_person_id {
+        select = select.filter(sales::Column::DeliveryPersonId.eq(person_id));
+    }
+
+    let pending = match select
+        .find_also_related(kermesses::Entity)
+        .order_by_asc(sales::Column::CreatedAt)
+        .all(conn)
+        .await
+    {
+        Ok(s) => s,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let stops = match build_stops(conn, pending).await {
+        Ok(s) => s,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let mut groups: BTreeMap<(String, String), Vec<DeliveryStop>> = BTreeMap::new();
+    for stop in stops {
+        let city = stop.delivery_city.clone().filter(|c| !c.trim().is_empty()).unwrap_or_else(|| "Sin ciudad".to_string());
+        let neighborhood = stop
+            .delivery_neighborhood
+            .clone()
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| "Sin barrio".to_string());
+        groups.entry((city, neighborhood)).or_default().push(stop);
+    }
+
+    let response: Vec<RouteSheetGroup> = groups
+        .into_iter()
+        .map(|((city, neighborhood), stops)| RouteSheetGroup { city, neighborhood, stops })
+        .collect();
+
+    HttpResponse::Ok().json(response)
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/delivery-people")
+            .route(web::get().to(list_delivery_people)),
+    )
+    .service(
+        web::resource("/kermesses/{id}/route-sheet")
+            .route(web::get().to(get_route_sheet)),
+    )
+    .service(
+        web::resource("/sales/{id}/delivery-assignment")
+            .route(web::put().to(assign_delivery)),
+    )
+    .service(
+        web::resource("/sales/{id}/delivery-status")
+            .route(web::put().to(update_delivery_status)),
+    )
+    .service(
+        web::resource("/my-deliveries")
+            .route(web::get().to(list_my_deliveries)),
+    );
+}
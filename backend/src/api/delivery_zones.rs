@@ -0,0 +1,225 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::Deserialize;
+
+use crate::entity::{delivery_zones, kermesses, prelude::*};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+
+#[derive(Deserialize)]
+pub struct ZoneListQuery {
+    /// Also list removed zones, so clients can tell "never configured" from "all removed".
+    #[serde(default)]
+    pub include_inactive: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CreateDeliveryZoneRequest {
+    pub name: String,
+    pub fee: rust_decimal::Decimal,
+    pub min_order: Option<rust_decimal::Decimal>,
+}
+
+const NO_DELIVERY: &str = "This kermesse does not offer delivery";
+
+/// Zone a delivery order is charged for, or the reason the order is not accepted.
+/// Kermesses that offer delivery but never set up zones deliver without a zone or fee.
+pub async fn zone_for_order<C: ConnectionTrait>(
+    conn: &C,
+    kermesse: &kermesses::Model,
+    zone_id: Option<i32>,
+    order_amount: rust_decimal::Decimal,
+) -> Result<Result<Option<delivery_zones::Model>, String>, DbErr> {
+    if !kermesse.delivery_enabled {
+        return Ok(Err(NO_DELIVERY.to_string()));
+    }
+
+    let zones = DeliveryZones::find()
+        .filter(delivery_zones::Column::KermesseId.eq(kermesse.id))
+        .all(conn)
+        .await?;
+
+    Ok(choose_zone(zones, zone_id, order_amount))
+}
+
+fn choose_zone(
+    zones: Vec<delivery_zones::Model>,
+    zone_id: Option<i32>,
+    order_amount: rust_decimal::Decimal,
+) -> Result<Option<delivery_zones::Model>, String> {
+    if zones.is_empty() {
+        return Ok(None);
+    }
+
+    // Zones exist but were all removed: the organizer stopped offering delivery
+    let active: Vec<delivery_zones::Model> = zones.into_iter().filter(|z| z.active).collect();
+    if active.is_empty() {
+        return Err(NO_DELIVERY.to_string());
+    }
+
+    let zone = match zone_id.and_then(|id| active.into_iter().find(|z| z.id == id)) {
+        Some(z) => z,
+        None => return Err("A valid delivery zone is required".to_string()),
+    };
+
+    if let Some(min) = zone.min_order {
+        if order_amount < min {
+            return Err(format!("Minimum order for zone '{}' is {}", zone.name, min));
+        }
+    }
+
+    Ok(Some(zone))
+}
+
+pub async fn create_delivery_zone(
+    path: web::Path<i32>,
+    req: web::Json<CreateDeliveryZoneRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can manage delivery zones");
+    }
+
+    if req.name.trim().is_empty() {
+        return HttpResponse::BadRequest().body("Zone name cannot be empty");
+    }
+
+    if req.fee < rust_decimal::Decimal::ZERO || req.min_order.map(|m| m < rust_decimal::Decimal::ZERO).unwrap_or(false) {
+        return HttpResponse::BadRequest().body("Fee and minimum order cannot be negative");
+    }
+
+    let zone = delivery_zones::ActiveModel {
+        kermesse_id: Set(kermesse_id),
+        name: Set(req.name.trim().to_string()),
+        fee: Set(req.fee),
+        min_order: Set(req.min_order),
+        active: Set(true),
+        ..Default::default()
+    };
+
+    match zone.insert(conn).await {
+        Ok(model) => HttpResponse::Created().json(model),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to create delivery zone"),
+    }
+}
+
+pub async fn list_delivery_zones(
+    path: web::Path<i32>,
+    query: web::Query<ZoneListQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+
+    let mut select = DeliveryZones::find().filter(delivery_zones::Column::KermesseId.eq(kermesse_id));
+    if !query.include_inactive {
+        select = select.filter(delivery_zones::Column::Active.eq(true));
+    }
+
+    match select
+        .order_by_asc(delivery_zones::Column::Fee)
+        .all(&data.conn)
+        .await
+    {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+/// Zones are deactivated rather than deleted so past sales keep their reference.
+pub async fn deactivate_delivery_zone(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let zone_id = path.into_inner();
+    let conn = &data.conn;
+
+    let (zone, kermesse) = match DeliveryZones::find_by_id(zone_id).find_also_related(Kermesses).one(conn).await {
+        Ok(Some((z, Some(k)))) => (z, k),
+        Ok(_) => return HttpResponse::NotFound().body("Delivery zone not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can manage delivery zones");
+    }
+
+    let mut zone: delivery_zones::ActiveModel = zone.into();
+    zone.active = Set(false);
+
+    match zone.update(conn).await {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({"status": "deactivated"})),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to update delivery zone"),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/delivery-zones")
+            .route(web::get().to(list_delivery_zones))
+            .route(web::post().to(create_delivery_zone)),
+    )
+    .service(
+        web::resource("/delivery-zones/{id}")
+            .route(web::delete().to(deactivate_delivery_zone)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn zone(id: i32, active: bool, min_order: Option<i64>) -> delivery_zones::Model {
+        delivery_zones::Model {
+            id,
+            kermesse_id: 1,
+            name: format!("Zona {}", id),
+            fee: Decimal::from(5),
+            min_order: min_order.map(Decimal::from),
+            active,
+        }
+    }
+
+    #[test]
+    fn kermesses_without_zones_deliver_without_one() {
+        assert_eq!(choose_zone(Vec::new(), None, Decimal::from(10)), Ok(None));
+        assert_eq!(choose_zone(Vec::new(), Some(3), Decimal::from(10)), Ok(None));
+    }
+
+    #[test]
+    fn configured_zones_require_an_active_one() {
+        let zones = vec![zone(1, true, None), zone(2, false, None)];
+        assert_eq!(choose_zone(zones.clone(), Some(1), Decimal::from(10)), Ok(Some(zone(1, true, None))));
+        assert!(choose_zone(zones.clone(), None, Decimal::from(10)).is_err());
+        assert!(choose_zone(zones.clone(), Some(2), Decimal::from(10)).is_err());
+        assert!(choose_zone(zones, Some(9), Decimal::from(10)).is_err());
+    }
+
+    #[test]
+    fn only_removed_zones_means_no_delivery() {
+        assert_eq!(
+            choose_zone(vec![zone(1, false, None)], Some(1), Decimal::from(10)),
+            Err("This kermesse does not offer delivery".to_string())
+        );
+    }
+
+    #[test]
+    fn minimum_order_is_enforced() {
+        let zones = vec![zone(1, true, Some(30))];
+        assert!(choose_zone(zones.clone(), Some(1), Decimal::from(29)).is_err());
+        assert!(choose_zone(zones, Some(1), Decimal::from(30)).is_ok());
+    }
+}
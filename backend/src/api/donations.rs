@@ -0,0 +1,204 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::entity::{donations, prelude::*};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+
+#[derive(Deserialize)]
+pub struct CreateDonationRequest {
+    pub amount: rust_decimal::Decimal,
+    pub donor_name: Option<String>,
+    #[serde(default)]
+    pub is_anonymous: bool,
+    pub message: Option<String>,
+    pub payment_method: String, // "QR", "CASH", "TRANSFER"
+}
+
+#[derive(Serialize)]
+pub struct DonorWallEntry {
+    pub donor_name: String,
+    pub amount: rust_decimal::Decimal,
+    pub message: Option<String>,
+    pub created_at: String,
+}
+
+/// Organizer-confirmed donations, summed per kermesse.
+pub async fn confirmed_by_kermesse<C: ConnectionTrait>(
+    conn: &C,
+    kermesse_ids: &[i32],
+) -> Result<HashMap<i32, rust_decimal::Decimal>, DbErr> {
+    Ok(Donations::find()
+        .filter(donations::Column::KermesseId.is_in(kermesse_ids.iter().copied()))
+        .filter(donations::Column::Status.eq("CONFIRMED"))
+        .select_only()
+        .column(donations::Column::KermesseId)
+        .column_as(donations::Column::Amount.sum(), "total")
+        .group_by(donations::Column::KermesseId)
+        .into_tuple::<(i32, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .collect())
+}
+
+pub async fn create_donation(
+    path: web::Path<i32>,
+    req: web::Json<CreateDonationRequest>,
+    user: Option<AuthenticatedUser>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    if Kermesses::find_by_id(kermesse_id).one(conn).await.unwrap_or(None).is_none() {
+        return HttpResponse::NotFound().body("Kermesse not found");
+    }
+
+    if req.amount <= rust_decimal::Decimal::ZERO {
+        return HttpResponse::BadRequest().body("Donation amount must be positive");
+    }
+
+    let donation = donations::ActiveModel {
+        kermesse_id: Set(kermesse_id),
+        user_id: Set(user.map(|u| u.id)),
+        amount: Set(req.amount),
+        donor_name: Set(req.donor_name.clone().filter(|n| !n.trim().is_empty())),
+        is_anonymous: Set(req.is_anonymous),
+        message: Set(req.message.clone().filter(|m| !m.trim().is_empty())),
+        payment_method: Set(req.payment_method.clone()),
+        status: Set("PENDING".to_string()),
+        ..Default::default()
+    };
+
+    match donation.insert(conn).await {
+        Ok(model) => HttpResponse::Created().json(serde_json::json!({"id": model.id, "status": model.status})),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Failed to record donation: {}", e)),
+    }
+}
+
+pub async fn list_donations(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can view donations");
+    }
+
+    match Donations::find()
+        .filter(donations::Column::KermesseId.eq(kermesse_id))
+        .order_by_desc(donations::Column::CreatedAt)
+        .all(conn)
+        .await
+    {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+pub async fn donor_wall(
+    path: web::Path<i32>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let list = match Donations::find()
+        .filter(donations::Column::KermesseId.eq(kermesse_id))
+        .filter(donations::Column::Status.eq("CONFIRMED"))
+        .order_by_desc(donations::Column::CreatedAt)
+        .all(conn)
+        .await
+    {
+        Ok(l) => l,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let response: Vec<DonorWallEntry> = list
+        .into_iter()
+        .map(|d| DonorWallEntry {
+            donor_name: if d.is_anonymous {
+                "Anónimo".to_string()
+            } else {
+                d.donor_name.unwrap_or_else(|| "Anónimo".to_string())
+            },
+            amount: d.amount,
+            message: d.message,
+            created_at: d.created_at.to_string(),
+        })
+        .collect();
+
+    HttpResponse::Ok().json(response)
+}
+
+#[derive(Deserialize)]
+pub struct UpdateDonationStatusRequest {
+    pub status: String, // "CONFIRMED" or "REJECTED"
+}
+
+pub async fn update_donation_status(
+    path: web::Path<i32>,
+    req: web::Json<UpdateDonationStatusRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let donation_id = path.into_inner();
+    let conn = &data.conn;
+
+    if !matches!(req.status.as_str(), "CONFIRMED" | "REJECTED" | "PENDING") {
+        return HttpResponse::BadRequest().body("Invalid donation status");
+    }
+
+    let donation = match Donations::find_by_id(donation_id).one(conn).await {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().body("Donation not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let kermesse = match Kermesses::find_by_id(donation.kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        _ => return HttpResponse::InternalServerError().body("Kermesse lookup error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can confirm donations");
+    }
+
+    let mut donation: donations::ActiveModel = donation.into();
+    donation.status = Set(req.status.clone());
+
+    match donation.update(conn).await {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({"status": "updated"})),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to update donation"),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/donations")
+            .route(web::get().to(list_donations))
+            .route(web::post().to(create_donation)),
+    )
+    .service(
+        web::resource("/kermesses/{id}/donations/wall")
+            .route(web::get().to(donor_wall)),
+    )
+    .service(
+        web::resource("/donations/{id}/status")
+            .route(web::put().to(update_donation_status)),
+    );
+}
@@ -0,0 +1,199 @@
+use actix_web::{web, web::Bytes, HttpResponse, Responder};
+use futures::future::{self, BoxFuture};
+use futures::stream;
+use sea_orm::EntityTrait;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tokio::time::Interval;
+
+use crate::api::collaboration::is_staff;
+use crate::entity::prelude::*;
+use crate::events::KermesseEvent;
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+use crate::utils::jwt::{sign_stream_ticket, verify_stream_ticket};
+
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+/// How often a staff stream checks the user still belongs to the kermesse.
+const STAFF_RECHECK: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize)]
+pub struct EventsQuery {
+    // EventSource cannot send an Authorization header, so it carries a stream ticket
+    pub ticket: Option<String>,
+}
+
+/// Answers whether the stream may stay open.
+type Recheck = Box<dyn Fn() -> BoxFuture<'static, bool>>;
+
+fn kermesse_stream(kermesse_id: i32) -> String {
+    format!("kermesse:{}", kermesse_id)
+}
+
+const MY_ORDERS_STREAM: &str = "my-orders";
+
+async fn next_recheck(recheck: &mut Option<(Interval, Recheck)>) -> bool {
+    match recheck {
+        Some((interval, still_allowed)) => {
+            interval.tick().await;
+            still_allowed().await
+        }
+        None => future::pending().await,
+    }
+}
+
+/// Turns the bus receiver into an SSE body, keeping only the events `allow` accepts.
+/// With `recheck`, the stream ends as soon as it answers false.
+fn sse_response<F>(receiver: Receiver<KermesseEvent>, allow: F, recheck: Option<Recheck>) -> HttpResponse
+where
+    F: Fn(&KermesseEvent) -> bool + 'static,
+{
+    let mut keep_alive = tokio::time::interval(KEEP_ALIVE);
+    keep_alive.reset();
+    let recheck = recheck.map(|still_allowed| {
+        let mut interval = tokio::time::interval(STAFF_RECHECK);
+        interval.reset();
+        (interval, still_allowed)
+    });
+
+    let state = (receiver, keep_alive, recheck, allow);
+    let body = stream::unfold(state, |(mut receiver, mut keep_alive, mut recheck, allow)| async move {
+        loop {
+            tokio::select! {
+                event = receiver.recv() => match event {
+                    Ok(event) if allow(&event) => {
+                        let payload = serde_json::to_string(&event).unwrap_or_default();
+                        let frame = format!("event: {}\ndata: {}\n\n", event.name(), payload);
+                        return Some((Ok::<_, actix_web::Error>(Bytes::from(frame)), (receiver, keep_alive, recheck, allow)));
+                    }
+                    Ok(_) => continue,
+                    // A slow client just misses some events; it reloads on the next one
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = keep_alive.tick() => {
+                    return Some((Ok(Bytes::from_static(b": keep-alive\n\n")), (receiver, keep_alive, recheck, allow)));
+                }
+                still_allowed = next_recheck(&mut recheck) => {
+                    if !still_allowed {
+                        return None;
+                    }
+                }
+            }
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(body)
+}
+
+/// Staff get every event of the kermesse; everyone else only public ones (stock).
+pub async fn kermesse_events(
+    path: web::Path<i32>,
+    query: web::Query<EventsQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let stream = kermesse_stream(kermesse_id);
+    let staff_id = match query.ticket.as_deref().and_then(|t| verify_stream_ticket(t, &stream)) {
+        Some(user_id) if is_staff(conn, &kermesse, user_id).await => Some(user_id),
+        _ => None,
+    };
+
+    // Staff lose the stream when they are removed from the kermesse
+    let recheck = staff_id.map(|user_id| -> Recheck {
+        let conn = conn.clone();
+        Box::new(move || {
+            let conn = conn.clone();
+            let kermesse = kermesse.clone();
+            Box::pin(async move { is_staff(&conn, &kermesse, user_id).await })
+        })
+    });
+
+    let staff = staff_id.is_some();
+    sse_response(
+        data.events.subscribe(),
+        move |event| event.kermesse_id == kermesse_id && (staff || event.is_public()),
+        recheck,
+    )
+}
+
+/// Ticket for the staff view of a kermesse stream.
+pub async fn kermesse_events_ticket(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if !is_staff(conn, &kermesse, user.id).await {
+        return HttpResponse::Forbidden().body("Only staff can follow kermesse events");
+    }
+
+    match sign_stream_ticket(user.id, &kermesse_stream(kermesse_id)) {
+        Ok(ticket) => HttpResponse::Ok().json(serde_json::json!({ "ticket": ticket })),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to sign ticket"),
+    }
+}
+
+/// Updates on the signed-in buyer's own orders, across kermesses.
+pub async fn my_order_events(
+    query: web::Query<EventsQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let user_id = match query.ticket.as_deref().and_then(|t| verify_stream_ticket(t, MY_ORDERS_STREAM)) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().body("Invalid ticket"),
+    };
+
+    sse_response(
+        data.events.subscribe(),
+        move |event| !event.is_public() && event.buyer_id == Some(user_id),
+        None,
+    )
+}
+
+/// Ticket for the signed-in buyer's order stream.
+pub async fn my_order_events_ticket(user: AuthenticatedUser) -> impl Responder {
+    match sign_stream_ticket(user.id, MY_ORDERS_STREAM) {
+        Ok(ticket) => HttpResponse::Ok().json(serde_json::json!({ "ticket": ticket })),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to sign ticket"),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/events")
+            .route(web::get().to(kermesse_events)),
+    )
+    .service(
+        web::resource("/kermesses/{id}/events/ticket")
+            .route(web::post().to(kermesse_events_ticket)),
+    )
+    .service(
+        web::resource("/my-orders/events")
+            .route(web::get().to(my_order_events)),
+    )
+    .service(
+        web::resource("/my-orders/events/ticket")
+            .route(web::post().to(my_order_events_ticket)),
+    );
+}
@@ -0,0 +1,126 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, ModelTrait, QueryFilter, QueryOrder, Set};
+use serde::Deserialize;
+
+use crate::entity::{expenses, prelude::*};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+use crate::utils::time;
+
+#[derive(Deserialize)]
+pub struct CreateExpenseRequest {
+    pub description: String,
+    pub category: String, // e.g. "INGREDIENTS", "RENTAL", "SUPPLIES", "OTHER"
+    pub amount: rust_decimal::Decimal,
+    pub spent_on: Option<chrono::NaiveDate>,
+}
+
+pub async fn create_expense(
+    path: web::Path<i32>,
+    req: web::Json<CreateExpenseRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can manage expenses");
+    }
+
+    if req.description.trim().is_empty() {
+        return HttpResponse::BadRequest().body("Description cannot be empty");
+    }
+
+    if req.amount <= rust_decimal::Decimal::ZERO {
+        return HttpResponse::BadRequest().body("Amount must be positive");
+    }
+
+    let category = req.category.trim().to_uppercase();
+    let expense = expenses::ActiveModel {
+        kermesse_id: Set(kermesse_id),
+        description: Set(req.description.trim().to_string()),
+        category: Set(if category.is_empty() { "OTHER".to_string() } else { category }),
+        amount: Set(req.amount),
+        spent_on: Set(req.spent_on.unwrap_or_else(time::today)),
+        created_by: Set(user.id),
+        ..Default::default()
+    };
+
+    match expense.insert(conn).await {
+        Ok(model) => HttpResponse::Created().json(model),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to create expense"),
+    }
+}
+
+pub async fn list_expenses(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can view expenses");
+    }
+
+    match Expenses::find()
+        .filter(expenses::Column::KermesseId.eq(kermesse_id))
+        .order_by_desc(expenses::Column::SpentOn)
+        .order_by_desc(expenses::Column::Id)
+        .all(conn)
+        .await
+    {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+pub async fn delete_expense(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let expense_id = path.into_inner();
+    let conn = &data.conn;
+
+    let (expense, kermesse) = match Expenses::find_by_id(expense_id).find_also_related(Kermesses).one(conn).await {
+        Ok(Some((e, Some(k)))) => (e, k),
+        Ok(_) => return HttpResponse::NotFound().body("Expense not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can manage expenses");
+    }
+
+    match expense.delete(conn).await {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({"status": "deleted"})),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to delete expense"),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/expenses")
+            .route(web::get().to(list_expenses))
+            .route(web::post().to(create_expense)),
+    )
+    .service(
+        web::resource("/expenses/{id}")
+            .route(web::delete().to(delete_expense)),
+    );
+}
@@ -0,0 +1,469 @@
+use actix_web::{web, HttpResponse, Responder};
+use rust_decimal::prelude::ToPrimitive;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::entity::{
+    dishes, donations, expenses, ingredient_donations, ingredients, prelude::*, refunds, sale_items, sales, users,
+};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+use crate::utils::time;
+
+/// Rows fetched per query; the CSV is streamed one page at a time.
+const EXPORT_PAGE_SIZE: u64 = 500;
+
+/// Spreadsheet apps read text starting with one of these as a formula.
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Customer names, notes and descriptions are user input: anything that could
+/// be taken for a formula is quoted so opening the export never evaluates it.
+fn neutralize_formula(text: &str) -> Cow<'_, str> {
+    if text.starts_with(FORMULA_PREFIXES) {
+        Cow::Owned(format!("'{}", text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// A spreadsheet cell; numbers stay numeric in the XLSX workbook.
+enum Cell {
+    Text(String),
+    Number(rust_decimal::Decimal),
+    Empty,
+}
+
+impl Cell {
+    fn text(value: impl Into<String>) -> Self {
+        Cell::Text(value.into())
+    }
+
+    fn to_csv(&self) -> String {
+        match self {
+            Cell::Text(s) => neutralize_formula(s).into_owned(),
+            Cell::Number(n) => n.to_string(),
+            Cell::Empty => String::new(),
+        }
+    }
+}
+
+type Page = (Vec<Vec<Cell>>, Option<i32>);
+
+#[derive(Clone, Copy)]
+enum Dataset {
+    Sales,
+    Donations,
+    IngredientDonations,
+    Expenses,
+}
+
+const WORKBOOK: [Dataset; 4] = [
+    Dataset::Sales,
+    Dataset::Donations,
+    Dataset::IngredientDonations,
+    Dataset::Expenses,
+];
+
+fn local_time(t: &sea_orm::prelude::DateTimeWithTimeZone) -> String {
+    t.with_timezone(&time::kermesse_offset()).format("%Y-%m-%d %H:%M").to_string()
+}
+
+async fn user_names(conn: &DatabaseConnection, ids: Vec<i32>) -> Result<HashMap<i32, String>, DbErr> {
+    Ok(Users::find()
+        .filter(users::Column::Id.is_in(ids))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u.full_name))
+        .collect())
+}
+
+impl Dataset {
+    fn from_file(file: &str) -> Option<Self> {
+        match file {
+            "sales.csv" => Some(Dataset::Sales),
+            "donations.csv" => Some(Dataset::Donations),
+            "ingredient-donations.csv" => Some(Dataset::IngredientDonations),
+            "expenses.csv" => Some(Dataset::Expenses),
+            _ => None,
+        }
+    }
+
+    fn file_stem(self) -> &'static str {
+        match self {
+            Dataset::Sales => "ventas",
+            Dataset::Donations => "donaciones",
+            Dataset::IngredientDonations => "donaciones-ingredientes",
+            Dataset::Expenses => "gastos",
+        }
+    }
+
+    fn sheet_name(self) -> &'static str {
+        match self {
+            Dataset::Sales => "Ventas",
+            Dataset::Donations => "Donaciones",
+            Dataset::IngredientDonations => "Ingredientes donados",
+            Dataset::Expenses => "Gastos",
+        }
+    }
+
+    fn headers(self) -> &'static [&'static str] {
+        match self {
+            // Sale-level amounts only appear on the first line of each sale so columns can be summed
+            Dataset::Sales => &[
+                "Orden", "Fecha", "Cliente", "Estado", "Pago", "Entrega", "Vendedor", "Plato", "Cantidad",
+                "Subtotal Plato", "Descuento", "Envío", "Donación Extra", "Total Venta", "Reembolsado", "Neto",
+            ],
+            Dataset::Donations => &["ID", "Fecha", "Donante", "Monto", "Pago", "Estado", "Mensaje"],
+            Dataset::IngredientDonations => &["ID", "Fecha", "Ingrediente", "Cantidad", "Unidad", "Donante"],
+            Dataset::Expenses => &["ID", "Fecha", "Categoría", "Descripción", "Monto", "Registrado por"],
+        }
+    }
+
+    /// Rows with an id greater than `after`, and the last id returned (None once exhausted).
+    async fn page(self, conn: &DatabaseConnection, kermesse_id: i32, after: i32) -> Result<Page, DbErr> {
+        match self {
+            Dataset::Sales => sales_page(conn, kermesse_id, after).await,
+            Dataset::Donations => donations_page(conn, kermesse_id, after).await,
+            Dataset::IngredientDonations => ingredient_donations_page(conn, kermesse_id, after).await,
+            Dataset::Expenses => expenses_page(conn, kermesse_id, after).await,
+        }
+    }
+}
+
+async fn sales_page(conn: &DatabaseConnection, kermesse_id: i32, after: i32) -> Result<Page, DbErr> {
+    let sales_list = Sales::find()
+        .filter(sales::Column::KermesseId.eq(kermesse_id))
+        .filter(sales::Column::Id.gt(after))
+        .order_by_asc(sales::Column::Id)
+        .limit(EXPORT_PAGE_SIZE)
+        .all(conn)
+        .await?;
+    let last_id = sales_list.last().map(|s| s.id);
+    if sales_list.is_empty() {
+        return Ok((Vec::new(), None));
+    }
+    let sale_ids: Vec<i32> = sales_list.iter().map(|s| s.id).collect();
+
+    let items = SaleItems::find()
+        .filter(sale_items::Column::SaleId.is_in(sale_ids.clone()))
+        .order_by_asc(sale_items::Column::Id)
+        .find_also_related(Dishes)
+        .all(conn)
+        .await?;
+    let mut items_by_sale: HashMap<i32, Vec<(sale_items::Model, Option<dishes::Model>)>> = HashMap::new();
+    for (item, dish) in items {
+        items_by_sale.entry(item.sale_id).or_default().push((item, dish));
+    }
+
+    let refunded: HashMap<i32, rust_decimal::Decimal> = Refunds::find()
+        .filter(refunds::Column::SaleId.is_in(sale_ids))
+        .select_only()
+        .column(refunds::Column::SaleId)
+        .column_as(refunds::Column::Amount.sum(), "refunded")
+        .group_by(refunds::Column::SaleId)
+        .into_tuple::<(i32, Option<rust_decimal::Decimal>)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|(id, total)| (id, total.unwrap_or_default()))
+        .collect();
+
+    let sellers = user_names(conn, sales_list.iter().map(|s| s.seller_id).collect()).await?;
+
+    let mut rows = Vec::new();
+    for sale in sales_list {
+        let refund = refunded.get(&sale.id).copied().unwrap_or_default();
+        let lines = items_by_sale.remove(&sale.id).unwrap_or_default();
+        let line_count = lines.len().max(1);
+        let mut lines = lines.into_iter().map(Some).collect::<Vec<_>>();
+        lines.resize_with(line_count, || None);
+
+        for (index, line) in lines.into_iter().enumerate() {
+            let (dish, quantity, subtotal) = match line {
+                Some((item, dish)) => (
+                    Cell::text(dish.map(|d| d.name).unwrap_or_default()),
+                    Cell::Number(item.quantity.into()),
+                    Cell::Number(item.subtotal),
+                ),
+                None => (Cell::Empty, Cell::Empty, Cell::Empty),
+            };
+            let first = index == 0;
+            let amount = |value: rust_decimal::Decimal| if first { Cell::Number(value) } else { Cell::Empty };
+            rows.push(vec![
+                Cell::Number(sale.id.into()),
+                Cell::text(local_time(&sale.created_at)),
+                Cell::text(sale.customer_name.clone()),
+                Cell::text(sale.status.clone()),
+                Cell::text(sale.payment_method.clone()),
+                Cell::text(sale.delivery_method.clone()),
+                Cell::text(sellers.get(&sale.seller_id).cloned().unwrap_or_default()),
+                dish,
+                quantity,
+                subtotal,
+                amount(sale.discount_amount),
+                amount(sale.delivery_fee),
+                amount(sale.extra_donation),
+                amount(sale.total_amount),
+                amount(refund),
+                amount(sale.total_amount - refund),
+            ]);
+        }
+    }
+
+    Ok((rows, last_id))
+}
+
+async fn donations_page(conn: &DatabaseConnection, kermesse_id: i32, after: i32) -> Result<Page, DbErr> {
+    let list = Donations::find()
+        .filter(donations::Column::KermesseId.eq(kermesse_id))
+        .filter(donations::Column::Id.gt(after))
+        .order_by_asc(donations::Column::Id)
+        .limit(EXPORT_PAGE_SIZE)
+        .all(conn)
+        .await?;
+    let last_id = list.last().map(|d| d.id);
+
+    let rows = list
+        .into_iter()
+        .map(|d| {
+            let donor = if d.is_anonymous { "Anónimo".to_string() } else { d.donor_name.unwrap_or_default() };
+            vec![
+                Cell::Number(d.id.into()),
+                Cell::text(local_time(&d.created_at)),
+                Cell::text(donor),
+                Cell::Number(d.amount),
+                Cell::text(d.payment_method),
+                Cell::text(d.status),
+                Cell::text(d.message.unwrap_or_default()),
+            ]
+        })
+        .collect();
+
+    Ok((rows, last_id))
+}
+
+async fn ingredient_donations_page(conn: &DatabaseConnection, kermesse_id: i32, after: i32) -> Result<Page, DbErr> {
+    let list = IngredientDonations::find()
+        .inner_join(Ingredients)
+        .filter(ingredients::Column::KermesseId.eq(kermesse_id))
+        .filter(ingredient_donations::Column::Id.gt(after))
+        .order_by_asc(ingredient_donations::Column::Id)
+        .limit(EXPORT_PAGE_SIZE)
+        .all(conn)
+        .await?;
+    let last_id = list.last().map(|d| d.id);
+    if list.is_empty() {
+        return Ok((Vec::new(), None));
+    }
+
+    let ingredient_list: HashMap<i32, ingredients::Model> = Ingredients::find()
+        .filter(ingredients::Column::KermesseId.eq(kermesse_id))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|i| (i.id, i))
+        .collect();
+    let donors = user_names(conn, list.iter().map(|d| d.user_id).collect()).await?;
+
+    let rows = list
+        .into_iter()
+        .map(|d| {
+            let ingredient = ingredient_list.get(&d.ingredient_id);
+            vec![
+                Cell::Number(d.id.into()),
+                Cell::text(local_time(&d.created_at)),
+                Cell::text(ingredient.map(|i| i.name.clone()).unwrap_or_default()),
+                Cell::Number(d.quantity_donated),
+                Cell::text(ingredient.map(|i| i.unit.clone()).unwrap_or_default()),
+                Cell::text(donors.get(&d.user_id).cloned().unwrap_or_default()),
+            ]
+        })
+        .collect();
+
+    Ok((rows, last_id))
+}
+
+async fn expenses_page(conn: &DatabaseConnection, kermesse_id: i32, after: i32) -> Result<Page, DbErr> {
+    let list = Expenses::find()
+        .filter(expenses::Column::KermesseId.eq(kermesse_id))
+        .filter(expenses::Column::Id.gt(after))
+        .order_by_asc(expenses::Column::Id)
+        .limit(EXPORT_PAGE_SIZE)
+        .all(conn)
+        .await?;
+    let last_id = list.last().map(|e| e.id);
+    if list.is_empty() {
+        return Ok((Vec::new(), None));
+    }
+
+    let authors = user_names(conn, list.iter().map(|e| e.created_by).collect()).await?;
+
+    let rows = list
+        .into_iter()
+        .map(|e| {
+            vec![
+                Cell::Number(e.id.into()),
+                Cell::text(e.spent_on.to_string()),
+                Cell::text(e.category),
+                Cell::text(e.description),
+                Cell::Number(e.amount),
+                Cell::text(authors.get(&e.created_by).cloned().unwrap_or_default()),
+            ]
+        })
+        .collect();
+
+    Ok((rows, last_id))
+}
+
+/// One page of the dataset as CSV; the first page carries the BOM and header.
+async fn csv_page(conn: &DatabaseConnection, kermesse_id: i32, dataset: Dataset, after: i32) -> Result<(web::Bytes, Option<i32>), String> {
+    let (rows, last_id) = dataset.page(conn, kermesse_id, after).await.map_err(|e| e.to_string())?;
+
+    let mut buffer = Vec::new();
+    if after == 0 {
+        // UTF-8 BOM so spreadsheet apps keep the accents
+        buffer.extend_from_slice(b"\xEF\xBB\xBF");
+    }
+    let mut writer = csv::Writer::from_writer(buffer);
+    if after == 0 {
+        writer.write_record(dataset.headers()).map_err(|e| e.to_string())?;
+    }
+    for row in rows {
+        writer.write_record(row.iter().map(Cell::to_csv)).map_err(|e| e.to_string())?;
+    }
+    let buffer = writer.into_inner().map_err(|e| e.to_string())?;
+
+    Ok((web::Bytes::from(buffer), last_id))
+}
+
+fn csv_response(conn: DatabaseConnection, kermesse_id: i32, dataset: Dataset) -> HttpResponse {
+    // State: the id to continue after, or None once the last page was sent
+    let pages = futures::stream::unfold(Some(0), move |after| {
+        let conn = conn.clone();
+        async move {
+            let after = after?;
+            match csv_page(&conn, kermesse_id, dataset, after).await {
+                Ok((bytes, next)) => Some((Ok::<_, actix_web::Error>(bytes), next)),
+                Err(e) => {
+                    log::error!("CSV export for kermesse {} failed: {}", kermesse_id, e);
+                    Some((Err(actix_web::error::ErrorInternalServerError("Export failed")), None))
+                }
+            }
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"kermesse-{}-{}.csv\"", kermesse_id, dataset.file_stem()),
+        ))
+        .streaming(pages)
+}
+
+async fn build_workbook(conn: &DatabaseConnection, kermesse_id: i32) -> Result<Vec<u8>, String> {
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let bold = rust_xlsxwriter::Format::new().set_bold();
+
+    for dataset in WORKBOOK {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(dataset.sheet_name()).map_err(|e| e.to_string())?;
+        for (col, title) in dataset.headers().iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, *title, &bold).map_err(|e| e.to_string())?;
+        }
+
+        let mut row_index: u32 = 1;
+        let mut after = 0;
+        loop {
+            let (rows, last_id) = dataset.page(conn, kermesse_id, after).await.map_err(|e| e.to_string())?;
+            for row in rows {
+                for (col, cell) in row.into_iter().enumerate() {
+                    let result = match cell {
+                        Cell::Text(s) => sheet.write_string(row_index, col as u16, neutralize_formula(&s)),
+                        Cell::Number(n) => sheet.write_number(row_index, col as u16, n.to_f64().unwrap_or_default()),
+                        Cell::Empty => continue,
+                    };
+                    result.map_err(|e| e.to_string())?;
+                }
+                row_index += 1;
+            }
+            match last_id {
+                Some(id) => after = id,
+                None => break,
+            }
+        }
+        sheet.autofit();
+    }
+
+    workbook.save_to_buffer().map_err(|e| e.to_string())
+}
+
+/// Spreadsheets for the treasurer: one CSV per dataset, or every dataset as an XLSX workbook.
+pub async fn export_kermesse(
+    path: web::Path<(i32, String)>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let (kermesse_id, file) = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can export kermesse data");
+    }
+
+    if file == "workbook.xlsx" {
+        return match build_workbook(conn, kermesse_id).await {
+            Ok(bytes) => HttpResponse::Ok()
+                .content_type("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+                .insert_header((
+                    "Content-Disposition",
+                    format!("attachment; filename=\"kermesse-{}.xlsx\"", kermesse_id),
+                ))
+                .body(bytes),
+            Err(e) => {
+                log::error!("Workbook export for kermesse {} failed: {}", kermesse_id, e);
+                HttpResponse::InternalServerError().body("Failed to build workbook")
+            }
+        };
+    }
+
+    match Dataset::from_file(&file) {
+        Some(dataset) => csv_response(conn.clone(), kermesse_id, dataset),
+        None => HttpResponse::NotFound().body("Unknown export"),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/export/{file}")
+            .route(web::get().to(export_kermesse)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formula_prefixes_are_quoted() {
+        for text in ["=SUM(A1:A9)", "+1", "-2+3", "@cmd", "\tx", "\rx"] {
+            assert_eq!(Cell::text(text).to_csv(), format!("'{}", text));
+        }
+    }
+
+    #[test]
+    fn plain_cells_are_unchanged() {
+        assert_eq!(Cell::text("María Pérez").to_csv(), "María Pérez");
+        assert_eq!(Cell::text("a=b").to_csv(), "a=b");
+        assert_eq!(Cell::Number(rust_decimal::Decimal::from(-5)).to_csv(), "-5");
+        assert_eq!(Cell::Empty.to_csv(), "");
+    }
+}
@@ -0,0 +1,96 @@
+use sea_orm::{
+    sea_query::{Expr, OnConflict}, ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Set,
+};
+use sha2::{Digest, Sha256};
+
+use crate::entity::{idempotency_keys, prelude::*};
+
+/// How long a key keeps replaying its original response.
+const RETENTION_HOURS: i64 = 24;
+
+/// A key without a response after this long belongs to a request that never
+/// committed (its response is stored with the sale), so it can be claimed again.
+const IN_PROGRESS_LEASE_MINUTES: i64 = 2;
+
+pub enum Claim {
+    /// First request with this key; the caller must `complete` or `release` it.
+    New,
+    /// The same request already succeeded; holds the stored response body.
+    Replay(String),
+    /// The first request with this key has not finished yet.
+    InProgress,
+    /// The key was already used with a different request.
+    Mismatch,
+}
+
+pub fn request_hash(payload: &[u8]) -> String {
+    hex::encode(Sha256::digest(payload))
+}
+
+/// Reserves `key` for the request identified by `hash`, or reports what happened to it before.
+pub async fn claim<C: ConnectionTrait>(conn: &C, key: &str, hash: &str) -> Result<Claim, DbErr> {
+    let now = chrono::Utc::now().fixed_offset();
+    IdempotencyKeys::delete_many()
+        .filter(
+            Condition::any()
+                .add(idempotency_keys::Column::CreatedAt.lt(now - chrono::Duration::hours(RETENTION_HOURS)))
+                .add(
+                    Condition::all()
+                        .add(idempotency_keys::Column::ResponseBody.is_null())
+                        .add(idempotency_keys::Column::CreatedAt.lt(now - chrono::Duration::minutes(IN_PROGRESS_LEASE_MINUTES))),
+                ),
+        )
+        .exec(conn)
+        .await?;
+
+    // The unique index decides between concurrent requests carrying the same key
+    let inserted = IdempotencyKeys::insert(idempotency_keys::ActiveModel {
+        key: Set(key.to_string()),
+        request_hash: Set(hash.to_string()),
+        ..Default::default()
+    })
+    .on_conflict(OnConflict::column(idempotency_keys::Column::Key).do_nothing().to_owned())
+    .exec(conn)
+    .await;
+
+    match inserted {
+        Ok(_) => return Ok(Claim::New),
+        Err(DbErr::RecordNotInserted) => {}
+        Err(e) => return Err(e),
+    }
+
+    let existing = IdempotencyKeys::find()
+        .filter(idempotency_keys::Column::Key.eq(key))
+        .one(conn)
+        .await?;
+
+    Ok(match existing {
+        Some(k) if k.request_hash != hash => Claim::Mismatch,
+        Some(k) => match k.response_body {
+            Some(body) => Claim::Replay(body),
+            None => Claim::InProgress,
+        },
+        // Expired and purged by a concurrent request in between
+        None => Claim::InProgress,
+    })
+}
+
+/// Stores the response to replay for later requests with the same key. Call it
+/// inside the transaction that records the result, so both commit together.
+pub async fn complete<C: ConnectionTrait>(conn: &C, key: &str, response_body: &str) -> Result<(), DbErr> {
+    IdempotencyKeys::update_many()
+        .col_expr(idempotency_keys::Column::ResponseBody, Expr::value(response_body.to_string()))
+        .filter(idempotency_keys::Column::Key.eq(key))
+        .exec(conn)
+        .await
+        .map(|_| ())
+}
+
+/// Frees the key after a failed request so the client can retry with it.
+pub async fn release<C: ConnectionTrait>(conn: &C, key: &str) -> Result<(), DbErr> {
+    IdempotencyKeys::delete_many()
+        .filter(idempotency_keys::Column::Key.eq(key))
+        .exec(conn)
+        .await
+        .map(|_| ())
+}
@@ -6,8 +6,11 @@ use serde::{Deserialize, Serialize};
 use chrono::NaiveDate;
 
 use crate::entity::{dishes, ingredients, kermesses, collaborators, users, prelude::*};
+use crate::api::dashboard::kermesse_totals;
+use crate::api::progress::progress_percentage;
 use crate::state::AppState;
 use crate::utils::auth::AuthenticatedUser;
+use crate::utils::time;
 
 #[derive(Serialize, Deserialize)]
 pub struct CreateKermesseRequest {
@@ -23,6 +26,8 @@ pub struct CreateKermesseRequest {
     pub qr_code_url: Option<String>,
     pub department: Option<String>,
     pub city: Option<String>,
+    /// Off unless the organizer offers home delivery.
+    pub delivery_enabled: Option<bool>,
 }
 
 #[derive(Serialize)]
@@ -42,6 +47,8 @@ pub struct KermesseResponse {
     pub organizer_id: i32,
     pub department: Option<String>,
     pub city: Option<String>,
+    pub transparency_enabled: bool,
+    pub delivery_enabled: bool,
 }
 
 #[derive(Serialize)]
@@ -80,6 +87,8 @@ impl From<kermesses::Model> for KermesseResponse {
             organizer_id: model.organizer_id,
             department: model.department,
             city: model.city,
+            transparency_enabled: model.transparency_enabled,
+            delivery_enabled: model.delivery_enabled,
         }
     }
 }
@@ -108,6 +117,7 @@ pub async fn create_kermesse(
         qr_code_url: Set(req.qr_code_url.clone()),
         department: Set(req.department.clone()),
         city: Set(req.city.clone()),
+        delivery_enabled: Set(req.delivery_enabled.unwrap_or(false)),
         status: Set("ACTIVE".to_string()), // Default to ACTIVE for now for testing
         ..Default::default()
     };
@@ -130,7 +140,7 @@ pub async fn list_kermesses(
 ) -> impl Responder {
     let conn = &data.conn;
 
-    let today = chrono::Local::now().date_naive();
+    let today = time::today();
 
     let mut query = Kermesses::find()
         .filter(kermesses::Column::Status.eq("ACTIVE"))
@@ -153,15 +163,41 @@ pub async fn list_kermesses(
         }
     }
 
-    let kermesses = query.all(conn).await;
+    let kermesses = match query.all(conn).await {
+        Ok(list) => list,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
 
-    match kermesses {
-        Ok(list) => {
-            let response: Vec<KermesseResponse> = list.into_iter().map(KermesseResponse::from).collect();
-            HttpResponse::Ok().json(response)
-        }
-        Err(_) => HttpResponse::InternalServerError().body("Database error"),
-    }
+    let ids: Vec<i32> = kermesses.iter().map(|k| k.id).collect();
+    let mut totals = match kermesse_totals(conn, &ids).await {
+        Ok(t) => t,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let response: Vec<KermesseListItem> = kermesses
+        .into_iter()
+        .map(|k| {
+            let total_raised = totals.remove(&k.id).unwrap_or_default().total_raised();
+            KermesseListItem {
+                progress_percentage: progress_percentage(total_raised, k.financial_goal),
+                total_raised,
+                kermesse: KermesseResponse::from(k),
+            }
+        })
+        .collect();
+
+    HttpResponse::Ok().json(response)
+}
+
+/// A public listing entry with its fundraising progress, so the cards
+/// don't need a `/progress` request each.
+#[derive(Serialize)]
+pub struct KermesseListItem {
+    #[serde(flatten)]
+    pub kermesse: KermesseResponse,
+    pub total_raised: rust_decimal::Decimal,
+    /// Same figure as `/kermesses/{id}/progress`.
+    pub progress_percentage: Option<f64>,
 }
 
 #[derive(Serialize)]
@@ -187,32 +223,23 @@ pub async fn get_my_kermesses(
         Err(_) => return HttpResponse::InternalServerError().body("Database error"),
     };
 
-    let mut response = Vec::new();
-    for k in kermesses {
-        let k_id = k.id;
-        
-        let sales_list = match crate::entity::sales::Entity::find()
-            .filter(crate::entity::sales::Column::KermesseId.eq(k_id))
-            .all(conn)
-            .await
-        {
-            Ok(s) => s,
-            Err(_) => vec![],
-        };
-
-        let total_raised = sales_list
-            .iter()
-            .filter(|s| s.status == "PAID" || s.status == "DELIVERED")
-            .fold(rust_decimal::Decimal::ZERO, |acc, s| acc + s.total_amount);
-
-        let total_orders = sales_list.len() as i64;
-
-        response.push(MyKermesseResponse {
-            kermesse: KermesseResponse::from(k),
-            total_raised,
-            total_orders,
-        });
-    }
+    let ids: Vec<i32> = kermesses.iter().map(|k| k.id).collect();
+    let mut totals = match kermesse_totals(conn, &ids).await {
+        Ok(t) => t,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let response: Vec<MyKermesseResponse> = kermesses
+        .into_iter()
+        .map(|k| {
+            let t = totals.remove(&k.id).unwrap_or_default();
+            MyKermesseResponse {
+                kermesse: KermesseResponse::from(k),
+                total_raised: t.total_raised(),
+                total_orders: t.total_orders(),
+            }
+        })
+        .collect();
 
     HttpResponse::Ok().json(response)
 }
@@ -358,6 +385,8 @@ pub struct UpdateKermesseRequest {
     pub qr_code_url: Option<String>,
     pub department: Option<String>,
     pub city: Option<String>,
+    pub transparency_enabled: Option<bool>,
+    pub delivery_enabled: Option<bool>,
 }
 
 pub async fn update_kermesse(
@@ -393,6 +422,8 @@ pub async fn update_kermesse(
     if let Some(qr) = &req.qr_code_url { kermesse.qr_code_url = Set(Some(qr.clone())); }
     if let Some(dept) = &req.department { kermesse.department = Set(Some(dept.clone())); }
     if let Some(city) = &req.city { kermesse.city = Set(Some(city.clone())); }
+    if let Some(enabled) = req.transparency_enabled { kermesse.transparency_enabled = Set(enabled); }
+    if let Some(enabled) = req.delivery_enabled { kermesse.delivery_enabled = Set(enabled); }
 
     match kermesse.update(conn).await {
         Ok(model) => HttpResponse::Ok().json(KermesseResponse::from(model)),
@@ -0,0 +1,240 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{DateTime, FixedOffset};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::api::collaboration::collaborator_role;
+use crate::entity::{dishes, kermesses, sale_items, sales, time_slots, prelude::*};
+use crate::events::{EventKind, KermesseEvent};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+
+const PREP_STATUSES: [&str; 3] = ["PENDING", "PREPARING", "READY"];
+
+#[derive(Serialize)]
+pub struct KitchenQueueItem {
+    pub id: i32,
+    pub dish_id: i32,
+    pub dish_name: String,
+    pub quantity: i32,
+    pub prep_status: String,
+}
+
+#[derive(Serialize)]
+pub struct KitchenQueueOrder {
+    pub sale_id: i32,
+    pub customer_name: String,
+    pub delivery_method: String,
+    pub time_slot: Option<String>,
+    pub paid_at: DateTime<FixedOffset>,
+    pub elapsed_minutes: i64,
+    pub items: Vec<KitchenQueueItem>,
+}
+
+#[derive(Serialize)]
+pub struct KitchenDishQueue {
+    pub dish_id: i32,
+    pub dish_name: String,
+    pub pending: i32,
+    pub preparing: i32,
+}
+
+#[derive(Serialize)]
+pub struct KitchenQueueResponse {
+    pub dishes: Vec<KitchenDishQueue>,
+    pub orders: Vec<KitchenQueueOrder>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdatePrepStatusRequest {
+    pub prep_status: String,
+}
+
+/// Organizer or an accepted KITCHEN collaborator.
+async fn can_use_kitchen<C: ConnectionTrait>(conn: &C, kermesse: &kermesses::Model, user_id: i32) -> bool {
+    kermesse.organizer_id == user_id
+        || collaborator_role(conn, kermesse.id, user_id).await.as_deref() == Some("KITCHEN")
+}
+
+pub async fn get_kitchen_queue(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if !can_use_kitchen(conn, &kermesse, user.id).await {
+        return HttpResponse::Forbidden().body("Only the organizer or kitchen staff can view the queue");
+    }
+
+    // Paid orders, oldest first (FIFO)
+    let paid_sales = match Sales::find()
+        .filter(sales::Column::KermesseId.eq(kermesse_id))
+        .filter(sales::Column::Status.eq("PAID"))
+        .order_by_asc(sales::Column::CreatedAt)
+        .all(conn)
+        .await
+    {
+        Ok(s) => s,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let items = match SaleItems::find()
+        .filter(sale_items::Column::SaleId.is_in(paid_sales.iter().map(|s| s.id)))
+        .filter(sale_items::Column::PrepStatus.ne("READY"))
+        .find_also_related(dishes::Entity)
+        .all(conn)
+        .await
+    {
+        Ok(i) => i,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let slots: BTreeMap<i32, String> = match TimeSlots::find()
+        .filter(time_slots::Column::KermesseId.eq(kermesse_id))
+        .all(conn)
+        .await
+    {
+        Ok(s) => s.into_iter().map(|s| (s.id, format!("{} - {}", s.starts_at, s.ends_at))).collect(),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let now = chrono::Utc::now();
+    let mut dishes_queue: BTreeMap<i32, KitchenDishQueue> = BTreeMap::new();
+    let mut orders = Vec::new();
+
+    for sale in paid_sales {
+        let order_items: Vec<KitchenQueueItem> = items
+            .iter()
+            .filter(|(item, _)| item.sale_id == sale.id)
+            .map(|(item, dish)| KitchenQueueItem {
+                id: item.id,
+                dish_id: item.dish_id,
+                dish_name: dish.as_ref().map(|d| d.name.clone()).unwrap_or_default(),
+                quantity: item.quantity,
+                prep_status: item.prep_status.clone(),
+            })
+            .collect();
+
+        // Orders whose items are all READY have left the queue
+        if order_items.is_empty() {
+            continue;
+        }
+
+        for item in &order_items {
+            let entry = dishes_queue.entry(item.dish_id).or_insert_with(|| KitchenDishQueue {
+                dish_id: item.dish_id,
+                dish_name: item.dish_name.clone(),
+                pending: 0,
+                preparing: 0,
+            });
+            if item.prep_status == "PREPARING" {
+                entry.preparing += item.quantity;
+            } else {
+                entry.pending += item.quantity;
+            }
+        }
+
+        let paid_at = sale.paid_at.unwrap_or(sale.created_at);
+        orders.push(KitchenQueueOrder {
+            sale_id: sale.id,
+            customer_name: sale.customer_name,
+            delivery_method: sale.delivery_method,
+            time_slot: sale.time_slot_id.and_then(|id| slots.get(&id).cloned()),
+            paid_at,
+            elapsed_minutes: (now - paid_at.with_timezone(&chrono::Utc)).num_minutes().max(0),
+            items: order_items,
+        });
+    }
+
+    // Cash orders may have been marked PAID without a paid_at; fall back to creation time
+    orders.sort_by_key(|o| o.paid_at);
+
+    HttpResponse::Ok().json(KitchenQueueResponse {
+        dishes: dishes_queue.into_values().collect(),
+        orders,
+    })
+}
+
+pub async fn update_prep_status(
+    path: web::Path<i32>,
+    req: web::Json<UpdatePrepStatusRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let item_id = path.into_inner();
+    let conn = &data.conn;
+
+    if !PREP_STATUSES.contains(&req.prep_status.as_str()) {
+        return HttpResponse::BadRequest().body("Invalid preparation status");
+    }
+
+    let (item, sale) = match SaleItems::find_by_id(item_id).find_also_related(sales::Entity).one(conn).await {
+        Ok(Some((item, Some(sale)))) => (item, sale),
+        Ok(_) => return HttpResponse::NotFound().body("Sale item not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let kermesse = match Kermesses::find_by_id(sale.kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if !can_use_kitchen(conn, &kermesse, user.id).await {
+        return HttpResponse::Forbidden().body("Only the organizer or kitchen staff can update items");
+    }
+
+    if sale.status != "PAID" {
+        return HttpResponse::BadRequest().body("Only paid orders can be prepared");
+    }
+
+    let res = SaleItems::update_many()
+        .col_expr(sale_items::Column::PrepStatus, Expr::value(req.prep_status.clone()))
+        .col_expr(sale_items::Column::PrepUpdatedAt, Expr::current_timestamp().into())
+        .filter(sale_items::Column::Id.eq(item.id))
+        .exec(conn)
+        .await;
+
+    if res.is_err() {
+        return HttpResponse::InternalServerError().body("Failed to update item");
+    }
+
+    // Let the buyer know once the whole order is ready
+    if req.prep_status == "READY" {
+        let still_cooking = SaleItems::find()
+            .filter(sale_items::Column::SaleId.eq(sale.id))
+            .filter(sale_items::Column::PrepStatus.ne("READY"))
+            .count(conn)
+            .await;
+        if let Ok(0) = still_cooking {
+            data.events.publish(KermesseEvent::new(sale.kermesse_id, sale.buyer_id, EventKind::OrderReady { sale_id: sale.id }));
+        }
+    }
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "id": item.id,
+        "prep_status": req.prep_status,
+    }))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/kitchen/queue")
+            .route(web::get().to(get_kitchen_queue)),
+    )
+    .service(
+        web::resource("/sale-items/{id}/prep-status")
+            .route(web::put().to(update_prep_status)),
+    );
+}
@@ -3,3 +3,22 @@ pub mod kermesse;
 pub mod sales;
 pub mod collaboration;
 pub mod dashboard;
+pub mod payments;
+pub mod refunds;
+pub mod promo_codes;
+pub mod donations;
+pub mod time_slots;
+pub mod kitchen;
+pub mod events;
+pub mod deliveries;
+pub mod delivery_zones;
+pub mod idempotency;
+pub mod pickup;
+pub mod tracking;
+pub mod claims;
+pub mod expenses;
+pub mod exports;
+pub mod reports;
+pub mod overview;
+pub mod transparency;
+pub mod progress;
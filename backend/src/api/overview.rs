@@ -0,0 +1,278 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{Datelike, NaiveDate};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect, RelationTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::api::dashboard::kermesse_totals;
+use crate::api::sales::KEPT_STATUSES;
+use crate::entity::{
+    collaborators, dishes, ingredient_donations, ingredients, kermesses, prelude::*, refunds, sale_items, sales,
+};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+
+/// How many best sellers are listed per kermesse.
+const TOP_DISHES: usize = 3;
+
+#[derive(Deserialize)]
+pub struct OverviewQuery {
+    /// Inclusive range on the event date.
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Serialize)]
+pub struct OrganizerOverview {
+    pub kermesses: Vec<KermesseComparison>,
+    pub years: Vec<YearSummary>,
+}
+
+#[derive(Serialize)]
+pub struct KermesseComparison {
+    pub id: i32,
+    pub name: String,
+    pub event_date: NaiveDate,
+    pub status: String,
+    /// Sales net of refunds plus standalone donations, as on the dashboard.
+    pub total_raised: rust_decimal::Decimal,
+    pub sales_revenue: rust_decimal::Decimal,
+    pub donations_total: rust_decimal::Decimal,
+    pub orders: i64,
+    pub average_ticket: rust_decimal::Decimal,
+    pub ingredient_coverage_percentage: f64,
+    pub collaborators: i64,
+    pub top_dishes: Vec<TopDish>,
+}
+
+#[derive(Serialize)]
+pub struct TopDish {
+    pub name: String,
+    pub units_sold: i64,
+    pub revenue: rust_decimal::Decimal,
+}
+
+#[derive(Serialize)]
+pub struct YearSummary {
+    pub year: i32,
+    pub kermesses: i64,
+    pub total_raised: rust_decimal::Decimal,
+    pub orders: i64,
+    pub average_ticket: rust_decimal::Decimal,
+    /// Change of `total_raised` against the previous year that had events, in percent.
+    pub total_raised_change_percentage: Option<f64>,
+}
+
+fn average(total: rust_decimal::Decimal, count: i64) -> rust_decimal::Decimal {
+    if count > 0 {
+        (total / rust_decimal::Decimal::from(count)).round_dp(2)
+    } else {
+        rust_decimal::Decimal::ZERO
+    }
+}
+
+pub async fn get_organizer_overview(
+    query: web::Query<OverviewQuery>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let conn = &data.conn;
+
+    match organizer_overview(conn, user.id, &query).await {
+        Ok(overview) => HttpResponse::Ok().json(overview),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+/// Side-by-side figures of every kermesse the user organizes. The number of queries
+/// does not depend on how many kermesses or sales there are.
+async fn organizer_overview(
+    conn: &DatabaseConnection,
+    organizer_id: i32,
+    query: &OverviewQuery,
+) -> Result<OrganizerOverview, DbErr> {
+    let mut select = Kermesses::find().filter(kermesses::Column::OrganizerId.eq(organizer_id));
+    if let Some(from) = query.from {
+        select = select.filter(kermesses::Column::EventDate.gte(from));
+    }
+    if let Some(to) = query.to {
+        select = select.filter(kermesses::Column::EventDate.lte(to));
+    }
+    let kermesse_list = select
+        .order_by_desc(kermesses::Column::EventDate)
+        .order_by_desc(kermesses::Column::Id)
+        .all(conn)
+        .await?;
+    let ids: Vec<i32> = kermesse_list.iter().map(|k| k.id).collect();
+    if ids.is_empty() {
+        return Ok(OrganizerOverview { kermesses: Vec::new(), years: Vec::new() });
+    }
+
+    let mut totals = kermesse_totals(conn, &ids).await?;
+
+    // Net of refunds, like the dashboard's sales analytics
+    let mut top_dishes: HashMap<i32, Vec<TopDish>> = HashMap::new();
+    let dish_sales = SaleItems::find()
+        .inner_join(Sales)
+        .inner_join(Dishes)
+        .filter(sales::Column::KermesseId.is_in(ids.iter().copied()))
+        .filter(sales::Column::Status.is_in(KEPT_STATUSES))
+        .select_only()
+        .column(sales::Column::KermesseId)
+        .column(sale_items::Column::DishId)
+        .column(dishes::Column::Name)
+        .column_as(sale_items::Column::Quantity.sum(), "units")
+        .column_as(sale_items::Column::Subtotal.sum(), "revenue")
+        .group_by(sales::Column::KermesseId)
+        .group_by(sale_items::Column::DishId)
+        .group_by(dishes::Column::Name)
+        .into_tuple::<(i32, i32, String, i64, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?;
+    let refunded_per_dish: HashMap<(i32, i32), (i64, rust_decimal::Decimal)> = Refunds::find()
+        .inner_join(SaleItems)
+        .join(JoinType::InnerJoin, sale_items::Relation::Sales.def())
+        .filter(sales::Column::KermesseId.is_in(ids.iter().copied()))
+        .filter(sales::Column::Status.is_in(KEPT_STATUSES))
+        .select_only()
+        .column(sales::Column::KermesseId)
+        .column(sale_items::Column::DishId)
+        .column_as(refunds::Column::Quantity.sum(), "units")
+        .column_as(refunds::Column::Amount.sum(), "amount")
+        .group_by(sales::Column::KermesseId)
+        .group_by(sale_items::Column::DishId)
+        .into_tuple::<(i32, i32, Option<i64>, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|(kermesse_id, dish_id, units, amount)| ((kermesse_id, dish_id), (units.unwrap_or(0), amount)))
+        .collect();
+    for (kermesse_id, dish_id, name, units, gross) in dish_sales {
+        let (refunded_units, refunded) = refunded_per_dish
+            .get(&(kermesse_id, dish_id))
+            .copied()
+            .unwrap_or((0, rust_decimal::Decimal::ZERO));
+        top_dishes.entry(kermesse_id).or_default().push(TopDish {
+            name,
+            units_sold: units - refunded_units,
+            revenue: gross - refunded,
+        });
+    }
+    for dishes in top_dishes.values_mut() {
+        dishes.sort_by_key(|d| std::cmp::Reverse(d.units_sold));
+        dishes.truncate(TOP_DISHES);
+    }
+
+    let needed: HashMap<i32, rust_decimal::Decimal> = Ingredients::find()
+        .filter(ingredients::Column::KermesseId.is_in(ids.iter().copied()))
+        .select_only()
+        .column(ingredients::Column::KermesseId)
+        .column_as(ingredients::Column::QuantityNeeded.sum(), "needed")
+        .group_by(ingredients::Column::KermesseId)
+        .into_tuple::<(i32, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .collect();
+    let donated: HashMap<i32, rust_decimal::Decimal> = IngredientDonations::find()
+        .inner_join(Ingredients)
+        .filter(ingredients::Column::KermesseId.is_in(ids.iter().copied()))
+        .select_only()
+        .column(ingredients::Column::KermesseId)
+        .column_as(ingredient_donations::Column::QuantityDonated.sum(), "donated")
+        .group_by(ingredients::Column::KermesseId)
+        .into_tuple::<(i32, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .collect();
+
+    let collaborator_counts: HashMap<i32, i64> = Collaborators::find()
+        .filter(collaborators::Column::KermesseId.is_in(ids.iter().copied()))
+        .filter(collaborators::Column::Status.eq("ACCEPTED"))
+        .select_only()
+        .column(collaborators::Column::KermesseId)
+        .column_as(collaborators::Column::Id.count(), "collaborators")
+        .group_by(collaborators::Column::KermesseId)
+        .into_tuple::<(i32, i64)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .collect();
+
+    // Per year: events, total raised, sales net of refunds, orders not fully refunded
+    let mut per_year: BTreeMap<i32, (i64, rust_decimal::Decimal, rust_decimal::Decimal, i64)> = BTreeMap::new();
+    let comparisons: Vec<KermesseComparison> = kermesse_list
+        .into_iter()
+        .map(|k| {
+            let t = totals.remove(&k.id).unwrap_or_default();
+            let sales_net = t.gross_collected - t.refunded;
+            // Fully refunded orders brought nothing in, so they would only pull the average down
+            let orders = t.kept_orders();
+
+            // Same rule as the dashboard: nothing to collect counts as fully covered
+            let total_needed = needed.get(&k.id).copied().unwrap_or(rust_decimal::Decimal::ZERO);
+            let ingredient_coverage_percentage = if total_needed > rust_decimal::Decimal::ZERO {
+                let total_donated = donated.get(&k.id).copied().unwrap_or(rust_decimal::Decimal::ZERO);
+                let ratio: f64 = (total_donated / total_needed).try_into().unwrap_or(0.0);
+                (ratio * 100.0).min(100.0)
+            } else {
+                100.0
+            };
+
+            let year = per_year
+                .entry(k.event_date.year())
+                .or_insert((0, rust_decimal::Decimal::ZERO, rust_decimal::Decimal::ZERO, 0));
+            year.0 += 1;
+            year.1 += t.total_raised();
+            year.2 += sales_net;
+            year.3 += orders;
+
+            KermesseComparison {
+                total_raised: t.total_raised(),
+                sales_revenue: sales_net - t.extra_donations,
+                donations_total: t.extra_donations + t.donations,
+                orders,
+                average_ticket: average(sales_net, orders),
+                ingredient_coverage_percentage,
+                collaborators: collaborator_counts.get(&k.id).copied().unwrap_or(0),
+                top_dishes: top_dishes.remove(&k.id).unwrap_or_default(),
+                id: k.id,
+                name: k.name,
+                event_date: k.event_date,
+                status: k.status,
+            }
+        })
+        .collect();
+
+    // Walk the years oldest first so each one is compared with the one before it
+    let mut years = Vec::new();
+    let mut previous: Option<rust_decimal::Decimal> = None;
+    for (year, (kermesses, total_raised, sales_net, orders)) in per_year {
+        let total_raised_change_percentage = previous
+            .filter(|p| *p > rust_decimal::Decimal::ZERO)
+            .and_then(|p| f64::try_from((total_raised - p) / p).ok())
+            .map(|ratio| ratio * 100.0);
+        previous = Some(total_raised);
+        years.push(YearSummary {
+            year,
+            kermesses,
+            total_raised,
+            orders,
+            average_ticket: average(sales_net, orders),
+            total_raised_change_percentage,
+        });
+    }
+    years.reverse();
+
+    Ok(OrganizerOverview { kermesses: comparisons, years })
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/my-kermesses/overview")
+            .route(web::get().to(get_organizer_overview)),
+    );
+}
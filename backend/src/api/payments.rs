@@ -0,0 +1,91 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter,
+};
+
+use crate::entity::{sales, prelude::*};
+use crate::api::sales::record_status;
+use crate::events::{EventKind, KermesseEvent};
+use crate::payments::PaymentEventStatus;
+use crate::state::AppState;
+
+pub async fn payment_webhook(
+    path: web::Path<String>,
+    req: HttpRequest,
+    body: web::Bytes,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let provider_name = path.into_inner();
+    let conn = &data.conn;
+
+    let provider = match data.payments.get(&provider_name) {
+        Some(p) => p,
+        None => return HttpResponse::NotFound().body("Unknown payment provider"),
+    };
+
+    let signature = match req.headers().get("X-Signature").and_then(|v| v.to_str().ok()) {
+        Some(s) => s,
+        None => return HttpResponse::Unauthorized().body("Missing signature"),
+    };
+
+    if !provider.verify_signature(&body, signature) {
+        return HttpResponse::Unauthorized().body("Invalid signature");
+    }
+
+    let event = match provider.parse_event(&body) {
+        Ok(e) => e,
+        Err(e) => return HttpResponse::BadRequest().body(format!("Invalid payload: {}", e)),
+    };
+
+    let sale = match Sales::find()
+        .filter(sales::Column::PaymentReference.eq(event.reference.clone()))
+        .one(conn)
+        .await
+    {
+        Ok(Some(s)) => s,
+        Ok(None) => return HttpResponse::NotFound().body("No sale matches this payment reference"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if event.status == PaymentEventStatus::Failed {
+        // Leave the sale PENDING so the buyer can retry or pay in cash
+        return HttpResponse::Ok().json(serde_json::json!({"status": "ignored"}));
+    }
+
+    if event.amount != sale.total_amount {
+        return HttpResponse::Conflict().body("Payment amount does not match sale total");
+    }
+
+    // Only PENDING sales transition, so webhook retries are harmless
+    let result = Sales::update_many()
+        .col_expr(sales::Column::Status, Expr::value("PAID"))
+        .col_expr(sales::Column::PaymentProvider, Expr::value(provider.name()))
+        .col_expr(sales::Column::PaidAt, Expr::current_timestamp().into())
+        .filter(sales::Column::Id.eq(sale.id))
+        .filter(sales::Column::Status.eq("PENDING"))
+        .exec(conn)
+        .await;
+
+    match result {
+        Ok(res) if res.rows_affected == 0 => {
+            HttpResponse::Ok().json(serde_json::json!({"status": "already_processed", "sale_id": sale.id}))
+        }
+        Ok(_) => {
+            let _ = record_status(conn, sale.id, "PAID", None).await;
+            data.events.publish(KermesseEvent::new(
+                sale.kermesse_id,
+                sale.buyer_id,
+                EventKind::SaleStatusChanged { sale_id: sale.id, status: "PAID".to_string() },
+            ));
+            HttpResponse::Ok().json(serde_json::json!({"status": "paid", "sale_id": sale.id}))
+        }
+        Err(_) => HttpResponse::InternalServerError().body("Failed to update sale"),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/payments/webhook/{provider}")
+            .route(web::post().to(payment_webhook)),
+    );
+}
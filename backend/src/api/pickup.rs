@@ -0,0 +1,154 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+use crate::api::collaboration::is_staff;
+use crate::api::sales::{progressed_status, record_status};
+use crate::entity::{dishes, sale_items, sales, prelude::*};
+use crate::events::{EventKind, KermesseEvent};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+
+#[derive(Deserialize)]
+pub struct VerifyPickupRequest {
+    pub code: String,
+    pub deliver: Option<bool>, // false/absent only looks the order up
+}
+
+#[derive(Serialize)]
+pub struct PickupItem {
+    pub dish_name: String,
+    pub quantity: i32,
+}
+
+#[derive(Serialize)]
+pub struct PickupOrder {
+    pub sale_id: i32,
+    pub customer_name: String,
+    pub status: String,
+    pub payment_method: String,
+    pub delivery_method: String,
+    pub total_amount: rust_decimal::Decimal,
+    pub delivered_at: Option<String>,
+    pub items: Vec<PickupItem>,
+}
+
+/// Codes are printed in groups and may be typed in lowercase; compare the bare characters.
+fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Looks up the order behind a pickup code and, with `deliver`, hands it over.
+/// Unpaid cash orders are collected at the stand, so they may be delivered too.
+pub async fn verify_pickup(
+    path: web::Path<i32>,
+    req: web::Json<VerifyPickupRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if !is_staff(conn, &kermesse, user.id).await {
+        return HttpResponse::Forbidden().body("Only staff can verify pickups");
+    }
+
+    let sale = match Sales::find()
+        .filter(sales::Column::KermesseId.eq(kermesse_id))
+        .filter(sales::Column::PickupCode.eq(normalize_code(&req.code)))
+        .one(conn)
+        .await
+    {
+        Ok(Some(s)) => s,
+        Ok(None) => return HttpResponse::NotFound().body("No order with this pickup code"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let items = match SaleItems::find()
+        .filter(sale_items::Column::SaleId.eq(sale.id))
+        .find_also_related(dishes::Entity)
+        .all(conn)
+        .await
+    {
+        Ok(i) => i,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let mut order = PickupOrder {
+        sale_id: sale.id,
+        customer_name: sale.customer_name.clone(),
+        status: sale.status.clone(),
+        payment_method: sale.payment_method.clone(),
+        delivery_method: sale.delivery_method.clone(),
+        total_amount: sale.total_amount,
+        delivered_at: sale.delivered_at.map(|t| t.to_string()),
+        items: items
+            .into_iter()
+            .map(|(item, dish)| PickupItem {
+                dish_name: dish.map(|d| d.name).unwrap_or_default(),
+                quantity: item.quantity,
+            })
+            .collect(),
+    };
+
+    if !req.deliver.unwrap_or(false) {
+        return HttpResponse::Ok().json(order);
+    }
+
+    // Same rule as deliveries: a partially refunded order can be handed over once, keeping its status
+    let deliverable = match sale.status.as_str() {
+        "PAID" | "OUT_FOR_DELIVERY" => true,
+        "PARTIALLY_REFUNDED" => sale.delivered_at.is_none(),
+        "PENDING" => sale.payment_method == "CASH",
+        _ => false,
+    };
+    if !deliverable {
+        return HttpResponse::Conflict().json(order);
+    }
+
+    // Guard on what was read above so two scans cannot both hand the order over
+    let now = chrono::Utc::now().fixed_offset();
+    let new_status = progressed_status(&sale.status, "DELIVERED").to_string();
+    let mut update = Sales::update_many()
+        .col_expr(sales::Column::Status, Expr::value(new_status.clone()))
+        .col_expr(sales::Column::DeliveredAt, Expr::value(now))
+        .col_expr(sales::Column::PaidAt, Expr::value(sale.paid_at.unwrap_or(now)))
+        .filter(sales::Column::Id.eq(sale.id))
+        .filter(sales::Column::Status.eq(sale.status.clone()));
+    if new_status == sale.status {
+        update = update.filter(sales::Column::DeliveredAt.is_null());
+    }
+    let result = update.exec(conn).await;
+
+    match result {
+        Ok(res) if res.rows_affected == 1 => {
+            let _ = record_status(conn, sale.id, "DELIVERED", Some(user.id)).await;
+            data.events.publish(KermesseEvent::new(
+                sale.kermesse_id,
+                sale.buyer_id,
+                EventKind::SaleStatusChanged { sale_id: sale.id, status: new_status.clone() },
+            ));
+            order.status = new_status;
+            order.delivered_at = Some(now.to_string());
+            HttpResponse::Ok().json(order)
+        }
+        Ok(_) => HttpResponse::Conflict().body("Order was updated by someone else, scan it again"),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to update order"),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/pickup/verify")
+            .route(web::post().to(verify_pickup)),
+    );
+}
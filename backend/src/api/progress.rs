@@ -0,0 +1,208 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait};
+use serde::Serialize;
+
+use crate::api::dashboard::kermesse_totals;
+use crate::entity::{kermesses, prelude::*};
+use crate::state::AppState;
+
+/// Progress only changes when someone pays, so a short shared cache keeps
+/// embedded badges from hitting the database on every page view.
+const CACHE_CONTROL: &str = "public, max-age=60";
+
+/// Width of the bar drawn in the SVG badge.
+const BADGE_BAR_WIDTH: f64 = 296.0;
+
+/// Names longer than this are cut so they fit in the badge.
+const BADGE_NAME_CHARS: usize = 38;
+
+/// Public fundraising figures. Only totals: no buyers, donors or per-sale data.
+#[derive(Serialize)]
+pub struct FundraisingProgress {
+    pub kermesse_id: i32,
+    pub name: String,
+    pub beneficiary_name: String,
+    /// Sales net of refunds plus standalone donations, as on the dashboard.
+    pub total_raised: rust_decimal::Decimal,
+    pub financial_goal: Option<rust_decimal::Decimal>,
+    /// Not capped at 100 so the goal can be shown as exceeded; `None` without a goal.
+    pub progress_percentage: Option<f64>,
+}
+
+/// Share of the goal raised so far, uncapped; `None` without a positive goal.
+pub fn progress_percentage(
+    total_raised: rust_decimal::Decimal,
+    financial_goal: Option<rust_decimal::Decimal>,
+) -> Option<f64> {
+    financial_goal
+        .filter(|goal| *goal > rust_decimal::Decimal::ZERO)
+        .map(|goal| {
+            let ratio: f64 = (total_raised / goal).try_into().unwrap_or(0.0);
+            ratio * 100.0
+        })
+}
+
+async fn fundraising_progress(conn: &DatabaseConnection, kermesse: kermesses::Model) -> Result<FundraisingProgress, DbErr> {
+    let total_raised = kermesse_totals(conn, &[kermesse.id])
+        .await?
+        .remove(&kermesse.id)
+        .unwrap_or_default()
+        .total_raised();
+
+    let progress_percentage = progress_percentage(total_raised, kermesse.financial_goal);
+
+    Ok(FundraisingProgress {
+        kermesse_id: kermesse.id,
+        name: kermesse.name,
+        beneficiary_name: kermesse.beneficiary_name,
+        total_raised,
+        financial_goal: kermesse.financial_goal,
+        progress_percentage,
+    })
+}
+
+async fn load_progress(conn: &DatabaseConnection, kermesse_id: i32) -> Result<FundraisingProgress, HttpResponse> {
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return Err(HttpResponse::NotFound().body("Kermesse not found")),
+        Err(_) => return Err(HttpResponse::InternalServerError().body("Database error")),
+    };
+
+    fundraising_progress(conn, kermesse)
+        .await
+        .map_err(|_| HttpResponse::InternalServerError().body("Database error"))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// "Bs. 1.250 de Bs. 5.000 · 25%", or just the amount raised when there is no goal.
+fn summary(progress: &FundraisingProgress) -> String {
+    let raised = progress.total_raised.round_dp(0);
+    match (progress.financial_goal, progress.progress_percentage) {
+        (Some(goal), Some(percentage)) => {
+            format!("Bs. {} de Bs. {} · {:.0}%", raised, goal.round_dp(0), percentage)
+        }
+        _ => format!("Bs. {} recaudados", raised),
+    }
+}
+
+/// Share of the bar to fill, between 0 and 1.
+fn fill_ratio(progress: &FundraisingProgress) -> f64 {
+    progress.progress_percentage.map(|p| (p / 100.0).clamp(0.0, 1.0)).unwrap_or(0.0)
+}
+
+pub async fn get_progress(path: web::Path<i32>, data: web::Data<AppState>) -> impl Responder {
+    match load_progress(&data.conn, path.into_inner()).await {
+        Ok(progress) => HttpResponse::Ok()
+            .insert_header(("Cache-Control", CACHE_CONTROL))
+            .json(progress),
+        Err(resp) => resp,
+    }
+}
+
+/// Progress badge for `<img>` tags on other websites.
+pub async fn get_progress_badge(path: web::Path<i32>, data: web::Data<AppState>) -> impl Responder {
+    let progress = match load_progress(&data.conn, path.into_inner()).await {
+        Ok(progress) => progress,
+        Err(resp) => return resp,
+    };
+
+    let mut name: String = progress.name.chars().take(BADGE_NAME_CHARS).collect();
+    if progress.name.chars().count() > BADGE_NAME_CHARS {
+        name.push('…');
+    }
+    let label = escape(&format!("{}: {}", progress.name, summary(&progress)));
+
+    let svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="320" height="68" viewBox="0 0 320 68" role="img" aria-label="{label}">
+<title>{label}</title>
+<rect x="0.5" y="0.5" width="319" height="67" rx="10" fill="#ffffff" stroke="#e5e7eb"/>
+<g font-family="Helvetica,Arial,sans-serif">
+<text x="12" y="22" font-size="13" font-weight="bold" fill="#1f2937">{name}</text>
+<rect x="12" y="32" width="{bar}" height="10" rx="5" fill="#e5e7eb"/>
+<rect x="12" y="32" width="{fill:.1}" height="10" rx="5" fill="#16a34a"/>
+<text x="12" y="58" font-size="11" fill="#4b5563">{summary}</text>
+</g>
+</svg>"##,
+        label = label,
+        name = escape(&name),
+        bar = BADGE_BAR_WIDTH,
+        fill = BADGE_BAR_WIDTH * fill_ratio(&progress),
+        summary = escape(&summary(&progress)),
+    );
+
+    HttpResponse::Ok()
+        .content_type("image/svg+xml; charset=utf-8")
+        .insert_header(("Cache-Control", CACHE_CONTROL))
+        .body(svg)
+}
+
+/// Self-contained widget meant for an `<iframe>`, linking back to the kermesse page.
+pub async fn get_progress_embed(path: web::Path<i32>, data: web::Data<AppState>) -> impl Responder {
+    let progress = match load_progress(&data.conn, path.into_inner()).await {
+        Ok(progress) => progress,
+        Err(resp) => return resp,
+    };
+
+    let frontend_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://127.0.0.1:8000".into());
+    let html = format!(
+        r##"<!DOCTYPE html>
+<html lang="es">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{name}</title>
+<style>
+body {{ margin: 0; font-family: Helvetica, Arial, sans-serif; color: #1f2937; }}
+.card {{ box-sizing: border-box; border: 1px solid #e5e7eb; border-radius: 12px; padding: 14px 16px; background: #fff; }}
+.name {{ font-weight: bold; font-size: 15px; margin: 0 0 2px; }}
+.beneficiary {{ font-size: 12px; color: #6b7280; margin: 0 0 10px; }}
+.bar {{ background: #e5e7eb; border-radius: 999px; height: 12px; overflow: hidden; }}
+.fill {{ background: #16a34a; height: 12px; border-radius: 999px; }}
+.summary {{ font-size: 13px; color: #4b5563; margin: 8px 0 0; }}
+a {{ display: inline-block; margin-top: 10px; font-size: 13px; font-weight: bold; color: #ea580c; text-decoration: none; }}
+</style>
+</head>
+<body>
+<div class="card">
+<p class="name">{name}</p>
+<p class="beneficiary">A beneficio de {beneficiary}</p>
+<div class="bar"><div class="fill" style="width: {fill:.1}%"></div></div>
+<p class="summary">{summary}</p>
+<a href="{link}" target="_blank" rel="noopener">Colaborar →</a>
+</div>
+</body>
+</html>"##,
+        name = escape(&progress.name),
+        beneficiary = escape(&progress.beneficiary_name),
+        fill = fill_ratio(&progress) * 100.0,
+        summary = escape(&summary(&progress)),
+        link = escape(&format!("{}/kermesses/{}", frontend_url.trim_end_matches('/'), progress.kermesse_id)),
+    );
+
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .insert_header(("Cache-Control", CACHE_CONTROL))
+        .body(html)
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/progress")
+            .route(web::get().to(get_progress)),
+    )
+    .service(
+        web::resource("/kermesses/{id}/progress/badge.svg")
+            .route(web::get().to(get_progress_badge)),
+    )
+    .service(
+        web::resource("/kermesses/{id}/progress/embed")
+            .route(web::get().to(get_progress_embed)),
+    );
+}
@@ -0,0 +1,192 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{DateTime, FixedOffset};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{promo_codes, prelude::*};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+
+#[derive(Deserialize)]
+pub struct CreatePromoCodeRequest {
+    pub code: String,
+    pub discount_type: String, // "PERCENTAGE" or "FIXED"
+    pub value: rust_decimal::Decimal,
+    pub max_uses: Option<i32>,
+    pub expires_at: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Serialize)]
+pub struct PromoCodeValidation {
+    pub code: String,
+    pub discount_type: String,
+    pub value: rust_decimal::Decimal,
+}
+
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+/// Discount a promo code grants on the given dish subtotal, never above it.
+pub fn discount_for(promo: &promo_codes::Model, subtotal: rust_decimal::Decimal) -> rust_decimal::Decimal {
+    let discount = match promo.discount_type.as_str() {
+        "PERCENTAGE" => (subtotal * promo.value / rust_decimal::Decimal::from(100)).round_dp(2),
+        _ => promo.value,
+    };
+    discount.min(subtotal)
+}
+
+/// Looks up an active, unexpired code of the kermesse that still has uses left.
+pub async fn find_usable<C: ConnectionTrait>(
+    conn: &C,
+    kermesse_id: i32,
+    code: &str,
+) -> Result<Option<promo_codes::Model>, DbErr> {
+    let promo = PromoCodes::find()
+        .filter(promo_codes::Column::KermesseId.eq(kermesse_id))
+        .filter(promo_codes::Column::Code.eq(normalize_code(code)))
+        .filter(promo_codes::Column::Active.eq(true))
+        .one(conn)
+        .await?;
+
+    Ok(promo.filter(|p| {
+        let not_expired = p.expires_at.map(|e| e > chrono::Utc::now()).unwrap_or(true);
+        let has_uses = p.max_uses.map(|max| p.times_used < max).unwrap_or(true);
+        not_expired && has_uses
+    }))
+}
+
+/// Atomically consumes one use; returns false if the code ran out meanwhile.
+pub async fn redeem<C: ConnectionTrait>(conn: &C, promo_id: i32) -> Result<bool, DbErr> {
+    let res = PromoCodes::update_many()
+        .col_expr(promo_codes::Column::TimesUsed, Expr::col(promo_codes::Column::TimesUsed).add(1))
+        .filter(promo_codes::Column::Id.eq(promo_id))
+        .filter(
+            Condition::any()
+                .add(promo_codes::Column::MaxUses.is_null())
+                .add(Expr::col(promo_codes::Column::TimesUsed).lt(Expr::col(promo_codes::Column::MaxUses))),
+        )
+        .exec(conn)
+        .await?;
+
+    Ok(res.rows_affected == 1)
+}
+
+pub async fn create_promo_code(
+    path: web::Path<i32>,
+    req: web::Json<CreatePromoCodeRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can create promo codes");
+    }
+
+    let code = normalize_code(&req.code);
+    if code.is_empty() {
+        return HttpResponse::BadRequest().body("Code cannot be empty");
+    }
+
+    match req.discount_type.as_str() {
+        "PERCENTAGE" if req.value <= rust_decimal::Decimal::ZERO || req.value > rust_decimal::Decimal::from(100) => {
+            return HttpResponse::BadRequest().body("Percentage must be between 0 and 100");
+        }
+        "FIXED" if req.value <= rust_decimal::Decimal::ZERO => {
+            return HttpResponse::BadRequest().body("Fixed discount must be positive");
+        }
+        "PERCENTAGE" | "FIXED" => {}
+        _ => return HttpResponse::BadRequest().body("discount_type must be PERCENTAGE or FIXED"),
+    }
+
+    let promo = promo_codes::ActiveModel {
+        kermesse_id: Set(kermesse_id),
+        code: Set(code),
+        discount_type: Set(req.discount_type.clone()),
+        value: Set(req.value),
+        max_uses: Set(req.max_uses),
+        expires_at: Set(req.expires_at),
+        active: Set(true),
+        ..Default::default()
+    };
+
+    match promo.insert(conn).await {
+        Ok(model) => HttpResponse::Created().json(model),
+        Err(e) => HttpResponse::BadRequest().body(format!("Failed to create promo code: {}", e)),
+    }
+}
+
+pub async fn list_promo_codes(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can view promo codes");
+    }
+
+    match PromoCodes::find()
+        .filter(promo_codes::Column::KermesseId.eq(kermesse_id))
+        .order_by_desc(promo_codes::Column::CreatedAt)
+        .all(conn)
+        .await
+    {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ValidateQuery {
+    pub code: String,
+}
+
+pub async fn validate_promo_code(
+    path: web::Path<i32>,
+    query: web::Query<ValidateQuery>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+
+    match find_usable(&data.conn, kermesse_id, &query.code).await {
+        Ok(Some(p)) => HttpResponse::Ok().json(PromoCodeValidation {
+            code: p.code,
+            discount_type: p.discount_type,
+            value: p.value,
+        }),
+        Ok(None) => HttpResponse::NotFound().body("Invalid or expired promo code"),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/promo-codes")
+            .route(web::get().to(list_promo_codes))
+            .route(web::post().to(create_promo_code)),
+    )
+    .service(
+        web::resource("/kermesses/{id}/promo-codes/validate")
+            .route(web::get().to(validate_promo_code)),
+    );
+}
@@ -0,0 +1,362 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QuerySelect,
+    Set, TransactionTrait,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::entity::{dishes, refunds, sale_items, sales, prelude::*};
+use crate::api::sales::{record_status, COLLECTED_STATUSES};
+use crate::api::time_slots;
+use crate::events::{EventKind, KermesseEvent};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+
+#[derive(Deserialize)]
+pub struct RefundItemRequest {
+    pub sale_item_id: i32,
+    pub quantity: i32,
+}
+
+#[derive(Deserialize)]
+pub struct CreateRefundRequest {
+    pub items: Option<Vec<RefundItemRequest>>, // None refunds everything not yet refunded
+    pub reason: String,
+    pub refund_method: String, // "CASH", "QR", "TRANSFER"
+    #[serde(default)]
+    pub restock: bool,
+}
+
+/// Refunds issued against collected sales, summed per kermesse.
+pub async fn refunded_by_kermesse<C: ConnectionTrait>(
+    conn: &C,
+    kermesse_ids: &[i32],
+) -> Result<HashMap<i32, rust_decimal::Decimal>, DbErr> {
+    Ok(Refunds::find()
+        .inner_join(Sales)
+        .filter(sales::Column::KermesseId.is_in(kermesse_ids.iter().copied()))
+        .filter(sales::Column::Status.is_in(COLLECTED_STATUSES))
+        .select_only()
+        .column(sales::Column::KermesseId)
+        .column_as(refunds::Column::Amount.sum(), "refunded")
+        .group_by(sales::Column::KermesseId)
+        .into_tuple::<(i32, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .collect())
+}
+
+/// What each line actually cost once the promo discount is spread across the lines in
+/// proportion to their subtotals. The shares add up to the whole discount, so refunding
+/// every line never pays back more than was charged for the items.
+fn net_line_totals(items: &[sale_items::Model], discount: rust_decimal::Decimal) -> HashMap<i32, rust_decimal::Decimal> {
+    let gross = items.iter().fold(rust_decimal::Decimal::ZERO, |acc, i| acc + i.subtotal);
+    let discount = discount.max(rust_decimal::Decimal::ZERO).min(gross);
+    let mut remaining = discount;
+    let mut totals = HashMap::new();
+    for (index, item) in items.iter().enumerate() {
+        // The last line takes the rounding leftovers
+        let share = if index + 1 == items.len() {
+            remaining
+        } else {
+            (discount * item.subtotal / gross).round_dp(2)
+        };
+        remaining -= share;
+        totals.insert(item.id, item.subtotal - share);
+    }
+    totals
+}
+
+/// Refund for `quantity` more units of a line when `already` units were refunded before.
+/// Rounding the cumulative amount keeps a line's refunds adding up to its net total.
+fn line_refund(net_total: rust_decimal::Decimal, line_quantity: i32, already: i32, quantity: i32) -> rust_decimal::Decimal {
+    let up_to = |units: i32| {
+        (net_total * rust_decimal::Decimal::from(units) / rust_decimal::Decimal::from(line_quantity)).round_dp(2)
+    };
+    up_to(already + quantity) - up_to(already)
+}
+
+async fn restock_dish<C: ConnectionTrait>(conn: &C, dish_id: i32, quantity: i32) -> Result<(), DbErr> {
+    Dishes::update_many()
+        .col_expr(
+            dishes::Column::QuantityAvailable,
+            Expr::col(dishes::Column::QuantityAvailable).add(quantity),
+        )
+        .filter(dishes::Column::Id.eq(dish_id))
+        .exec(conn)
+        .await
+        .map(|_| ())
+}
+
+pub async fn create_refund(
+    path: web::Path<i32>,
+    req: web::Json<CreateRefundRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let sale_id = path.into_inner();
+    let conn = &data.conn;
+
+    let txn = match conn.begin().await {
+        Ok(t) => t,
+        Err(_) => return HttpResponse::InternalServerError().body("Failed to start transaction"),
+    };
+
+    // Hold the sale row so concurrent refunds see each other's totals
+    let sale = match Sales::find_by_id(sale_id).lock_exclusive().one(&txn).await {
+        Ok(Some(s)) => s,
+        Ok(None) => return HttpResponse::NotFound().body("Sale not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let kermesse = match Kermesses::find_by_id(sale.kermesse_id).one(&txn).await {
+        Ok(Some(k)) => k,
+        _ => return HttpResponse::InternalServerError().body("Kermesse lookup error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can issue refunds");
+    }
+
+    if !matches!(sale.status.as_str(), "PAID" | "OUT_FOR_DELIVERY" | "DELIVERED" | "PARTIALLY_REFUNDED") {
+        return HttpResponse::BadRequest().body(format!("Cannot refund a sale in status {}", sale.status));
+    }
+
+    let items = match SaleItems::find()
+        .filter(sale_items::Column::SaleId.eq(sale_id))
+        .all(&txn)
+        .await
+    {
+        Ok(i) => i,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let previous = match Refunds::find()
+        .filter(refunds::Column::SaleId.eq(sale_id))
+        .all(&txn)
+        .await
+    {
+        Ok(r) => r,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let mut refunded_qty: HashMap<i32, i32> = HashMap::new();
+    for r in &previous {
+        if let (Some(item_id), Some(qty)) = (r.sale_item_id, r.quantity) {
+            *refunded_qty.entry(item_id).or_insert(0) += qty;
+        }
+    }
+    let already_refunded = previous.iter().fold(rust_decimal::Decimal::ZERO, |acc, r| acc + r.amount);
+
+    // (sale_item_id, quantity, amount) per refund row, (dish_id, quantity) to re-credit
+    let mut lines = Vec::new();
+    let mut restock_lines: Vec<(i32, i32)> = Vec::new();
+    let net_totals = net_line_totals(&items, sale.discount_amount);
+    match &req.items {
+        Some(requested) => {
+            for line in requested {
+                let item = match items.iter().find(|i| i.id == line.sale_item_id) {
+                    Some(i) => i,
+                    None => return HttpResponse::BadRequest().body(format!("Item {} does not belong to sale {}", line.sale_item_id, sale_id)),
+                };
+                let already = refunded_qty.get(&item.id).copied().unwrap_or(0);
+                let remaining = item.quantity - already;
+                if line.quantity <= 0 || line.quantity > remaining {
+                    return HttpResponse::BadRequest().body(format!("Invalid refund quantity for item {}. Refundable: {}", item.id, remaining));
+                }
+                let amount = line_refund(net_totals[&item.id], item.quantity, already, line.quantity);
+                // Count this line too in case the same item is listed twice
+                *refunded_qty.entry(item.id).or_insert(0) += line.quantity;
+                lines.push((Some(item.id), Some(line.quantity), amount));
+                restock_lines.push((item.dish_id, line.quantity));
+            }
+        }
+        None => {
+            let remaining_amount = sale.total_amount - already_refunded;
+            if remaining_amount <= rust_decimal::Decimal::ZERO {
+                return HttpResponse::BadRequest().body("Sale is already fully refunded");
+            }
+            lines.push((None, None, remaining_amount));
+            restock_lines = items
+                .iter()
+                .map(|i| (i.dish_id, i.quantity - refunded_qty.get(&i.id).copied().unwrap_or(0)))
+                .collect();
+        }
+    }
+
+    let new_refunded = lines.iter().fold(already_refunded, |acc, l| acc + l.2);
+    if new_refunded > sale.total_amount {
+        return HttpResponse::BadRequest().body("Refund exceeds sale total");
+    }
+
+    let mut restocked_dishes = Vec::new();
+    if req.restock {
+        for (dish_id, qty) in restock_lines.into_iter().filter(|(_, q)| *q > 0) {
+            restocked_dishes.push(dish_id);
+            if restock_dish(&txn, dish_id, qty).await.is_err() {
+                return HttpResponse::InternalServerError().body("Failed to update dish stock");
+            }
+        }
+    }
+
+    let mut created = Vec::new();
+    for (sale_item_id, quantity, amount) in lines {
+        let refund = refunds::ActiveModel {
+            sale_id: Set(sale_id),
+            sale_item_id: Set(sale_item_id),
+            quantity: Set(quantity),
+            amount: Set(amount),
+            reason: Set(req.reason.clone()),
+            refund_method: Set(req.refund_method.clone()),
+            restocked: Set(req.restock),
+            created_by: Set(user.id),
+            ..Default::default()
+        };
+        match refund.insert(&txn).await {
+            Ok(r) => created.push(r),
+            Err(_) => return HttpResponse::InternalServerError().body("Failed to record refund"),
+        }
+    }
+
+    let new_status = if new_refunded >= sale.total_amount { "REFUNDED" } else { "PARTIALLY_REFUNDED" };
+    // A fully refunded order no longer needs its place in the slot
+    let releases_slot = new_status == "REFUNDED" && !time_slots::RELEASED_STATUSES.contains(&sale.status.as_str());
+    if let Some(slot_id) = sale.time_slot_id.filter(|_| releases_slot) {
+        if time_slots::release(&txn, slot_id).await.is_err() {
+            return HttpResponse::InternalServerError().body("Failed to release time slot");
+        }
+    }
+    let (kermesse_id, buyer_id) = (sale.kermesse_id, sale.buyer_id);
+    let mut sale: sales::ActiveModel = sale.into();
+    sale.status = Set(new_status.to_string());
+    if sale.update(&txn).await.is_err() {
+        return HttpResponse::InternalServerError().body("Failed to update sale status");
+    }
+    if record_status(&txn, sale_id, new_status, Some(user.id)).await.is_err() {
+        return HttpResponse::InternalServerError().body("Failed to record sale history");
+    }
+
+    match txn.commit().await {
+        Ok(_) => {
+            data.events.publish(KermesseEvent::new(
+                kermesse_id,
+                buyer_id,
+                EventKind::SaleStatusChanged { sale_id, status: new_status.to_string() },
+            ));
+            if let Ok(dishes) = Dishes::find().filter(dishes::Column::Id.is_in(restocked_dishes)).all(conn).await {
+                for dish in dishes {
+                    data.events.publish(KermesseEvent::stock(dish.kermesse_id, dish.id, dish.quantity_available));
+                }
+            }
+            HttpResponse::Created().json(serde_json::json!({
+                "status": new_status,
+                "total_refunded": new_refunded,
+                "refunds": created,
+            }))
+        }
+        Err(_) => HttpResponse::InternalServerError().body("Failed to commit transaction"),
+    }
+}
+
+pub async fn list_refunds(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let sale_id = path.into_inner();
+    let conn = &data.conn;
+
+    let sale = match Sales::find_by_id(sale_id).one(conn).await {
+        Ok(Some(s)) => s,
+        Ok(None) => return HttpResponse::NotFound().body("Sale not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let kermesse = match Kermesses::find_by_id(sale.kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        _ => return HttpResponse::InternalServerError().body("Kermesse lookup error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can view refunds");
+    }
+
+    match Refunds::find()
+        .filter(refunds::Column::SaleId.eq(sale_id))
+        .all(conn)
+        .await
+    {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/sales/{id}/refunds")
+            .route(web::get().to(list_refunds))
+            .route(web::post().to(create_refund)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn item(id: i32, quantity: i32, subtotal: i64) -> sale_items::Model {
+        sale_items::Model {
+            id,
+            sale_id: 1,
+            dish_id: id,
+            quantity,
+            subtotal: Decimal::from(subtotal),
+            prep_status: "PENDING".to_string(),
+            prep_updated_at: None,
+        }
+    }
+
+    #[test]
+    fn discount_is_spread_across_lines() {
+        let items = [item(1, 3, 35), item(2, 1, 10), item(3, 2, 24)];
+        let totals = net_line_totals(&items, Decimal::from(10));
+        let net: Decimal = totals.values().copied().sum();
+        assert_eq!(net, Decimal::from(59));
+        assert!(totals.values().all(|t| *t > Decimal::ZERO));
+        assert_eq!(net_line_totals(&items, Decimal::ZERO)[&1], Decimal::from(35));
+    }
+
+    #[test]
+    fn refunding_a_discounted_sale_item_by_item_matches_what_was_charged() {
+        // 69 of items, 20 off, 5 of delivery fee and no donation: the sale charged 54
+        let items = [item(1, 3, 35), item(2, 1, 10), item(3, 2, 24)];
+        let (discount, delivery_fee) = (Decimal::from(20), Decimal::from(5));
+        let total_amount = Decimal::from(69) - discount + delivery_fee;
+        let totals = net_line_totals(&items, discount);
+
+        let mut refunded = Decimal::ZERO;
+        for line in &items {
+            for already in 0..line.quantity {
+                let amount = line_refund(totals[&line.id], line.quantity, already, 1);
+                assert!(amount < line.subtotal / Decimal::from(line.quantity));
+                refunded += amount;
+            }
+        }
+        assert_eq!(refunded, total_amount - delivery_fee);
+        assert!(refunded <= total_amount);
+    }
+
+    #[test]
+    fn partial_refunds_of_a_line_add_up_to_its_net_total() {
+        let net_total = Decimal::new(3333, 2);
+        let parts = [
+            line_refund(net_total, 3, 0, 1),
+            line_refund(net_total, 3, 1, 1),
+            line_refund(net_total, 3, 2, 1),
+        ];
+        assert_eq!(parts.iter().copied().sum::<Decimal>(), net_total);
+        assert_eq!(line_refund(net_total, 3, 0, 3), net_total);
+    }
+}
@@ -0,0 +1,437 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::api::collaboration::is_staff;
+use crate::api::dashboard::{dashboard_stats, sales_analytics};
+use crate::api::sales::{stored_receipt, SaleReceipt};
+use crate::entity::{donations, expenses, ingredient_donations, ingredients, kermesses, prelude::*, users};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+use crate::utils::pdf::{Align, Column, PdfDocument, A5};
+use crate::utils::time;
+
+fn money(amount: rust_decimal::Decimal) -> String {
+    format!("Bs. {:.2}", amount)
+}
+
+fn quantity(amount: rust_decimal::Decimal) -> String {
+    amount.normalize().to_string()
+}
+
+/// Timestamps are stored in UTC; reports show them as the kermesse's wall clock.
+fn local_time(t: &DateTimeWithTimeZone, pattern: &str) -> String {
+    t.with_timezone(&time::kermesse_offset()).format(pattern).to_string()
+}
+
+async fn closing_report(conn: &DatabaseConnection, kermesse: &kermesses::Model) -> Result<PdfDocument, DbErr> {
+    let stats = dashboard_stats(conn, kermesse).await?;
+
+    // Net of refunds, the same figures as the sales analytics
+    let analytics = sales_analytics(conn, kermesse.id).await?;
+
+    let ingredient_list = Ingredients::find()
+        .filter(ingredients::Column::KermesseId.eq(kermesse.id))
+        .order_by_asc(ingredients::Column::Name)
+        .all(conn)
+        .await?;
+    let ingredient_gifts = IngredientDonations::find()
+        .inner_join(Ingredients)
+        .filter(ingredients::Column::KermesseId.eq(kermesse.id))
+        .order_by_asc(ingredient_donations::Column::CreatedAt)
+        .all(conn)
+        .await?;
+
+    let donation_list = Donations::find()
+        .filter(donations::Column::KermesseId.eq(kermesse.id))
+        .filter(donations::Column::Status.eq("CONFIRMED"))
+        .order_by_desc(donations::Column::Amount)
+        .all(conn)
+        .await?;
+
+    let expense_list = Expenses::find()
+        .filter(expenses::Column::KermesseId.eq(kermesse.id))
+        .order_by_asc(expenses::Column::SpentOn)
+        .all(conn)
+        .await?;
+
+    let donor_names: HashMap<i32, String> = Users::find()
+        .filter(users::Column::Id.is_in(ingredient_gifts.iter().map(|d| d.user_id)))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u.full_name))
+        .collect();
+
+    let expenses_total = expense_list.iter().fold(rust_decimal::Decimal::ZERO, |acc, e| acc + e.amount);
+    let net_result = stats.total_raised - expenses_total;
+
+    let generated = time::now().format("%d/%m/%Y %H:%M");
+    let mut pdf = PdfDocument::new(&format!("{} - Informe de cierre generado el {}", kermesse.name, generated));
+    let width = pdf.content_width();
+
+    pdf.title(&format!("Informe de cierre: {}", kermesse.name));
+    let place = kermesse.city.clone().unwrap_or_default();
+    pdf.paragraph(
+        &format!(
+            "Fecha del evento: {}{}. Beneficiario: {}.",
+            kermesse.event_date.format("%d/%m/%Y"),
+            if place.is_empty() { String::new() } else { format!(", {}", place) },
+            kermesse.beneficiary_name
+        ),
+        10.0,
+    );
+    pdf.paragraph(&kermesse.beneficiary_reason, 10.0);
+
+    pdf.heading("Resumen financiero");
+    pdf.key_value("Ventas (sin donaciones extra)", &money(stats.sales_revenue), false);
+    pdf.key_value("Donaciones", &money(stats.donations_total), false);
+    pdf.key_value("Reembolsos", &format!("- {}", money(stats.total_refunded)), false);
+    pdf.key_value("Total recaudado", &money(stats.total_raised), true);
+    pdf.key_value("Gastos", &format!("- {}", money(expenses_total)), false);
+    pdf.key_value("Resultado neto", &money(net_result), true);
+    if let Some(goal) = stats.financial_goal {
+        pdf.key_value(
+            "Meta de recaudación",
+            &format!("{} ({:.0}% alcanzado)", money(goal), stats.progress_percentage),
+            false,
+        );
+        pdf.progress_bar(stats.progress_percentage / 100.0);
+    }
+    pdf.key_value(
+        "Pedidos",
+        &format!("{} en total, {} entregados", stats.total_orders, stats.delivered_orders),
+        false,
+    );
+
+    pdf.heading("Ventas por plato");
+    let dish_rows: Vec<_> = analytics.dishes.into_iter().filter(|d| d.units_sold > 0).collect();
+    if dish_rows.is_empty() {
+        pdf.paragraph("No se registraron ventas cobradas.", 10.0);
+    } else {
+        pdf.table(
+            &[
+                Column { title: "Plato", width: width * 0.6, align: Align::Left },
+                Column { title: "Cantidad", width: width * 0.15, align: Align::Right },
+                Column { title: "Monto", width: width * 0.25, align: Align::Right },
+            ],
+            &dish_rows
+                .into_iter()
+                .map(|d| vec![d.name, d.units_sold.to_string(), money(d.revenue)])
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    pdf.heading("Ventas por método de pago");
+    if analytics.payment_methods.is_empty() {
+        pdf.paragraph("No se registraron ventas cobradas.", 10.0);
+    } else {
+        pdf.table(
+            &[
+                Column { title: "Método", width: width * 0.6, align: Align::Left },
+                Column { title: "Pedidos", width: width * 0.15, align: Align::Right },
+                Column { title: "Monto", width: width * 0.25, align: Align::Right },
+            ],
+            &analytics
+                .payment_methods
+                .into_iter()
+                .map(|m| vec![m.method, m.orders.to_string(), money(m.revenue)])
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    pdf.heading("Cobertura de ingredientes");
+    pdf.paragraph(&format!("Cobertura total: {:.0}%", stats.ingredient_coverage_percentage), 10.0);
+    if !ingredient_list.is_empty() {
+        let mut donated: HashMap<i32, rust_decimal::Decimal> = HashMap::new();
+        for gift in &ingredient_gifts {
+            *donated.entry(gift.ingredient_id).or_default() += gift.quantity_donated;
+        }
+        pdf.table(
+            &[
+                Column { title: "Ingrediente", width: width * 0.5, align: Align::Left },
+                Column { title: "Necesario", width: width * 0.25, align: Align::Right },
+                Column { title: "Donado", width: width * 0.25, align: Align::Right },
+            ],
+            &ingredient_list
+                .iter()
+                .map(|i| {
+                    let given = donated.get(&i.id).copied().unwrap_or_default();
+                    vec![
+                        i.name.clone(),
+                        format!("{} {}", quantity(i.quantity_needed), i.unit),
+                        format!("{} {}", quantity(given), i.unit),
+                    ]
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    pdf.heading("Donantes");
+    if donation_list.is_empty() && ingredient_gifts.is_empty() {
+        pdf.paragraph("No se registraron donaciones.", 10.0);
+    }
+    if !donation_list.is_empty() {
+        pdf.table(
+            &[
+                Column { title: "Donante", width: width * 0.55, align: Align::Left },
+                Column { title: "Fecha", width: width * 0.2, align: Align::Left },
+                Column { title: "Monto", width: width * 0.25, align: Align::Right },
+            ],
+            &donation_list
+                .iter()
+                .map(|d| {
+                    let donor = if d.is_anonymous { "Anónimo".to_string() } else { d.donor_name.clone().unwrap_or_default() };
+                    vec![donor, local_time(&d.created_at, "%d/%m/%Y"), money(d.amount)]
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+    if !ingredient_gifts.is_empty() {
+        let units: HashMap<i32, (&str, &str)> = ingredient_list
+            .iter()
+            .map(|i| (i.id, (i.name.as_str(), i.unit.as_str())))
+            .collect();
+        pdf.table(
+            &[
+                Column { title: "Donante", width: width * 0.45, align: Align::Left },
+                Column { title: "Ingrediente", width: width * 0.3, align: Align::Left },
+                Column { title: "Cantidad", width: width * 0.25, align: Align::Right },
+            ],
+            &ingredient_gifts
+                .iter()
+                .map(|g| {
+                    let (name, unit) = units.get(&g.ingredient_id).copied().unwrap_or_default();
+                    vec![
+                        donor_names.get(&g.user_id).cloned().unwrap_or_default(),
+                        name.to_string(),
+                        format!("{} {}", quantity(g.quantity_donated), unit),
+                    ]
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    pdf.heading("Gastos");
+    if expense_list.is_empty() {
+        pdf.paragraph("No se registraron gastos.", 10.0);
+    } else {
+        pdf.table(
+            &[
+                Column { title: "Fecha", width: width * 0.17, align: Align::Left },
+                Column { title: "Categoría", width: width * 0.18, align: Align::Left },
+                Column { title: "Descripción", width: width * 0.45, align: Align::Left },
+                Column { title: "Monto", width: width * 0.2, align: Align::Right },
+            ],
+            &expense_list
+                .iter()
+                .map(|e| {
+                    vec![
+                        e.spent_on.format("%d/%m/%Y").to_string(),
+                        e.category.clone(),
+                        e.description.clone(),
+                        money(e.amount),
+                    ]
+                })
+                .collect::<Vec<_>>(),
+        );
+        pdf.key_value("Total gastos", &money(expenses_total), true);
+    }
+
+    Ok(pdf)
+}
+
+/// Printable summary for the beneficiary once the kermesse is over.
+pub async fn get_closing_report(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can download the closing report");
+    }
+
+    let report = match closing_report(conn, &kermesse).await {
+        Ok(report) => report,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    match report.finish() {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type("application/pdf")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"informe-{}.pdf\"", kermesse.slug),
+            ))
+            .body(bytes),
+        Err(e) => {
+            log::error!("Closing report for kermesse {} failed: {}", kermesse_id, e);
+            HttpResponse::InternalServerError().body("Failed to build report")
+        }
+    }
+}
+
+fn receipt_pdf(receipt: &SaleReceipt, ordered_at: &DateTimeWithTimeZone, kermesse: &kermesses::Model) -> PdfDocument {
+    let mut pdf = PdfDocument::with_page(&format!("{} - Orden #{}", kermesse.name, receipt.id), A5, 36.0);
+    let width = pdf.content_width();
+
+    pdf.centered("Comprobante de Pedido", 16.0, true);
+    pdf.centered(&format!("Orden #{}", receipt.id), 11.0, false);
+
+    pdf.heading(&kermesse.name);
+    let mut event = kermesse.event_date.format("%d/%m/%Y").to_string();
+    if let (Some(start), Some(end)) = (&kermesse.start_time, &kermesse.end_time) {
+        event.push_str(&format!(", {} - {}", start, end));
+    }
+    if let Some(city) = &kermesse.city {
+        event.push_str(&format!(", {}", city));
+    }
+    pdf.key_value("Evento", &event, false);
+    pdf.key_value("Cliente", &receipt.customer_name, false);
+    pdf.key_value("Fecha del pedido", &local_time(ordered_at, "%d/%m/%Y %H:%M"), false);
+
+    pdf.table(
+        &[
+            Column { title: "Plato", width: width * 0.46, align: Align::Left },
+            Column { title: "Cant.", width: width * 0.12, align: Align::Right },
+            Column { title: "Precio", width: width * 0.21, align: Align::Right },
+            Column { title: "Subtotal", width: width * 0.21, align: Align::Right },
+        ],
+        &receipt
+            .items
+            .iter()
+            .map(|i| vec![i.dish_name.clone(), i.quantity.to_string(), money(i.unit_price), money(i.subtotal)])
+            .collect::<Vec<_>>(),
+    );
+
+    pdf.key_value("Subtotal", &money(receipt.subtotal), false);
+    if receipt.discount_amount > rust_decimal::Decimal::ZERO {
+        let label = format!("Descuento {}", receipt.promo_code.clone().unwrap_or_default());
+        pdf.key_value(label.trim(), &format!("- {}", money(receipt.discount_amount)), false);
+    }
+    if receipt.delivery_fee > rust_decimal::Decimal::ZERO {
+        let label = format!("Envío {}", receipt.delivery_zone.clone().unwrap_or_default());
+        pdf.key_value(label.trim(), &money(receipt.delivery_fee), false);
+    }
+    if receipt.extra_donation > rust_decimal::Decimal::ZERO {
+        pdf.key_value("Donación extra", &money(receipt.extra_donation), false);
+    }
+    pdf.key_value("Total", &money(receipt.total_amount), true);
+
+    pdf.heading("Pago y entrega");
+    let payment = match receipt.payment_method.as_str() {
+        "QR" => "Pago QR",
+        "CASH" => "Efectivo",
+        other => other,
+    };
+    let status = match receipt.status.as_str() {
+        "PENDING" | "PENDING_PAYMENT" => "pendiente",
+        "PAID" => "pagado",
+        "OUT_FOR_DELIVERY" => "pagado, en camino",
+        "DELIVERED" => "pagado, entregado",
+        "CANCELLED" => "cancelado",
+        "REFUNDED" => "reembolsado",
+        "PARTIALLY_REFUNDED" => "reembolso parcial",
+        other => other,
+    };
+    pdf.key_value("Pago", &format!("{} ({})", payment, status), false);
+    let delivery = match receipt.delivery_method.as_str() {
+        "DELIVERY" => "Delivery",
+        "PICKUP" => "Recojo en el lugar",
+        "EAT_HERE" => "Comer en el evento",
+        other => other,
+    };
+    pdf.key_value("Entrega", delivery, false);
+    if let Some(slot) = &receipt.time_slot {
+        pdf.key_value("Horario", slot, false);
+    }
+    if let Some(reference) = &receipt.payment_reference {
+        pdf.key_value("Referencia", reference, false);
+    }
+
+    if let Some(code) = &receipt.pickup_code {
+        pdf.heading("Código de Recojo");
+        pdf.qr_code(code, 120.0);
+        pdf.centered(code, 18.0, true);
+        pdf.centered("Muestra este código en el puesto para recoger tu pedido", 8.0, false);
+    }
+
+    pdf
+}
+
+#[derive(Deserialize)]
+pub struct ReceiptQuery {
+    /// The order's tracking token, so guests can open their receipt from a plain link.
+    pub token: Option<String>,
+}
+
+/// Receipt of a sale as PDF, for guests holding the tracking token or signed-in users.
+pub async fn get_receipt_pdf(
+    path: web::Path<i32>,
+    query: web::Query<ReceiptQuery>,
+    user: Option<AuthenticatedUser>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let sale_id = path.into_inner();
+    let conn = &data.conn;
+
+    let (sale, kermesse) = match Sales::find_by_id(sale_id).find_also_related(Kermesses).one(conn).await {
+        Ok(Some((s, Some(k)))) => (s, k),
+        Ok(_) => return HttpResponse::NotFound().body("Sale not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let by_link = match (&query.token, &sale.tracking_token) {
+        (Some(given), Some(expected)) => given == expected,
+        _ => false,
+    };
+    if !by_link {
+        let user = match user {
+            Some(u) => u,
+            None => return HttpResponse::Unauthorized().body("No valid token found"),
+        };
+        if sale.buyer_id != Some(user.id) && !is_staff(conn, &kermesse, user.id).await {
+            return HttpResponse::Forbidden().body("Access denied");
+        }
+    }
+
+    let ordered_at = sale.created_at;
+    let receipt = match stored_receipt(conn, sale, &kermesse).await {
+        Ok(r) => r,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    match receipt_pdf(&receipt, &ordered_at, &kermesse).finish() {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type("application/pdf")
+            .insert_header(("Content-Disposition", format!("inline; filename=\"pedido-{}.pdf\"", receipt.id)))
+            .insert_header(("Cache-Control", "no-store"))
+            .body(bytes),
+        Err(e) => {
+            log::error!("Receipt for sale {} failed: {}", receipt.id, e);
+            HttpResponse::InternalServerError().body("Failed to build receipt")
+        }
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/report.pdf")
+            .route(web::get().to(get_closing_report)),
+    )
+    .service(
+        web::resource("/sales/{id}/receipt.pdf")
+            .route(web::get().to(get_receipt_pdf)),
+    );
+}
@@ -1,12 +1,17 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, TransactionTrait, QueryOrder,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::entity::{dishes, kermesses, sale_items, sales, prelude::*};
+use crate::entity::{dishes, kermesses, refunds, sale_items, sale_status_history, sales, prelude::*};
+use crate::api::{delivery_zones, idempotency, promo_codes, time_slots};
+use crate::api::collaboration::{collaborator_role, is_staff};
+use crate::events::{EventKind, KermesseEvent};
 use crate::state::AppState;
 use crate::utils::auth::AuthenticatedUser;
+use crate::utils::time;
 
 #[derive(Serialize, Deserialize)]
 pub struct SaleItemRequest {
@@ -21,8 +26,17 @@ pub struct CreateSaleRequest {
     pub items: Vec<SaleItemRequest>,
     pub delivery_method: String, // "PICKUP" or "DELIVERY"
     pub delivery_address: Option<String>,
+    pub delivery_neighborhood: Option<String>,
+    pub delivery_city: Option<String>,
     pub contact_phone: Option<String>,
     pub payment_method: String, // "QR" or "CASH"
+    pub promo_code: Option<String>,
+    pub extra_donation: Option<rust_decimal::Decimal>,
+    pub time_slot_id: Option<i32>,
+    pub delivery_zone_id: Option<i32>,
+    pub pos_mode: Option<bool>,                        // walk-in sale rung up by a SELLER at the stand
+    pub amount_tendered: Option<rust_decimal::Decimal>, // cash handed over, POS only
+    pub client_key: Option<String>,                     // generated by the POS so retries are not recorded twice
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -39,61 +53,268 @@ pub struct SaleReceipt {
     pub kermesse_name: String,
     pub event_date: String,
     pub customer_name: String,
+    pub subtotal: rust_decimal::Decimal,
+    pub promo_code: Option<String>,
+    pub discount_amount: rust_decimal::Decimal,
+    pub delivery_zone: Option<String>,
+    pub delivery_fee: rust_decimal::Decimal,
+    pub extra_donation: rust_decimal::Decimal,
     pub total_amount: rust_decimal::Decimal,
     pub status: String,
     pub payment_method: String,
     pub delivery_method: String,
     pub created_at: String,
+    pub payment_reference: Option<String>,
+    pub time_slot: Option<String>, // "HH:MM - HH:MM"
+    pub change_due: Option<rust_decimal::Decimal>,
+    pub pickup_code: Option<String>,
+    pub tracking_token: Option<String>, // public link: GET /orders/track/{token}
     pub items: Vec<SaleItemReceipt>,
 }
 
+/// Characters of pickup codes: no 0/O or 1/I, so they can be read out loud.
+const PICKUP_CODE_ALPHABET: &[u8; 32] = b"23456789ABCDEFGHJKLMNPQRSTUVWXYZ";
+const PICKUP_CODE_LEN: usize = 8;
+
+/// Short random code the buyer shows (or has scanned) to collect the order.
+fn new_pickup_code() -> String {
+    uuid::Uuid::new_v4()
+        .as_bytes()
+        .iter()
+        .enumerate()
+        // Bytes 6 and 8 carry the UUID version and variant bits
+        .filter(|(i, _)| *i != 6 && *i != 8)
+        .map(|(_, b)| b)
+        .take(PICKUP_CODE_LEN)
+        .map(|b| PICKUP_CODE_ALPHABET[(*b as usize) % PICKUP_CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+/// Appends a status change to the sale's history. `changed_by` is None when the buyer
+/// or a payment provider caused it. Outside a transaction callers ignore failures: the
+/// status itself has already changed.
+pub async fn record_status<C: ConnectionTrait>(
+    conn: &C,
+    sale_id: i32,
+    status: &str,
+    changed_by: Option<i32>,
+) -> Result<(), DbErr> {
+    sale_status_history::ActiveModel {
+        sale_id: Set(sale_id),
+        status: Set(status.to_string()),
+        changed_by: Set(changed_by),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+    .map(|_| ())
+}
+
+/// Statuses whose money was received, even if it was later (partially) refunded.
+pub const COLLECTED_STATUSES: [&str; 5] = ["PAID", "OUT_FOR_DELIVERY", "DELIVERED", "PARTIALLY_REFUNDED", "REFUNDED"];
+
+/// Collected sales whose items were not fully refunded.
+pub const KEPT_STATUSES: [&str; 4] = ["PAID", "OUT_FOR_DELIVERY", "DELIVERED", "PARTIALLY_REFUNDED"];
+
+/// Status a sale takes when it is sent out or handed over (`progress` is OUT_FOR_DELIVERY or
+/// DELIVERED). A partial refund must stay visible, so that sale keeps its status and how far
+/// it got is only recorded in `out_for_delivery_at` / `delivered_at`.
+pub fn progressed_status<'a>(current: &'a str, progress: &'a str) -> &'a str {
+    if current == "PARTIALLY_REFUNDED" {
+        current
+    } else {
+        progress
+    }
+}
+
+/// Why a sale could not be placed.
+#[derive(Debug)]
+pub enum SaleError {
+    NotFound(String),
+    BadRequest(String),
+    Unauthorized(String),
+    Forbidden(String),
+    /// Stock ran out between ringing the sale up and recording it.
+    OutOfStock(String),
+    Conflict(String),
+    /// A sale with the same client key was already recorded.
+    Duplicate(i32),
+    Internal(String),
+}
+
+impl SaleError {
+    /// Whether the seller has to decide what to do (the order itself was valid).
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, SaleError::OutOfStock(_) | SaleError::Conflict(_))
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            SaleError::NotFound(m)
+            | SaleError::BadRequest(m)
+            | SaleError::Unauthorized(m)
+            | SaleError::Forbidden(m)
+            | SaleError::OutOfStock(m)
+            | SaleError::Conflict(m)
+            | SaleError::Internal(m) => m.clone(),
+            SaleError::Duplicate(id) => format!("Sale already recorded as #{}", id),
+        }
+    }
+
+    pub fn into_response(self) -> HttpResponse {
+        let message = self.message();
+        match self {
+            SaleError::NotFound(_) => HttpResponse::NotFound().body(message),
+            SaleError::BadRequest(_) | SaleError::OutOfStock(_) => HttpResponse::BadRequest().body(message),
+            SaleError::Unauthorized(_) => HttpResponse::Unauthorized().body(message),
+            SaleError::Forbidden(_) => HttpResponse::Forbidden().body(message),
+            SaleError::Conflict(_) | SaleError::Duplicate(_) => HttpResponse::Conflict().body(message),
+            SaleError::Internal(_) => HttpResponse::InternalServerError().body(message),
+        }
+    }
+}
+
+/// Longest `Idempotency-Key` accepted; clients normally send a UUID.
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// With an `Idempotency-Key` header, repeating the request (double tap, retry after a
+/// timeout) returns the original receipt instead of placing a second order.
 pub async fn create_sale(
+    http_req: HttpRequest,
     req: web::Json<CreateSaleRequest>,
     user: Option<AuthenticatedUser>,
     data: web::Data<AppState>,
 ) -> impl Responder {
+    let key = http_req
+        .headers()
+        .get("Idempotency-Key")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|k| !k.is_empty());
+
+    let key = match key {
+        Some(k) if k.len() > MAX_IDEMPOTENCY_KEY_LEN => {
+            return HttpResponse::BadRequest().body("Idempotency-Key is too long");
+        }
+        Some(k) => k,
+        None => {
+            return match place_sale(&data, &req, user.as_ref(), None).await {
+                Ok(receipt) => HttpResponse::Created().json(receipt),
+                Err(e) => e.into_response(),
+            };
+        }
+    };
+
+    let conn = &data.conn;
+    // The same key from another account must not replay someone else's receipt
+    let payload = serde_json::to_vec(&(user.as_ref().map(|u| u.id), &*req)).unwrap_or_default();
+    let hash = idempotency::request_hash(&payload);
+
+    match idempotency::claim(conn, key, &hash).await {
+        Ok(idempotency::Claim::New) => {}
+        Ok(idempotency::Claim::Replay(body)) => {
+            return HttpResponse::Created()
+                .content_type("application/json")
+                .insert_header(("Idempotent-Replayed", "true"))
+                .body(body);
+        }
+        Ok(idempotency::Claim::InProgress) => {
+            return HttpResponse::Conflict().body("A request with this Idempotency-Key is still being processed");
+        }
+        Ok(idempotency::Claim::Mismatch) => {
+            return HttpResponse::UnprocessableEntity().body("Idempotency-Key was already used with a different request");
+        }
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    }
+
+    // The response to replay is stored in the same transaction as the sale
+    match place_sale(&data, &req, user.as_ref(), Some(key)).await {
+        Ok(receipt) => HttpResponse::Created().json(receipt),
+        Err(e) => {
+            let _ = idempotency::release(conn, key).await;
+            e.into_response()
+        }
+    }
+}
+
+/// Validates, prices and records one sale in its own transaction. With an
+/// `idempotency_key`, the receipt is stored for replay before committing.
+pub async fn place_sale(
+    data: &AppState,
+    req: &CreateSaleRequest,
+    user: Option<&AuthenticatedUser>,
+    idempotency_key: Option<&str>,
+) -> Result<SaleReceipt, SaleError> {
     let conn = &data.conn;
 
+    // A queued sale that already reached the server must not be charged twice
+    if let Some(key) = req.client_key.as_deref() {
+        match Sales::find().filter(sales::Column::ClientKey.eq(key)).one(conn).await {
+            Ok(Some(existing)) => return Err(SaleError::Duplicate(existing.id)),
+            Ok(None) => {}
+            Err(_) => return Err(SaleError::Internal("Database error".to_string())),
+        }
+    }
+
     // Start transaction
     let txn = match conn.begin().await {
         Ok(t) => t,
-        Err(_) => return HttpResponse::InternalServerError().body("Failed to start transaction"),
+        Err(_) => return Err(SaleError::Internal("Failed to start transaction".to_string())),
     };
 
     // calculate total and verify dishes
     let mut total_decimal = rust_decimal::Decimal::ZERO;
     let mut sale_items_data = Vec::new();
     let mut receipt_items = Vec::new();
+    let mut stock_events = Vec::new();
 
     // Verify Kermesse exists to get Organizer ID (default seller)
     let kermesse = match Kermesses::find_by_id(req.kermesse_id).one(&txn).await {
          Ok(Some(k)) => k,
-         Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
-         Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+         Ok(None) => return Err(SaleError::NotFound("Kermesse not found".to_string())),
+         Err(_) => return Err(SaleError::Internal("Database error".to_string())),
+    };
+
+    // POS sales are credited to the SELLER (or organizer) who rang them up
+    let pos_seller = if req.pos_mode.unwrap_or(false) {
+        let seller = match &user {
+            Some(u) => u,
+            None => return Err(SaleError::Unauthorized("POS sales require a logged in seller".to_string())),
+        };
+        let is_seller = kermesse.organizer_id == seller.id
+            || collaborator_role(&txn, kermesse.id, seller.id).await.as_deref() == Some("SELLER");
+        if !is_seller {
+            return Err(SaleError::Forbidden("Only sellers of this kermesse can use POS mode".to_string()));
+        }
+        Some(seller.id)
+    } else {
+        None
     };
 
     for item in &req.items {
         let dish = match Dishes::find_by_id(item.dish_id).one(&txn).await {
             Ok(Some(d)) => d,
-            Ok(None) => return HttpResponse::BadRequest().body(format!("Dish {} not found", item.dish_id)),
-            Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+            Ok(None) => return Err(SaleError::BadRequest(format!("Dish {} not found", item.dish_id))),
+            Err(_) => return Err(SaleError::Internal("Database error".to_string())),
         };
 
         if dish.kermesse_id != req.kermesse_id {
-             return HttpResponse::BadRequest().body(format!("Dish {} does not belong to kermesse {}", item.dish_id, req.kermesse_id));
+             return Err(SaleError::BadRequest(format!("Dish {} does not belong to kermesse {}", item.dish_id, req.kermesse_id)));
         }
 
         if dish.quantity_available < item.quantity {
-             return HttpResponse::BadRequest().body(format!("Insufficient stock for dish '{}'. Available: {}, Requested: {}", dish.name, dish.quantity_available, item.quantity));
+             return Err(SaleError::OutOfStock(format!("Insufficient stock for dish '{}'. Available: {}, Requested: {}", dish.name, dish.quantity_available, item.quantity)));
         }
 
         // Decrement quantity available
         let mut dish_active: dishes::ActiveModel = dish.clone().into();
         dish_active.quantity_available = Set(dish.quantity_available - item.quantity);
         if let Err(_) = dish_active.update(&txn).await {
-             return HttpResponse::InternalServerError().body("Failed to update dish stock");
+             return Err(SaleError::Internal("Failed to update dish stock".to_string()));
         }
 
+        stock_events.push(KermesseEvent::stock(dish.kermesse_id, dish.id, dish.quantity_available - item.quantity));
+
         let subtotal = dish.price * rust_decimal::Decimal::from(item.quantity);
         total_decimal += subtotal;
         
@@ -107,29 +328,118 @@ pub async fn create_sale(
         sale_items_data.push((item, dish.price));
     }
 
-    // Determine Seller and Buyer
-    let seller_id = kermesse.organizer_id;
-    let buyer_id = user.map(|u| u.id);
+    // Apply promo code on dish subtotals only
+    let subtotal = total_decimal;
+    let mut discount_amount = rust_decimal::Decimal::ZERO;
+    let mut applied_promo = None;
+    if let Some(code) = req.promo_code.as_deref().filter(|c| !c.trim().is_empty()) {
+        let promo = match promo_codes::find_usable(&txn, req.kermesse_id, code).await {
+            Ok(Some(p)) => p,
+            Ok(None) => return Err(SaleError::BadRequest("Invalid or expired promo code".to_string())),
+            Err(_) => return Err(SaleError::Internal("Database error".to_string())),
+        };
+        match promo_codes::redeem(&txn, promo.id).await {
+            Ok(true) => {}
+            Ok(false) => return Err(SaleError::BadRequest("Promo code has no uses left".to_string())),
+            Err(_) => return Err(SaleError::Internal("Failed to redeem promo code".to_string())),
+        }
+        discount_amount = promo_codes::discount_for(&promo, subtotal);
+        applied_promo = Some(promo);
+    }
+
+    let extra_donation = req.extra_donation.unwrap_or(rust_decimal::Decimal::ZERO);
+    if extra_donation < rust_decimal::Decimal::ZERO {
+        return Err(SaleError::BadRequest("Extra donation cannot be negative".to_string()));
+    }
+
+    // Delivery fee goes on its own line; pickup orders never pay it
+    let mut delivery_fee = rust_decimal::Decimal::ZERO;
+    let mut delivery_zone = None;
+    if req.delivery_method == "DELIVERY" {
+        match delivery_zones::zone_for_order(&txn, &kermesse, req.delivery_zone_id, subtotal - discount_amount).await {
+            Ok(Ok(Some(zone))) => {
+                delivery_fee = zone.fee;
+                delivery_zone = Some(zone);
+            }
+            Ok(Ok(None)) => {}
+            Ok(Err(reason)) => return Err(SaleError::BadRequest(reason)),
+            Err(_) => return Err(SaleError::Internal("Database error".to_string())),
+        }
+    }
+
+    total_decimal = subtotal - discount_amount + delivery_fee + extra_donation;
+
+    let change_due = match (pos_seller, req.amount_tendered) {
+        (Some(_), Some(tendered)) if req.payment_method == "CASH" => {
+            if tendered < total_decimal {
+                return Err(SaleError::BadRequest(format!("Amount tendered is less than the total ({})", total_decimal)));
+            }
+            Some(tendered - total_decimal)
+        }
+        _ => None,
+    };
+
+    // Book the pickup/delivery slot inside the same transaction
+    let mut time_slot_label = None;
+    if let Some(slot_id) = req.time_slot_id {
+        match time_slots::reserve(&txn, slot_id, req.kermesse_id, &req.delivery_method).await {
+            Ok(true) => {}
+            Ok(false) => return Err(SaleError::Conflict("Selected time slot is full or unavailable".to_string())),
+            Err(_) => return Err(SaleError::Internal("Failed to reserve time slot".to_string())),
+        }
+        if let Ok(Some(slot)) = TimeSlots::find_by_id(slot_id).one(&txn).await {
+            time_slot_label = Some(format!("{} - {}", slot.starts_at, slot.ends_at));
+        }
+    }
+
+    // Determine Seller and Buyer; walk-in customers have no account
+    let seller_id = pos_seller.unwrap_or(kermesse.organizer_id);
+    let buyer_id = if pos_seller.is_some() { None } else { user.map(|u| u.id) };
+    // Money changes hands at the stand, so POS sales start out paid
+    let (status, paid_at) = if pos_seller.is_some() {
+        ("PAID", Some(chrono::Utc::now().fixed_offset()))
+    } else {
+        ("PENDING", None)
+    };
 
     let sale = sales::ActiveModel {
         kermesse_id: Set(req.kermesse_id),
         seller_id: Set(seller_id),
         customer_name: Set(req.customer_name.clone()),
         total_amount: Set(total_decimal),
+        promo_code_id: Set(applied_promo.as_ref().map(|p| p.id)),
+        discount_amount: Set(discount_amount),
+        extra_donation: Set(extra_donation),
+        delivery_zone_id: Set(delivery_zone.as_ref().map(|z| z.id)),
+        delivery_fee: Set(delivery_fee),
+        time_slot_id: Set(req.time_slot_id),
         delivery_method: Set(req.delivery_method.clone()),
         delivery_address: Set(req.delivery_address.clone()),
+        delivery_neighborhood: Set(req.delivery_neighborhood.clone()),
+        delivery_city: Set(req.delivery_city.clone()),
         contact_phone: Set(req.contact_phone.clone()),
         buyer_id: Set(buyer_id),
         payment_method: Set(req.payment_method.clone()), // "QR", "CASH"
-        status: Set("PENDING".to_string()),
+        status: Set(status.to_string()),
+        paid_at: Set(paid_at),
+        client_key: Set(req.client_key.clone()),
+        pickup_code: Set(Some(new_pickup_code())),
+        // Long and random: anyone holding it can follow the order without logging in
+        tracking_token: Set(Some(uuid::Uuid::new_v4().simple().to_string())),
+        // Providers confirm payment against this reference via webhook
+        payment_reference: Set(Some(uuid::Uuid::new_v4().simple().to_string())),
         ..Default::default()
     };
 
     let sale = match sale.insert(&txn).await {
         Ok(s) => s,
-        Err(_) => return HttpResponse::InternalServerError().body("Failed to create sale"),
+        Err(_) => return Err(SaleError::Internal("Failed to create sale".to_string())),
     };
 
+    if record_status(&txn, sale.id, &sale.status, pos_seller).await.is_err() {
+        return Err(SaleError::Internal("Failed to record sale history".to_string()));
+    }
+
     // Insert Sale Items
     for (item, price) in sale_items_data {
          let subtotal = price * rust_decimal::Decimal::from(item.quantity);
@@ -141,28 +451,198 @@ pub async fn create_sale(
             ..Default::default()
          };
          if let Err(_) = sale_item.insert(&txn).await {
-             return HttpResponse::InternalServerError().body("Failed to insert sale item");
+             return Err(SaleError::Internal("Failed to insert sale item".to_string()));
          }
     }
 
-    match txn.commit().await {
-        Ok(_) => {
-            let receipt = SaleReceipt {
-                id: sale.id,
-                kermesse_name: kermesse.name,
-                event_date: kermesse.event_date.to_string(),
-                customer_name: sale.customer_name,
-                total_amount: sale.total_amount,
-                status: sale.status,
-                payment_method: sale.payment_method,
-                delivery_method: sale.delivery_method,
-                created_at: sale.created_at.to_string(),
-                items: receipt_items,
-            };
-            HttpResponse::Created().json(receipt)
-        },
-        Err(_) => HttpResponse::InternalServerError().body("Failed to commit transaction"),
+    let created_event = KermesseEvent::new(
+        sale.kermesse_id,
+        sale.buyer_id,
+        EventKind::SaleCreated { sale_id: sale.id, status: sale.status.clone() },
+    );
+    let receipt = SaleReceipt {
+        id: sale.id,
+        kermesse_name: kermesse.name,
+        event_date: kermesse.event_date.to_string(),
+        customer_name: sale.customer_name,
+        subtotal,
+        promo_code: applied_promo.map(|p| p.code),
+        discount_amount: sale.discount_amount,
+        delivery_zone: delivery_zone.map(|z| z.name),
+        delivery_fee: sale.delivery_fee,
+        extra_donation: sale.extra_donation,
+        total_amount: sale.total_amount,
+        status: sale.status,
+        payment_method: sale.payment_method,
+        delivery_method: sale.delivery_method,
+        created_at: sale.created_at.to_string(),
+        payment_reference: sale.payment_reference,
+        time_slot: time_slot_label,
+        change_due,
+        pickup_code: sale.pickup_code,
+        tracking_token: sale.tracking_token,
+        items: receipt_items,
+    };
+
+    if let Some(key) = idempotency_key {
+        let body = serde_json::to_string(&receipt).unwrap_or_default();
+        if idempotency::complete(&txn, key, &body).await.is_err() {
+            return Err(SaleError::Internal("Failed to store idempotent response".to_string()));
+        }
+    }
+
+    if txn.commit().await.is_err() {
+        return Err(SaleError::Internal("Failed to commit transaction".to_string()));
+    }
+
+    data.events.publish(created_event);
+    for event in stock_events {
+        data.events.publish(event);
+    }
+
+    Ok(receipt)
+}
+
+/// Receipt of an existing sale, as it was returned when the sale was placed (minus POS change).
+pub async fn stored_receipt<C: ConnectionTrait>(
+    conn: &C,
+    sale: sales::Model,
+    kermesse: &kermesses::Model,
+) -> Result<SaleReceipt, DbErr> {
+    let items = SaleItems::find()
+        .filter(sale_items::Column::SaleId.eq(sale.id))
+        .order_by_asc(sale_items::Column::Id)
+        .find_also_related(dishes::Entity)
+        .all(conn)
+        .await?;
+
+    let promo_code = match sale.promo_code_id {
+        Some(id) => PromoCodes::find_by_id(id).one(conn).await?.map(|p| p.code),
+        None => None,
+    };
+    let delivery_zone = match sale.delivery_zone_id {
+        Some(id) => DeliveryZones::find_by_id(id).one(conn).await?.map(|z| z.name),
+        None => None,
+    };
+    let time_slot = match sale.time_slot_id {
+        Some(id) => TimeSlots::find_by_id(id).one(conn).await?.map(|s| format!("{} - {}", s.starts_at, s.ends_at)),
+        None => None,
+    };
+
+    let receipt_items: Vec<SaleItemReceipt> = items
+        .into_iter()
+        .map(|(item, dish)| SaleItemReceipt {
+            dish_name: dish.map(|d| d.name).unwrap_or_default(),
+            quantity: item.quantity,
+            unit_price: if item.quantity > 0 { item.subtotal / rust_decimal::Decimal::from(item.quantity) } else { item.subtotal },
+            subtotal: item.subtotal,
+        })
+        .collect();
+    let subtotal = receipt_items.iter().fold(rust_decimal::Decimal::ZERO, |acc, i| acc + i.subtotal);
+
+    Ok(SaleReceipt {
+        id: sale.id,
+        kermesse_name: kermesse.name.clone(),
+        event_date: kermesse.event_date.to_string(),
+        customer_name: sale.customer_name,
+        subtotal,
+        promo_code,
+        discount_amount: sale.discount_amount,
+        delivery_zone,
+        delivery_fee: sale.delivery_fee,
+        extra_donation: sale.extra_donation,
+        total_amount: sale.total_amount,
+        status: sale.status,
+        payment_method: sale.payment_method,
+        delivery_method: sale.delivery_method,
+        created_at: sale.created_at.to_string(),
+        payment_reference: sale.payment_reference,
+        time_slot,
+        change_due: None,
+        pickup_code: sale.pickup_code,
+        tracking_token: sale.tracking_token,
+        items: receipt_items,
+    })
+}
+
+/// Largest number of queued sales accepted in one sync.
+const MAX_BATCH_SIZE: usize = 100;
+
+#[derive(Deserialize)]
+pub struct SaleBatchRequest {
+    pub sales: Vec<CreateSaleRequest>,
+}
+
+#[derive(Serialize)]
+pub struct SaleBatchResult {
+    pub client_key: Option<String>,
+    pub result: String, // "CREATED", "DUPLICATE", "CONFLICT", "REJECTED" or "FAILED" (retry later)
+    pub sale_id: Option<i32>,
+    pub receipt: Option<SaleReceipt>,
+    pub message: Option<String>,
+}
+
+/// Ingests POS sales queued while offline. Each sale is recorded on its own so one
+/// conflict does not hold back the rest; resending the same batch is harmless.
+pub async fn create_sales_batch(
+    req: web::Json<SaleBatchRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let req = req.into_inner();
+    if req.sales.len() > MAX_BATCH_SIZE {
+        return HttpResponse::BadRequest().body(format!("At most {} sales per batch", MAX_BATCH_SIZE));
+    }
+
+    let mut results = Vec::with_capacity(req.sales.len());
+    for mut sale in req.sales {
+        let client_key = sale.client_key.clone();
+        if client_key.as_deref().map(|k| k.trim().is_empty()).unwrap_or(true) {
+            results.push(SaleBatchResult {
+                client_key,
+                result: "REJECTED".to_string(),
+                sale_id: None,
+                receipt: None,
+                message: Some("client_key is required".to_string()),
+            });
+            continue;
+        }
+
+        sale.pos_mode = Some(true);
+        let result = match place_sale(&data, &sale, Some(&user), None).await {
+            Ok(receipt) => SaleBatchResult {
+                client_key,
+                result: "CREATED".to_string(),
+                sale_id: Some(receipt.id),
+                receipt: Some(receipt),
+                message: None,
+            },
+            Err(SaleError::Duplicate(id)) => SaleBatchResult {
+                client_key,
+                result: "DUPLICATE".to_string(),
+                sale_id: Some(id),
+                receipt: None,
+                message: None,
+            },
+            Err(e) => SaleBatchResult {
+                client_key,
+                result: if e.is_conflict() {
+                    "CONFLICT"
+                } else if matches!(e, SaleError::Internal(_)) {
+                    "FAILED"
+                } else {
+                    "REJECTED"
+                }
+                .to_string(),
+                sale_id: None,
+                receipt: None,
+                message: Some(e.message()),
+            },
+        };
+        results.push(result);
     }
+
+    HttpResponse::Ok().json(results)
 }
 
 #[derive(Deserialize)]
@@ -179,14 +659,20 @@ pub async fn update_sale_status(
     let sale_id = path.into_inner();
     let conn = &data.conn;
 
-    let sale = match Sales::find_by_id(sale_id).one(conn).await {
+    // Releasing or re-booking the slot and changing the status must succeed or fail together
+    let txn = match conn.begin().await {
+        Ok(t) => t,
+        Err(_) => return HttpResponse::InternalServerError().body("Failed to start transaction"),
+    };
+
+    let sale = match Sales::find_by_id(sale_id).lock_exclusive().one(&txn).await {
         Ok(Some(s)) => s,
         Ok(None) => return HttpResponse::NotFound().body("Sale not found"),
         Err(_) => return HttpResponse::InternalServerError().body("Database error"),
     };
 
     // Verify User is Organizer OR Collaborator
-    let kermesse = match Kermesses::find_by_id(sale.kermesse_id).one(conn).await {
+    let kermesse = match Kermesses::find_by_id(sale.kermesse_id).one(&txn).await {
         Ok(Some(k)) => k,
         _ => return HttpResponse::InternalServerError().body("Kermesse lookup error"),
     };
@@ -197,7 +683,7 @@ pub async fn update_sale_status(
             .filter(crate::entity::collaborators::Column::KermesseId.eq(sale.kermesse_id))
             .filter(crate::entity::collaborators::Column::UserId.eq(user.id))
             .filter(crate::entity::collaborators::Column::Status.eq("ACCEPTED"))
-            .one(conn)
+            .one(&txn)
             .await;
         matches!(collab, Ok(Some(_)))
     } else {
@@ -208,12 +694,45 @@ pub async fn update_sale_status(
         return HttpResponse::Forbidden().body("Only organizer or collaborator can update status");
     }
 
+    // A cancelled or refunded order gives its place in the slot back, and takes it
+    // again if it is brought back, as long as the slot still has room
+    if let Some(slot_id) = sale.time_slot_id {
+        let held = !time_slots::RELEASED_STATUSES.contains(&sale.status.as_str());
+        let holds = !time_slots::RELEASED_STATUSES.contains(&req.status.as_str());
+        if held && !holds {
+            if time_slots::release(&txn, slot_id).await.is_err() {
+                return HttpResponse::InternalServerError().body("Failed to release time slot");
+            }
+        } else if !held && holds {
+            match time_slots::reserve(&txn, slot_id, sale.kermesse_id, &sale.delivery_method).await {
+                Ok(true) => {}
+                Ok(false) => return HttpResponse::Conflict().body("The order's time slot is full or unavailable"),
+                Err(_) => return HttpResponse::InternalServerError().body("Failed to reserve time slot"),
+            }
+        }
+    }
+
     let mut sale: sales::ActiveModel = sale.into();
     sale.status = Set(req.status.clone());
 
-    match sale.update(conn).await {
-        Ok(_) => HttpResponse::Ok().json(serde_json::json!({"status": "updated"})),
-        Err(_) => HttpResponse::InternalServerError().body("Failed to update status"),
+    let sale = match sale.update(&txn).await {
+        Ok(sale) => sale,
+        Err(_) => return HttpResponse::InternalServerError().body("Failed to update status"),
+    };
+    if record_status(&txn, sale.id, &sale.status, Some(user.id)).await.is_err() {
+        return HttpResponse::InternalServerError().body("Failed to update status");
+    }
+
+    match txn.commit().await {
+        Ok(_) => {
+            data.events.publish(KermesseEvent::new(
+                sale.kermesse_id,
+                sale.buyer_id,
+                EventKind::SaleStatusChanged { sale_id: sale.id, status: sale.status },
+            ));
+            HttpResponse::Ok().json(serde_json::json!({"status": "updated"}))
+        }
+        Err(_) => HttpResponse::InternalServerError().body("Failed to commit transaction"),
     }
 }
 
@@ -223,10 +742,163 @@ pub struct SaleResponse {
     pub customer_name: String,
     pub total_amount: rust_decimal::Decimal,
     pub status: String,
+    pub payment_method: String,
+    pub delivery_method: String,
+    pub delivery_person_id: Option<i32>,
+    pub seller_id: i32,
+    pub created_at: String,
+}
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 200;
+
+#[derive(Deserialize)]
+pub struct SalesQuery {
+    pub status: Option<String>, // comma separated, e.g. "PENDING,PENDING_PAYMENT"
+    pub payment_method: Option<String>,
+    pub delivery_method: Option<String>,
+    pub seller_id: Option<i32>,
+    pub search: Option<String>, // customer name, or an order number
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>, // inclusive
+    pub sort: Option<String>,  // "created_at" (default), "total_amount" or "customer_name"
+    pub order: Option<String>, // "desc" (default) or "asc"
+    pub limit: Option<u64>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SaleSort {
+    CreatedAt,
+    TotalAmount,
+    CustomerName,
+}
+
+impl SaleSort {
+    fn parse(raw: Option<&str>) -> Option<Self> {
+        match raw.unwrap_or("created_at") {
+            "created_at" => Some(SaleSort::CreatedAt),
+            "total_amount" => Some(SaleSort::TotalAmount),
+            "customer_name" => Some(SaleSort::CustomerName),
+            _ => None,
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            SaleSort::CreatedAt => "created_at",
+            SaleSort::TotalAmount => "total_amount",
+            SaleSort::CustomerName => "customer_name",
+        }
+    }
+
+    fn column(self) -> sales::Column {
+        match self {
+            SaleSort::CreatedAt => sales::Column::CreatedAt,
+            SaleSort::TotalAmount => sales::Column::TotalAmount,
+            SaleSort::CustomerName => sales::Column::CustomerName,
+        }
+    }
+
+    fn value_of(self, sale: &sales::Model) -> String {
+        match self {
+            SaleSort::CreatedAt => sale.created_at.to_rfc3339(),
+            SaleSort::TotalAmount => sale.total_amount.to_string(),
+            SaleSort::CustomerName => sale.customer_name.clone(),
+        }
+    }
+
+    fn parse_value(self, raw: &str) -> Option<sea_orm::Value> {
+        match self {
+            SaleSort::CreatedAt => chrono::DateTime::parse_from_rfc3339(raw).ok().map(Into::into),
+            SaleSort::TotalAmount => raw.parse::<rust_decimal::Decimal>().ok().map(Into::into),
+            SaleSort::CustomerName => Some(raw.to_string().into()),
+        }
+    }
+}
+
+/// Opaque keyset cursor: the sort it belongs to, the last row's sort value and its id.
+fn encode_cursor(sort: SaleSort, sale: &sales::Model) -> String {
+    hex::encode(format!("{}\n{}\n{}", sort.key(), sort.value_of(sale), sale.id))
+}
+
+fn decode_cursor(sort: SaleSort, cursor: &str) -> Option<(sea_orm::Value, i32)> {
+    let raw = String::from_utf8(hex::decode(cursor).ok()?).ok()?;
+    // The value sits between the key and the id; customer names may contain line breaks
+    let (key, rest) = raw.split_once('\n')?;
+    let (value, id) = rest.rsplit_once('\n')?;
+    if key != sort.key() {
+        return None;
+    }
+    Some((sort.parse_value(value)?, id.parse().ok()?))
+}
+
+/// Escapes LIKE wildcards so the search matches the text literally.
+fn like_contains(search: &str) -> String {
+    let escaped = search.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Every filter except status, so the status tabs can show their own counts.
+fn filter_sales(kermesse_id: i32, query: &SalesQuery) -> sea_orm::Select<sales::Entity> {
+    let mut select = Sales::find().filter(sales::Column::KermesseId.eq(kermesse_id));
+
+    if let Some(method) = query.payment_method.as_deref().filter(|m| !m.is_empty()) {
+        select = select.filter(sales::Column::PaymentMethod.eq(method));
+    }
+    if let Some(method) = query.delivery_method.as_deref().filter(|m| !m.is_empty()) {
+        select = select.filter(sales::Column::DeliveryMethod.eq(method));
+    }
+    if let Some(seller_id) = query.seller_id {
+        select = select.filter(sales::Column::SellerId.eq(seller_id));
+    }
+    if let Some(from) = query.from.and_then(time::start_of_day) {
+        select = select.filter(sales::Column::CreatedAt.gte(from));
+    }
+    if let Some(to) = query.to.and_then(|d| d.succ_opt()).and_then(time::start_of_day) {
+        select = select.filter(sales::Column::CreatedAt.lt(to));
+    }
+    if let Some(search) = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        let pattern = sea_orm::sea_query::LikeExpr::new(like_contains(&search.to_lowercase())).escape('\\');
+        let mut condition = sea_orm::Condition::any().add(
+            sea_orm::sea_query::Expr::expr(sea_orm::sea_query::Func::lower(
+                sea_orm::sea_query::Expr::col((sales::Entity, sales::Column::CustomerName)),
+            ))
+            .like(pattern),
+        );
+        if let Ok(id) = search.trim_start_matches('#').parse::<i32>() {
+            condition = condition.add(sales::Column::Id.eq(id));
+        }
+        select = select.filter(condition);
+    }
+    select
+}
+
+#[derive(Serialize)]
+pub struct StatusCount {
+    pub status: String,
+    pub count: i64,
+}
+
+#[derive(Serialize)]
+pub struct SellerCount {
+    pub seller_id: i32,
+    pub full_name: String,
+    pub count: i64,
+}
+
+#[derive(Serialize)]
+pub struct SalesPage {
+    pub items: Vec<SaleResponse>,
+    pub next_cursor: Option<String>,
+    pub total: u64, // rows matching every filter
+    pub status_counts: Vec<StatusCount>,
+    pub sellers: Vec<SellerCount>,
 }
 
 pub async fn list_sales(
     path: web::Path<i32>,
+    query: web::Query<SalesQuery>,
     user: AuthenticatedUser,
     data: web::Data<AppState>,
 ) -> impl Responder {
@@ -257,9 +929,105 @@ pub async fn list_sales(
         return HttpResponse::Forbidden().body("Access denied");
     }
 
-    let sales_list = match Sales::find()
+    let sort = match SaleSort::parse(query.sort.as_deref()) {
+        Some(s) => s,
+        None => return HttpResponse::BadRequest().body("Unknown sort field"),
+    };
+    let ascending = match query.order.as_deref().unwrap_or("desc") {
+        "asc" => true,
+        "desc" => false,
+        _ => return HttpResponse::BadRequest().body("Order must be asc or desc"),
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let statuses: Vec<String> = query
+        .status
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(|s| s.trim().to_uppercase())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let base = filter_sales(kermesse_id, &query);
+    let filtered = if statuses.is_empty() {
+        base.clone()
+    } else {
+        base.clone().filter(sales::Column::Status.is_in(statuses))
+    };
+
+    let total = match filtered.clone().count(conn).await {
+        Ok(t) => t,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let status_counts = match base
+        .clone()
+        .select_only()
+        .column(sales::Column::Status)
+        .column_as(sales::Column::Id.count(), "count")
+        .group_by(sales::Column::Status)
+        .into_tuple::<(String, i64)>()
+        .all(conn)
+        .await
+    {
+        Ok(rows) => rows.into_iter().map(|(status, count)| StatusCount { status, count }).collect(),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let seller_rows = match Sales::find()
         .filter(sales::Column::KermesseId.eq(kermesse_id))
-        .order_by_desc(sales::Column::CreatedAt)
+        .select_only()
+        .column(sales::Column::SellerId)
+        .column_as(sales::Column::Id.count(), "count")
+        .group_by(sales::Column::SellerId)
+        .into_tuple::<(i32, i64)>()
+        .all(conn)
+        .await
+    {
+        Ok(rows) => rows,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+    let seller_names: std::collections::HashMap<i32, String> = match Users::find()
+        .filter(crate::entity::users::Column::Id.is_in(seller_rows.iter().map(|(id, _)| *id)))
+        .all(conn)
+        .await
+    {
+        Ok(u) => u.into_iter().map(|u| (u.id, u.full_name)).collect(),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+    let sellers = seller_rows
+        .into_iter()
+        .map(|(seller_id, count)| SellerCount {
+            seller_id,
+            full_name: seller_names.get(&seller_id).cloned().unwrap_or_default(),
+            count,
+        })
+        .collect();
+
+    // Keyset pagination on (sort column, id) so pages stay stable while new orders arrive
+    let column = sort.column();
+    let mut page_query = filtered;
+    if let Some(cursor) = query.cursor.as_deref().filter(|c| !c.is_empty()) {
+        let (value, last_id) = match decode_cursor(sort, cursor) {
+            Some(c) => c,
+            None => return HttpResponse::BadRequest().body("Invalid cursor"),
+        };
+        let after = if ascending {
+            sea_orm::Condition::any()
+                .add(column.gt(value.clone()))
+                .add(sea_orm::Condition::all().add(column.eq(value)).add(sales::Column::Id.gt(last_id)))
+        } else {
+            sea_orm::Condition::any()
+                .add(column.lt(value.clone()))
+                .add(sea_orm::Condition::all().add(column.eq(value)).add(sales::Column::Id.lt(last_id)))
+        };
+        page_query = page_query.filter(after);
+    }
+    let order = if ascending { sea_orm::Order::Asc } else { sea_orm::Order::Desc };
+    let mut sales_list = match page_query
+        .order_by(column, order.clone())
+        .order_by(sales::Column::Id, order)
+        .limit(limit + 1)
         .all(conn)
         .await
     {
@@ -267,14 +1035,229 @@ pub async fn list_sales(
         Err(_) => return HttpResponse::InternalServerError().body("Database error"),
     };
 
-    let response: Vec<SaleResponse> = sales_list.into_iter().map(|s| SaleResponse {
+    let next_cursor = if sales_list.len() as u64 > limit {
+        sales_list.truncate(limit as usize);
+        sales_list.last().map(|s| encode_cursor(sort, s))
+    } else {
+        None
+    };
+
+    let items: Vec<SaleResponse> = sales_list.into_iter().map(|s| SaleResponse {
         id: s.id,
         customer_name: s.customer_name,
         total_amount: s.total_amount,
         status: s.status,
+        payment_method: s.payment_method,
+        delivery_method: s.delivery_method,
+        delivery_person_id: s.delivery_person_id,
+        seller_id: s.seller_id,
+        created_at: s.created_at.to_string(),
     }).collect();
 
-    HttpResponse::Ok().json(response)
+    HttpResponse::Ok().json(SalesPage {
+        items,
+        next_cursor,
+        total,
+        status_counts,
+        sellers,
+    })
+}
+
+#[derive(Serialize)]
+pub struct SaleDetailItem {
+    pub id: i32,
+    pub dish_id: i32,
+    pub dish_name: String,
+    pub quantity: i32,
+    pub unit_price: rust_decimal::Decimal,
+    pub subtotal: rust_decimal::Decimal,
+    pub prep_status: String,
+}
+
+#[derive(Serialize)]
+pub struct SaleDetailRefund {
+    pub amount: rust_decimal::Decimal,
+    pub quantity: Option<i32>,
+    pub reason: String,
+    pub refund_method: String,
+    pub created_at: String,
+}
+
+#[derive(Serialize)]
+pub struct SaleHistoryEntry {
+    pub status: String,
+    pub changed_by: Option<String>, // None: buyer or payment provider
+    pub created_at: String,
+}
+
+#[derive(Serialize)]
+pub struct SaleDetail {
+    pub id: i32,
+    pub kermesse_id: i32,
+    pub kermesse_name: String,
+    pub customer_name: String,
+    pub contact_phone: Option<String>,
+    pub buyer_id: Option<i32>,
+    pub seller_name: Option<String>,
+    pub status: String,
+    pub payment_method: String,
+    pub payment_provider: Option<String>,
+    pub delivery_method: String,
+    pub delivery_address: Option<String>,
+    pub delivery_neighborhood: Option<String>,
+    pub delivery_city: Option<String>,
+    pub delivery_zone: Option<String>,
+    pub delivery_person_name: Option<String>,
+    pub time_slot: Option<String>,
+    pub promo_code: Option<String>,
+    pub discount_amount: rust_decimal::Decimal,
+    pub delivery_fee: rust_decimal::Decimal,
+    pub extra_donation: rust_decimal::Decimal,
+    pub total_amount: rust_decimal::Decimal,
+    pub pickup_code: Option<String>,
+    pub created_at: String,
+    pub paid_at: Option<String>,
+    pub out_for_delivery_at: Option<String>,
+    pub delivered_at: Option<String>,
+    pub items: Vec<SaleDetailItem>,
+    pub refunds: Vec<SaleDetailRefund>,
+    pub history: Vec<SaleHistoryEntry>,
+}
+
+/// Full order for its buyer, the organizer and accepted collaborators.
+pub async fn get_sale(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let sale_id = path.into_inner();
+    let conn = &data.conn;
+
+    let (sale, kermesse) = match Sales::find_by_id(sale_id).find_also_related(kermesses::Entity).one(conn).await {
+        Ok(Some((s, Some(k)))) => (s, k),
+        Ok(_) => return HttpResponse::NotFound().body("Sale not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if sale.buyer_id != Some(user.id) && !is_staff(conn, &kermesse, user.id).await {
+        return HttpResponse::Forbidden().body("Access denied");
+    }
+
+    let items = match SaleItems::find()
+        .filter(sale_items::Column::SaleId.eq(sale.id))
+        .find_also_related(dishes::Entity)
+        .all(conn)
+        .await
+    {
+        Ok(i) => i,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let refund_list = match Refunds::find()
+        .filter(refunds::Column::SaleId.eq(sale.id))
+        .order_by_asc(refunds::Column::CreatedAt)
+        .all(conn)
+        .await
+    {
+        Ok(r) => r,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let history = match SaleStatusHistory::find()
+        .filter(sale_status_history::Column::SaleId.eq(sale.id))
+        .order_by_asc(sale_status_history::Column::CreatedAt)
+        .all(conn)
+        .await
+    {
+        Ok(h) => h,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    // Everyone named on the order, in one query
+    let mut user_ids: Vec<i32> = history.iter().filter_map(|h| h.changed_by).collect();
+    user_ids.push(sale.seller_id);
+    user_ids.extend(sale.delivery_person_id);
+    let names: std::collections::HashMap<i32, String> = match Users::find()
+        .filter(crate::entity::users::Column::Id.is_in(user_ids))
+        .all(conn)
+        .await
+    {
+        Ok(u) => u.into_iter().map(|u| (u.id, u.full_name)).collect(),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let time_slot = match sale.time_slot_id {
+        Some(id) => TimeSlots::find_by_id(id).one(conn).await.ok().flatten().map(|s| format!("{} - {}", s.starts_at, s.ends_at)),
+        None => None,
+    };
+    let delivery_zone = match sale.delivery_zone_id {
+        Some(id) => DeliveryZones::find_by_id(id).one(conn).await.ok().flatten().map(|z| z.name),
+        None => None,
+    };
+    let promo_code = match sale.promo_code_id {
+        Some(id) => PromoCodes::find_by_id(id).one(conn).await.ok().flatten().map(|p| p.code),
+        None => None,
+    };
+
+    HttpResponse::Ok().json(SaleDetail {
+        id: sale.id,
+        kermesse_id: kermesse.id,
+        kermesse_name: kermesse.name,
+        customer_name: sale.customer_name,
+        contact_phone: sale.contact_phone,
+        buyer_id: sale.buyer_id,
+        seller_name: names.get(&sale.seller_id).cloned(),
+        status: sale.status,
+        payment_method: sale.payment_method,
+        payment_provider: sale.payment_provider,
+        delivery_method: sale.delivery_method,
+        delivery_address: sale.delivery_address,
+        delivery_neighborhood: sale.delivery_neighborhood,
+        delivery_city: sale.delivery_city,
+        delivery_zone,
+        delivery_person_name: sale.delivery_person_id.and_then(|id| names.get(&id).cloned()),
+        time_slot,
+        promo_code,
+        discount_amount: sale.discount_amount,
+        delivery_fee: sale.delivery_fee,
+        extra_donation: sale.extra_donation,
+        total_amount: sale.total_amount,
+        pickup_code: sale.pickup_code,
+        created_at: sale.created_at.to_string(),
+        paid_at: sale.paid_at.map(|t| t.to_string()),
+        out_for_delivery_at: sale.out_for_delivery_at.map(|t| t.to_string()),
+        delivered_at: sale.delivered_at.map(|t| t.to_string()),
+        items: items
+            .into_iter()
+            .map(|(item, dish)| SaleDetailItem {
+                id: item.id,
+                dish_id: item.dish_id,
+                dish_name: dish.map(|d| d.name).unwrap_or_default(),
+                quantity: item.quantity,
+                unit_price: if item.quantity > 0 { item.subtotal / rust_decimal::Decimal::from(item.quantity) } else { item.subtotal },
+                subtotal: item.subtotal,
+                prep_status: item.prep_status,
+            })
+            .collect(),
+        refunds: refund_list
+            .into_iter()
+            .map(|r| SaleDetailRefund {
+                amount: r.amount,
+                quantity: r.quantity,
+                reason: r.reason,
+                refund_method: r.refund_method,
+                created_at: r.created_at.to_string(),
+            })
+            .collect(),
+        history: history
+            .into_iter()
+            .map(|h| SaleHistoryEntry {
+                status: h.status,
+                changed_by: h.changed_by.and_then(|id| names.get(&id).cloned()),
+                created_at: h.created_at.to_string(),
+            })
+            .collect(),
+    })
 }
 
 #[derive(Serialize)]
@@ -286,6 +1269,7 @@ pub struct MySaleResponse {
     pub status: String,
     pub payment_method: String,
     pub created_at: String,
+    pub pickup_code: Option<String>,
 }
 
 pub async fn list_my_orders(
@@ -321,6 +1305,7 @@ pub async fn list_my_orders(
                 status: sale.status,
                 payment_method: sale.payment_method,
                 created_at: sale.created_at.to_string(),
+                pickup_code: sale.pickup_code,
             });
         }
     }
@@ -332,9 +1317,15 @@ pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::resource("/sales").route(web::post().to(create_sale)),
     )
+    .service(
+        web::resource("/sales/batch").route(web::post().to(create_sales_batch)),
+    )
     .service(
         web::resource("/kermesses/{id}/sales").route(web::get().to(list_sales)),
     )
+    .service(
+        web::resource("/sales/{id}").route(web::get().to(get_sale)),
+    )
     .service(
         web::resource("/sales/{id}/status").route(web::put().to(update_sale_status)),
     )
@@ -342,3 +1333,76 @@ pub fn config(cfg: &mut web::ServiceConfig) {
         web::resource("/my-orders").route(web::get().to(list_my_orders)),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sale(id: i32, customer_name: &str) -> sales::Model {
+        sales::Model {
+            id,
+            kermesse_id: 1,
+            seller_id: 1,
+            customer_name: customer_name.to_string(),
+            total_amount: rust_decimal::Decimal::new(4550, 2),
+            status: "PAID".to_string(),
+            created_at: chrono::DateTime::parse_from_rfc3339("2026-10-18T12:30:00-04:00").unwrap(),
+            delivery_method: "PICKUP".to_string(),
+            delivery_address: None,
+            contact_phone: None,
+            buyer_id: None,
+            payment_method: "CASH".to_string(),
+            payment_reference: None,
+            payment_provider: None,
+            paid_at: None,
+            promo_code_id: None,
+            discount_amount: rust_decimal::Decimal::ZERO,
+            extra_donation: rust_decimal::Decimal::ZERO,
+            time_slot_id: None,
+            delivery_person_id: None,
+            delivery_neighborhood: None,
+            delivery_city: None,
+            out_for_delivery_at: None,
+            delivered_at: None,
+            delivery_zone_id: None,
+            delivery_fee: rust_decimal::Decimal::ZERO,
+            client_key: None,
+            pickup_code: None,
+            tracking_token: None,
+        }
+    }
+
+    #[test]
+    fn cursor_round_trips_for_every_sort() {
+        let s = sale(42, "Ana\nMaría");
+        for (sort, value) in [
+            (SaleSort::CreatedAt, sea_orm::Value::from(s.created_at)),
+            (SaleSort::TotalAmount, sea_orm::Value::from(s.total_amount)),
+            (SaleSort::CustomerName, sea_orm::Value::from(s.customer_name.clone())),
+        ] {
+            assert_eq!(decode_cursor(sort, &encode_cursor(sort, &s)), Some((value, 42)));
+        }
+    }
+
+    #[test]
+    fn cursor_is_rejected_for_another_sort_or_garbage() {
+        let cursor = encode_cursor(SaleSort::TotalAmount, &sale(7, "Ana"));
+        assert_eq!(decode_cursor(SaleSort::CreatedAt, &cursor), None);
+        assert_eq!(decode_cursor(SaleSort::TotalAmount, "zz"), None);
+        assert_eq!(decode_cursor(SaleSort::TotalAmount, &hex::encode("total_amount\nabc\n7")), None);
+    }
+
+    #[test]
+    fn search_pattern_escapes_like_wildcards() {
+        assert_eq!(like_contains("ana"), "%ana%");
+        assert_eq!(like_contains("100%_a\\b"), "%100\\%\\_a\\\\b%");
+    }
+
+    #[test]
+    fn delivery_progress_keeps_a_partial_refund() {
+        assert_eq!(progressed_status("PAID", "OUT_FOR_DELIVERY"), "OUT_FOR_DELIVERY");
+        assert_eq!(progressed_status("OUT_FOR_DELIVERY", "DELIVERED"), "DELIVERED");
+        assert_eq!(progressed_status("PARTIALLY_REFUNDED", "OUT_FOR_DELIVERY"), "PARTIALLY_REFUNDED");
+        assert_eq!(progressed_status("PARTIALLY_REFUNDED", "DELIVERED"), "PARTIALLY_REFUNDED");
+    }
+}
@@ -0,0 +1,292 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::NaiveTime;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::api::collaboration::is_staff;
+use crate::entity::{dishes, sale_items, sales, time_slots, prelude::*};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+
+#[derive(Deserialize)]
+pub struct CreateTimeSlotRequest {
+    pub slot_type: Option<String>, // "PICKUP" (default) or "DELIVERY"
+    pub starts_at: String,         // "HH:MM"
+    pub ends_at: String,
+    pub capacity: i32,
+}
+
+#[derive(Serialize)]
+pub struct TimeSlotResponse {
+    pub id: i32,
+    pub slot_type: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub capacity: i32,
+    pub remaining: i32,
+}
+
+impl From<time_slots::Model> for TimeSlotResponse {
+    fn from(model: time_slots::Model) -> Self {
+        Self {
+            id: model.id,
+            slot_type: model.slot_type,
+            starts_at: model.starts_at,
+            ends_at: model.ends_at,
+            capacity: model.capacity,
+            remaining: (model.capacity - model.booked).max(0),
+        }
+    }
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// Orders in these statuses no longer hold their place in a slot.
+pub const RELEASED_STATUSES: [&str; 2] = ["CANCELLED", "REFUNDED"];
+
+/// Books one order into the slot; returns false when it is full or belongs elsewhere.
+pub async fn reserve<C: ConnectionTrait>(
+    conn: &C,
+    slot_id: i32,
+    kermesse_id: i32,
+    slot_type: &str,
+) -> Result<bool, DbErr> {
+    let res = TimeSlots::update_many()
+        .col_expr(time_slots::Column::Booked, Expr::col(time_slots::Column::Booked).add(1))
+        .filter(time_slots::Column::Id.eq(slot_id))
+        .filter(time_slots::Column::KermesseId.eq(kermesse_id))
+        .filter(time_slots::Column::SlotType.eq(slot_type))
+        .filter(Expr::col(time_slots::Column::Booked).lt(Expr::col(time_slots::Column::Capacity)))
+        .exec(conn)
+        .await?;
+
+    Ok(res.rows_affected == 1)
+}
+
+/// Frees the place an order held in its slot (e.g. when cancelled or refunded).
+pub async fn release<C: ConnectionTrait>(conn: &C, slot_id: i32) -> Result<(), DbErr> {
+    TimeSlots::update_many()
+        .col_expr(time_slots::Column::Booked, Expr::col(time_slots::Column::Booked).sub(1))
+        .filter(time_slots::Column::Id.eq(slot_id))
+        .filter(time_slots::Column::Booked.gt(0))
+        .exec(conn)
+        .await
+        .map(|_| ())
+}
+
+pub async fn create_time_slot(
+    path: web::Path<i32>,
+    req: web::Json<CreateTimeSlotRequest>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can manage time slots");
+    }
+
+    let (starts, ends) = match (parse_time(&req.starts_at), parse_time(&req.ends_at)) {
+        (Some(s), Some(e)) if s < e => (s, e),
+        _ => return HttpResponse::BadRequest().body("Slot times must be HH:MM with start before end"),
+    };
+
+    if let Some(event_start) = kermesse.start_time.as_deref().and_then(parse_time) {
+        if starts < event_start {
+            return HttpResponse::BadRequest().body("Slot starts before the kermesse opens");
+        }
+    }
+    if let Some(event_end) = kermesse.end_time.as_deref().and_then(parse_time) {
+        if ends > event_end {
+            return HttpResponse::BadRequest().body("Slot ends after the kermesse closes");
+        }
+    }
+
+    if req.capacity <= 0 {
+        return HttpResponse::BadRequest().body("Capacity must be positive");
+    }
+
+    let slot_type = req.slot_type.clone().unwrap_or_else(|| "PICKUP".to_string());
+    if slot_type != "PICKUP" && slot_type != "DELIVERY" {
+        return HttpResponse::BadRequest().body("slot_type must be PICKUP or DELIVERY");
+    }
+
+    let slot = time_slots::ActiveModel {
+        kermesse_id: Set(kermesse_id),
+        slot_type: Set(slot_type),
+        starts_at: Set(starts.format("%H:%M").to_string()),
+        ends_at: Set(ends.format("%H:%M").to_string()),
+        capacity: Set(req.capacity),
+        booked: Set(0),
+        ..Default::default()
+    };
+
+    match slot.insert(conn).await {
+        Ok(model) => HttpResponse::Created().json(TimeSlotResponse::from(model)),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Failed to create time slot: {}", e)),
+    }
+}
+
+pub async fn list_time_slots(
+    path: web::Path<i32>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+
+    match TimeSlots::find()
+        .filter(time_slots::Column::KermesseId.eq(kermesse_id))
+        .order_by_asc(time_slots::Column::StartsAt)
+        .all(&data.conn)
+        .await
+    {
+        Ok(list) => {
+            let response: Vec<TimeSlotResponse> = list.into_iter().map(TimeSlotResponse::from).collect();
+            HttpResponse::Ok().json(response)
+        }
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+#[derive(Serialize)]
+pub struct KitchenDishTotal {
+    pub dish_name: String,
+    pub quantity: i32,
+}
+
+#[derive(Serialize)]
+pub struct KitchenOrder {
+    pub id: i32,
+    pub customer_name: String,
+    pub status: String,
+    pub delivery_method: String,
+    pub items: Vec<KitchenDishTotal>,
+}
+
+#[derive(Serialize)]
+pub struct KitchenSlotGroup {
+    pub slot: Option<TimeSlotResponse>, // None groups orders without a slot
+    pub dish_totals: Vec<KitchenDishTotal>,
+    pub orders: Vec<KitchenOrder>,
+}
+
+pub async fn kitchen_by_slot(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if !is_staff(conn, &kermesse, user.id).await {
+        return HttpResponse::Forbidden().body("Access denied");
+    }
+
+    let slots = match TimeSlots::find()
+        .filter(time_slots::Column::KermesseId.eq(kermesse_id))
+        .order_by_asc(time_slots::Column::StartsAt)
+        .all(conn)
+        .await
+    {
+        Ok(s) => s,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let open_sales = match Sales::find()
+        .filter(sales::Column::KermesseId.eq(kermesse_id))
+        .filter(sales::Column::Status.is_in(["PENDING", "PAID"]))
+        .order_by_asc(sales::Column::CreatedAt)
+        .all(conn)
+        .await
+    {
+        Ok(s) => s,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let items = match SaleItems::find()
+        .filter(sale_items::Column::SaleId.is_in(open_sales.iter().map(|s| s.id)))
+        .find_also_related(dishes::Entity)
+        .all(conn)
+        .await
+    {
+        Ok(i) => i,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    // slot_id -> (dish totals, orders)
+    let mut totals: BTreeMap<Option<i32>, BTreeMap<String, i32>> = BTreeMap::new();
+    let mut orders: BTreeMap<Option<i32>, Vec<KitchenOrder>> = BTreeMap::new();
+    for sale in open_sales {
+        let order_items: Vec<KitchenDishTotal> = items
+            .iter()
+            .filter(|(item, _)| item.sale_id == sale.id)
+            .map(|(item, dish)| KitchenDishTotal {
+                dish_name: dish.as_ref().map(|d| d.name.clone()).unwrap_or_default(),
+                quantity: item.quantity,
+            })
+            .collect();
+
+        let slot_totals = totals.entry(sale.time_slot_id).or_default();
+        for item in &order_items {
+            *slot_totals.entry(item.dish_name.clone()).or_insert(0) += item.quantity;
+        }
+        orders.entry(sale.time_slot_id).or_default().push(KitchenOrder {
+            id: sale.id,
+            customer_name: sale.customer_name,
+            status: sale.status,
+            delivery_method: sale.delivery_method,
+            items: order_items,
+        });
+    }
+
+    let mut response = Vec::new();
+    let keys = slots.into_iter().map(Some).chain(std::iter::once(None));
+    for slot in keys {
+        let key = slot.as_ref().map(|s| s.id);
+        let slot_orders = orders.remove(&key).unwrap_or_default();
+        if slot.is_none() && slot_orders.is_empty() {
+            continue;
+        }
+        response.push(KitchenSlotGroup {
+            slot: slot.map(TimeSlotResponse::from),
+            dish_totals: totals
+                .remove(&key)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(dish_name, quantity)| KitchenDishTotal { dish_name, quantity })
+                .collect(),
+            orders: slot_orders,
+        });
+    }
+
+    HttpResponse::Ok().json(response)
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/time-slots")
+            .route(web::get().to(list_time_slots))
+            .route(web::post().to(create_time_slot)),
+    )
+    .service(
+        web::resource("/kermesses/{id}/kitchen/slots")
+            .route(web::get().to(kitchen_by_slot)),
+    );
+}
@@ -0,0 +1,109 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::Serialize;
+
+use crate::entity::{dishes, kermesses, sale_items, sales, prelude::*};
+use crate::state::AppState;
+
+#[derive(Serialize)]
+pub struct TrackedItem {
+    pub dish_name: String,
+    pub quantity: i32,
+    pub prep_status: String,
+}
+
+/// What a guest sees of their order: no name, phone or address, so a leaked
+/// link exposes nothing about the buyer.
+#[derive(Serialize)]
+pub struct TrackedOrder {
+    pub sale_id: i32,
+    pub kermesse_name: String,
+    pub event_date: String,
+    pub kermesse_city: Option<String>,
+    pub status: String,
+    pub ready: bool, // every item marked READY in the kitchen
+    pub payment_method: String,
+    pub delivery_method: String,
+    pub total_amount: rust_decimal::Decimal,
+    pub time_slot: Option<String>,
+    pub pickup_code: Option<String>,
+    pub created_at: String,
+    pub paid_at: Option<String>,
+    pub out_for_delivery_at: Option<String>,
+    pub delivered_at: Option<String>,
+    pub items: Vec<TrackedItem>,
+}
+
+pub async fn track_order(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let token = path.into_inner();
+    let conn = &data.conn;
+
+    let (sale, kermesse) = match Sales::find()
+        .filter(sales::Column::TrackingToken.eq(token))
+        .find_also_related(kermesses::Entity)
+        .one(conn)
+        .await
+    {
+        Ok(Some((s, Some(k)))) => (s, k),
+        Ok(_) => return HttpResponse::NotFound().body("Order not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let items = match SaleItems::find()
+        .filter(sale_items::Column::SaleId.eq(sale.id))
+        .find_also_related(dishes::Entity)
+        .all(conn)
+        .await
+    {
+        Ok(i) => i,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let time_slot = match sale.time_slot_id {
+        Some(id) => match TimeSlots::find_by_id(id).one(conn).await {
+            Ok(slot) => slot.map(|s| format!("{} - {}", s.starts_at, s.ends_at)),
+            Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+        },
+        None => None,
+    };
+
+    let items: Vec<TrackedItem> = items
+        .into_iter()
+        .map(|(item, dish)| TrackedItem {
+            dish_name: dish.map(|d| d.name).unwrap_or_default(),
+            quantity: item.quantity,
+            prep_status: item.prep_status,
+        })
+        .collect();
+
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(TrackedOrder {
+            sale_id: sale.id,
+            kermesse_name: kermesse.name,
+            event_date: kermesse.event_date.to_string(),
+            kermesse_city: kermesse.city,
+            status: sale.status,
+            ready: !items.is_empty() && items.iter().all(|i| i.prep_status == "READY"),
+            payment_method: sale.payment_method,
+            delivery_method: sale.delivery_method,
+            total_amount: sale.total_amount,
+            time_slot,
+            pickup_code: sale.pickup_code,
+            created_at: sale.created_at.to_string(),
+            paid_at: sale.paid_at.map(|t| t.to_string()),
+            out_for_delivery_at: sale.out_for_delivery_at.map(|t| t.to_string()),
+            delivered_at: sale.delivered_at.map(|t| t.to_string()),
+            items,
+        })
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/orders/track/{token}")
+            .route(web::get().to(track_order)),
+    );
+}
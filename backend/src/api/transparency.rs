@@ -0,0 +1,311 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, FromQueryResult, ModelTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::dashboard::kermesse_totals;
+use crate::entity::{beneficiary_deliveries, expenses, kermesses, prelude::*};
+use crate::state::AppState;
+use crate::utils::auth::AuthenticatedUser;
+use crate::utils::time;
+
+/// Receipts and photos are stored in the database, so keep them reasonably small.
+const MAX_PROOF_BYTES: usize = 5 * 1024 * 1024;
+
+const PROOF_CONTENT_TYPES: [&str; 4] = ["image/jpeg", "image/png", "image/webp", "application/pdf"];
+
+#[derive(Serialize)]
+pub struct TransparencyReport {
+    pub kermesse_id: i32,
+    pub kermesse_name: String,
+    pub beneficiary_name: String,
+    pub beneficiary_reason: String,
+    pub event_date: chrono::NaiveDate,
+    pub published: bool,
+    pub total_raised: rust_decimal::Decimal,
+    pub sales_revenue: rust_decimal::Decimal,
+    pub donations_total: rust_decimal::Decimal,
+    pub expenses_total: rust_decimal::Decimal,
+    pub expenses_by_category: Vec<CategoryTotal>,
+    /// What is left for the beneficiary once expenses are paid.
+    pub net_amount: rust_decimal::Decimal,
+    pub delivered_total: rust_decimal::Decimal,
+    pub pending_delivery: rust_decimal::Decimal,
+    pub deliveries: Vec<DeliveryResponse>,
+}
+
+#[derive(Serialize)]
+pub struct CategoryTotal {
+    pub category: String,
+    pub total: rust_decimal::Decimal,
+}
+
+/// A delivery without its proof; the file is served by `GET /deliveries/{id}/proof`.
+#[derive(Serialize, FromQueryResult)]
+pub struct DeliveryResponse {
+    pub id: i32,
+    pub amount: rust_decimal::Decimal,
+    pub delivered_on: chrono::NaiveDate,
+    pub note: Option<String>,
+    pub proof_file_name: String,
+    pub proof_content_type: String,
+}
+
+#[derive(Deserialize)]
+pub struct CreateDeliveryQuery {
+    pub amount: rust_decimal::Decimal,
+    pub delivered_on: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+    pub file_name: String,
+}
+
+async fn delivery_list(conn: &DatabaseConnection, kermesse_id: i32) -> Result<Vec<DeliveryResponse>, DbErr> {
+    BeneficiaryDeliveries::find()
+        .filter(beneficiary_deliveries::Column::KermesseId.eq(kermesse_id))
+        .select_only()
+        .columns([
+            beneficiary_deliveries::Column::Id,
+            beneficiary_deliveries::Column::Amount,
+            beneficiary_deliveries::Column::DeliveredOn,
+            beneficiary_deliveries::Column::Note,
+            beneficiary_deliveries::Column::ProofFileName,
+            beneficiary_deliveries::Column::ProofContentType,
+        ])
+        .order_by_desc(beneficiary_deliveries::Column::DeliveredOn)
+        .order_by_desc(beneficiary_deliveries::Column::Id)
+        .into_model::<DeliveryResponse>()
+        .all(conn)
+        .await
+}
+
+async fn transparency_report(conn: &DatabaseConnection, kermesse: &kermesses::Model) -> Result<TransparencyReport, DbErr> {
+    let totals = kermesse_totals(conn, &[kermesse.id])
+        .await?
+        .remove(&kermesse.id)
+        .unwrap_or_default();
+
+    let mut expenses_by_category: Vec<CategoryTotal> = Expenses::find()
+        .filter(expenses::Column::KermesseId.eq(kermesse.id))
+        .select_only()
+        .column(expenses::Column::Category)
+        .column_as(expenses::Column::Amount.sum(), "total")
+        .group_by(expenses::Column::Category)
+        .into_tuple::<(String, rust_decimal::Decimal)>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|(category, total)| CategoryTotal { category, total })
+        .collect();
+    expenses_by_category.sort_by_key(|c| std::cmp::Reverse(c.total));
+    let expenses_total = expenses_by_category
+        .iter()
+        .fold(rust_decimal::Decimal::ZERO, |acc, c| acc + c.total);
+
+    let deliveries = delivery_list(conn, kermesse.id).await?;
+    let delivered_total = deliveries
+        .iter()
+        .fold(rust_decimal::Decimal::ZERO, |acc, d| acc + d.amount);
+
+    let total_raised = totals.total_raised();
+    let net_amount = total_raised - expenses_total;
+
+    Ok(TransparencyReport {
+        kermesse_id: kermesse.id,
+        kermesse_name: kermesse.name.clone(),
+        beneficiary_name: kermesse.beneficiary_name.clone(),
+        beneficiary_reason: kermesse.beneficiary_reason.clone(),
+        event_date: kermesse.event_date,
+        published: kermesse.transparency_enabled,
+        total_raised,
+        sales_revenue: totals.gross_collected - totals.refunded - totals.extra_donations,
+        donations_total: totals.extra_donations + totals.donations,
+        expenses_total,
+        expenses_by_category,
+        net_amount,
+        delivered_total,
+        pending_delivery: (net_amount - delivered_total).max(rust_decimal::Decimal::ZERO),
+        deliveries,
+    })
+}
+
+/// Public once the organizer opts in; the organizer can always preview it.
+pub async fn get_transparency(
+    path: web::Path<i32>,
+    user: Option<AuthenticatedUser>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    let is_organizer = user.is_some_and(|u| u.id == kermesse.organizer_id);
+    if !kermesse.transparency_enabled && !is_organizer {
+        return HttpResponse::NotFound().body("Transparency page is not enabled");
+    }
+
+    match transparency_report(conn, &kermesse).await {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(_) => HttpResponse::InternalServerError().body("Database error"),
+    }
+}
+
+/// Records a hand-over to the beneficiary. The request body is the proof file itself,
+/// sent with its own `Content-Type`; the other fields travel in the query string.
+pub async fn create_delivery(
+    path: web::Path<i32>,
+    query: web::Query<CreateDeliveryQuery>,
+    req: HttpRequest,
+    body: web::Bytes,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let kermesse_id = path.into_inner();
+    let conn = &data.conn;
+
+    let kermesse = match Kermesses::find_by_id(kermesse_id).one(conn).await {
+        Ok(Some(k)) => k,
+        Ok(None) => return HttpResponse::NotFound().body("Kermesse not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can record deliveries");
+    }
+
+    if query.amount <= rust_decimal::Decimal::ZERO {
+        return HttpResponse::BadRequest().body("Amount must be positive");
+    }
+
+    let content_type = req
+        .headers()
+        .get("Content-Type")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(';').next().unwrap_or("").trim().to_lowercase())
+        .unwrap_or_default();
+    if !PROOF_CONTENT_TYPES.contains(&content_type.as_str()) {
+        return HttpResponse::BadRequest().body("Proof must be a JPEG, PNG or WebP image or a PDF");
+    }
+    if body.is_empty() {
+        return HttpResponse::BadRequest().body("Proof file is required");
+    }
+
+    // Only keep characters that are safe inside a Content-Disposition header
+    let file_name: String = query
+        .file_name
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | ' '))
+        .collect();
+    let file_name = match file_name.trim() {
+        "" => "comprobante".to_string(),
+        name => name.to_string(),
+    };
+
+    let delivery = beneficiary_deliveries::ActiveModel {
+        kermesse_id: Set(kermesse_id),
+        amount: Set(query.amount),
+        delivered_on: Set(query.delivered_on.unwrap_or_else(time::today)),
+        note: Set(query.note.as_ref().map(|n| n.trim().to_string()).filter(|n| !n.is_empty())),
+        proof_file_name: Set(file_name),
+        proof_content_type: Set(content_type),
+        proof_data: Set(body.to_vec()),
+        created_by: Set(user.id),
+        ..Default::default()
+    };
+
+    match delivery.insert(conn).await {
+        Ok(model) => HttpResponse::Created().json(DeliveryResponse {
+            id: model.id,
+            amount: model.amount,
+            delivered_on: model.delivered_on,
+            note: model.note,
+            proof_file_name: model.proof_file_name,
+            proof_content_type: model.proof_content_type,
+        }),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to record delivery"),
+    }
+}
+
+pub async fn delete_delivery(
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let delivery_id = path.into_inner();
+    let conn = &data.conn;
+
+    let (delivery, kermesse) = match BeneficiaryDeliveries::find_by_id(delivery_id)
+        .find_also_related(Kermesses)
+        .one(conn)
+        .await
+    {
+        Ok(Some((d, Some(k)))) => (d, k),
+        Ok(_) => return HttpResponse::NotFound().body("Delivery not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if kermesse.organizer_id != user.id {
+        return HttpResponse::Forbidden().body("Only organizer can delete deliveries");
+    }
+
+    match delivery.delete(conn).await {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({"status": "deleted"})),
+        Err(_) => HttpResponse::InternalServerError().body("Failed to delete delivery"),
+    }
+}
+
+/// The uploaded receipt or photo, shown inline. Same visibility as the transparency page.
+pub async fn get_delivery_proof(
+    path: web::Path<i32>,
+    user: Option<AuthenticatedUser>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let delivery_id = path.into_inner();
+    let conn = &data.conn;
+
+    let (delivery, kermesse) = match BeneficiaryDeliveries::find_by_id(delivery_id)
+        .find_also_related(Kermesses)
+        .one(conn)
+        .await
+    {
+        Ok(Some((d, Some(k)))) => (d, k),
+        Ok(_) => return HttpResponse::NotFound().body("Delivery not found"),
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+
+    if !kermesse.transparency_enabled && user.is_none_or(|u| u.id != kermesse.organizer_id) {
+        return HttpResponse::NotFound().body("Delivery not found");
+    }
+
+    HttpResponse::Ok()
+        .content_type(delivery.proof_content_type)
+        .insert_header(("Content-Disposition", format!("inline; filename=\"{}\"", delivery.proof_file_name)))
+        .insert_header(("X-Content-Type-Options", "nosniff"))
+        .body(delivery.proof_data)
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/kermesses/{id}/transparency")
+            .route(web::get().to(get_transparency)),
+    )
+    .service(
+        web::resource("/kermesses/{id}/deliveries")
+            .app_data(web::PayloadConfig::new(MAX_PROOF_BYTES))
+            .route(web::post().to(create_delivery)),
+    )
+    .service(
+        web::resource("/deliveries/{id}")
+            .route(web::delete().to(delete_delivery)),
+    )
+    .service(
+        web::resource("/deliveries/{id}/proof")
+            .route(web::get().to(get_delivery_proof)),
+    );
+}
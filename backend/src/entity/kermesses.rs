@@ -25,18 +25,32 @@ pub struct Model {
     pub qr_code_url: Option<String>,
     pub department: Option<String>,
     pub city: Option<String>,
+    pub transparency_enabled: bool,
+    pub delivery_enabled: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::beneficiary_deliveries::Entity")]
+    BeneficiaryDeliveries,
     #[sea_orm(has_many = "super::collaborators::Entity")]
     Collaborators,
+    #[sea_orm(has_many = "super::delivery_zones::Entity")]
+    DeliveryZones,
     #[sea_orm(has_many = "super::dishes::Entity")]
     Dishes,
+    #[sea_orm(has_many = "super::donations::Entity")]
+    Donations,
+    #[sea_orm(has_many = "super::expenses::Entity")]
+    Expenses,
     #[sea_orm(has_many = "super::ingredients::Entity")]
     Ingredients,
+    #[sea_orm(has_many = "super::promo_codes::Entity")]
+    PromoCodes,
     #[sea_orm(has_many = "super::sales::Entity")]
     Sales,
+    #[sea_orm(has_many = "super::time_slots::Entity")]
+    TimeSlots,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::OrganizerId",
@@ -47,30 +61,66 @@ pub enum Relation {
     Users,
 }
 
+impl Related<super::beneficiary_deliveries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BeneficiaryDeliveries.def()
+    }
+}
+
 impl Related<super::collaborators::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Collaborators.def()
     }
 }
 
+impl Related<super::delivery_zones::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DeliveryZones.def()
+    }
+}
+
 impl Related<super::dishes::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Dishes.def()
     }
 }
 
+impl Related<super::donations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Donations.def()
+    }
+}
+
+impl Related<super::expenses::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Expenses.def()
+    }
+}
+
 impl Related<super::ingredients::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Ingredients.def()
     }
 }
 
+impl Related<super::promo_codes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PromoCodes.def()
+    }
+}
+
 impl Related<super::sales::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Sales.def()
     }
 }
 
+impl Related<super::time_slots::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TimeSlots.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
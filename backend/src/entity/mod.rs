@@ -2,11 +2,21 @@
 
 pub mod prelude;
 
+pub mod beneficiary_deliveries;
 pub mod collaborators;
+pub mod delivery_zones;
 pub mod dishes;
+pub mod donations;
+pub mod expenses;
+pub mod idempotency_keys;
 pub mod ingredient_donations;
 pub mod ingredients;
 pub mod kermesses;
+pub mod phone_verifications;
+pub mod promo_codes;
+pub mod refunds;
 pub mod sale_items;
+pub mod sale_status_history;
 pub mod sales;
+pub mod time_slots;
 pub mod users;
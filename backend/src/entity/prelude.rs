@@ -1,10 +1,20 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
 
+pub use super::beneficiary_deliveries::Entity as BeneficiaryDeliveries;
 pub use super::collaborators::Entity as Collaborators;
+pub use super::delivery_zones::Entity as DeliveryZones;
 pub use super::dishes::Entity as Dishes;
+pub use super::donations::Entity as Donations;
+pub use super::expenses::Entity as Expenses;
+pub use super::idempotency_keys::Entity as IdempotencyKeys;
 pub use super::ingredient_donations::Entity as IngredientDonations;
 pub use super::ingredients::Entity as Ingredients;
 pub use super::kermesses::Entity as Kermesses;
+pub use super::phone_verifications::Entity as PhoneVerifications;
+pub use super::promo_codes::Entity as PromoCodes;
+pub use super::refunds::Entity as Refunds;
 pub use super::sale_items::Entity as SaleItems;
+pub use super::sale_status_history::Entity as SaleStatusHistory;
 pub use super::sales::Entity as Sales;
+pub use super::time_slots::Entity as TimeSlots;
 pub use super::users::Entity as Users;
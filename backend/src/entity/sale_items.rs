@@ -12,6 +12,8 @@ pub struct Model {
     pub dish_id: i32,
     pub quantity: i32,
     pub subtotal: Decimal,
+    pub prep_status: String,
+    pub prep_updated_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -19,10 +19,39 @@ pub struct Model {
     pub contact_phone: Option<String>,
     pub buyer_id: Option<i32>,
     pub payment_method: String,
+    #[sea_orm(unique)]
+    pub payment_reference: Option<String>,
+    pub payment_provider: Option<String>,
+    pub paid_at: Option<DateTimeWithTimeZone>,
+    pub promo_code_id: Option<i32>,
+    pub discount_amount: Decimal,
+    pub extra_donation: Decimal,
+    pub time_slot_id: Option<i32>,
+    pub delivery_person_id: Option<i32>,
+    pub delivery_neighborhood: Option<String>,
+    pub delivery_city: Option<String>,
+    pub out_for_delivery_at: Option<DateTimeWithTimeZone>,
+    pub delivered_at: Option<DateTimeWithTimeZone>,
+    pub delivery_zone_id: Option<i32>,
+    pub delivery_fee: Decimal,
+    #[sea_orm(unique)]
+    pub client_key: Option<String>,
+    #[sea_orm(unique)]
+    pub pickup_code: Option<String>,
+    #[sea_orm(unique)]
+    pub tracking_token: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::delivery_zones::Entity",
+        from = "Column::DeliveryZoneId",
+        to = "super::delivery_zones::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    DeliveryZones,
     #[sea_orm(
         belongs_to = "super::kermesses::Entity",
         from = "Column::KermesseId",
@@ -31,8 +60,28 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Kermesses,
+    #[sea_orm(
+        belongs_to = "super::promo_codes::Entity",
+        from = "Column::PromoCodeId",
+        to = "super::promo_codes::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    PromoCodes,
+    #[sea_orm(has_many = "super::refunds::Entity")]
+    Refunds,
     #[sea_orm(has_many = "super::sale_items::Entity")]
     SaleItems,
+    #[sea_orm(has_many = "super::sale_status_history::Entity")]
+    SaleStatusHistory,
+    #[sea_orm(
+        belongs_to = "super::time_slots::Entity",
+        from = "Column::TimeSlotId",
+        to = "super::time_slots::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    TimeSlots,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::BuyerId",
@@ -49,6 +98,20 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Users1,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::DeliveryPersonId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Users3,
+}
+
+impl Related<super::delivery_zones::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DeliveryZones.def()
+    }
 }
 
 impl Related<super::kermesses::Entity> for Entity {
@@ -57,10 +120,34 @@ impl Related<super::kermesses::Entity> for Entity {
     }
 }
 
+impl Related<super::promo_codes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PromoCodes.def()
+    }
+}
+
+impl Related<super::refunds::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Refunds.def()
+    }
+}
+
 impl Related<super::sale_items::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::SaleItems.def()
     }
 }
 
+impl Related<super::sale_status_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SaleStatusHistory.def()
+    }
+}
+
+impl Related<super::time_slots::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TimeSlots.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -20,14 +20,28 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::beneficiary_deliveries::Entity")]
+    BeneficiaryDeliveries,
     #[sea_orm(has_many = "super::collaborators::Entity")]
     Collaborators,
+    #[sea_orm(has_many = "super::expenses::Entity")]
+    Expenses,
     #[sea_orm(has_many = "super::ingredient_donations::Entity")]
     IngredientDonations,
     #[sea_orm(has_many = "super::ingredients::Entity")]
     Ingredients,
     #[sea_orm(has_many = "super::kermesses::Entity")]
     Kermesses,
+    #[sea_orm(has_many = "super::phone_verifications::Entity")]
+    PhoneVerifications,
+    #[sea_orm(has_many = "super::sale_status_history::Entity")]
+    SaleStatusHistory,
+}
+
+impl Related<super::beneficiary_deliveries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BeneficiaryDeliveries.def()
+    }
 }
 
 impl Related<super::collaborators::Entity> for Entity {
@@ -36,6 +50,12 @@ impl Related<super::collaborators::Entity> for Entity {
     }
 }
 
+impl Related<super::expenses::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Expenses.def()
+    }
+}
+
 impl Related<super::ingredient_donations::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::IngredientDonations.def()
@@ -54,4 +74,16 @@ impl Related<super::kermesses::Entity> for Entity {
     }
 }
 
+impl Related<super::phone_verifications::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PhoneVerifications.def()
+    }
+}
+
+impl Related<super::sale_status_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SaleStatusHistory.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
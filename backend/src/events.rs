@@ -0,0 +1,77 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// How many events a slow subscriber may fall behind before it starts skipping.
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    SaleCreated { sale_id: i32, status: String },
+    SaleStatusChanged { sale_id: i32, status: String },
+    /// Every item of the order was marked READY in the kitchen.
+    OrderReady { sale_id: i32 },
+    StockChanged { dish_id: i32, quantity_available: i32 },
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct KermesseEvent {
+    pub kermesse_id: i32,
+    /// Buyer of the sale the event refers to; used to filter subscriptions, never sent.
+    #[serde(skip)]
+    pub buyer_id: Option<i32>,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+impl KermesseEvent {
+    pub fn new(kermesse_id: i32, buyer_id: Option<i32>, kind: EventKind) -> Self {
+        Self { kermesse_id, buyer_id, kind }
+    }
+
+    pub fn stock(kermesse_id: i32, dish_id: i32, quantity_available: i32) -> Self {
+        Self::new(kermesse_id, None, EventKind::StockChanged { dish_id, quantity_available })
+    }
+
+    /// Events anyone looking at the kermesse may see (no customer data).
+    pub fn is_public(&self) -> bool {
+        matches!(self.kind, EventKind::StockChanged { .. })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self.kind {
+            EventKind::SaleCreated { .. } => "sale_created",
+            EventKind::SaleStatusChanged { .. } => "sale_status_changed",
+            EventKind::OrderReady { .. } => "order_ready",
+            EventKind::StockChanged { .. } => "stock_changed",
+        }
+    }
+}
+
+/// In-process fan-out of kermesse events to the SSE subscribers.
+#[derive(Clone, Debug)]
+pub struct EventBus {
+    sender: broadcast::Sender<KermesseEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Fire and forget: having nobody listening is not an error.
+    pub fn publish(&self, event: KermesseEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<KermesseEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -9,6 +9,9 @@ pub mod entity;
 pub mod utils; // Make sure utils is modded
 pub mod api;
 pub mod seed;
+pub mod payments;
+pub mod events;
+pub mod sms;
 
 use state::AppState;
 
@@ -27,7 +30,12 @@ async fn main() -> std::io::Result<()> {
     let conn = Database::connect(&db_url).await.expect("Failed to connect to DB");
     info!("Database connected.");
 
-    let state = AppState { conn };
+    let state = AppState {
+        conn,
+        payments: payments::PaymentProviders::from_env(),
+        events: events::EventBus::new(),
+        sms: sms::SmsGateway::from_env(),
+    };
 
     // Check for seed flag
     let args: Vec<String> = env::args().collect();
@@ -55,6 +63,24 @@ use actix_cors::Cors;
             .configure(api::sales::config)
             .configure(api::collaboration::config)
             .configure(api::dashboard::config)
+            .configure(api::payments::config)
+            .configure(api::refunds::config)
+            .configure(api::promo_codes::config)
+            .configure(api::donations::config)
+            .configure(api::time_slots::config)
+            .configure(api::kitchen::config)
+            .configure(api::events::config)
+            .configure(api::deliveries::config)
+            .configure(api::delivery_zones::config)
+            .configure(api::pickup::config)
+            .configure(api::tracking::config)
+            .configure(api::claims::config)
+            .configure(api::expenses::config)
+            .configure(api::exports::config)
+            .configure(api::reports::config)
+            .configure(api::overview::config)
+            .configure(api::transparency::config)
+            .configure(api::progress::config)
     })
     .bind(("127.0.0.1", 8080))?
     .run()
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+pub mod mock;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PaymentEventStatus {
+    Paid,
+    Failed,
+}
+
+/// Normalized payload every provider must turn its webhook body into.
+#[derive(Debug, Clone)]
+pub struct PaymentEvent {
+    pub reference: String,
+    pub amount: Decimal,
+    pub status: PaymentEventStatus,
+}
+
+/// A payment gateway able to confirm sales through signed webhooks.
+///
+/// `create_sale` only stores a `payment_reference`; each provider is
+/// responsible for verifying its own webhooks and mapping them back to it.
+pub trait PaymentProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn verify_signature(&self, payload: &[u8], signature: &str) -> bool;
+    fn parse_event(&self, payload: &[u8]) -> Result<PaymentEvent, String>;
+}
+
+#[derive(Clone, Default)]
+pub struct PaymentProviders {
+    providers: HashMap<&'static str, Arc<dyn PaymentProvider>>,
+}
+
+impl PaymentProviders {
+    pub fn register(mut self, provider: impl PaymentProvider + 'static) -> Self {
+        self.providers.insert(provider.name(), Arc::new(provider));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn PaymentProvider>> {
+        self.providers.get(name).cloned()
+    }
+
+    /// Providers enabled for this deployment, configured from the environment.
+    pub fn from_env() -> Self {
+        let mut providers = Self::default();
+        if let Some(mock) = mock::MockProvider::from_env() {
+            providers = providers.register(mock);
+        }
+        providers
+    }
+}
+
+impl std::fmt::Debug for PaymentProviders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.providers.keys()).finish()
+    }
+}
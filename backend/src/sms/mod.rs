@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+
+pub mod webhook;
+
+/// A gateway able to deliver text messages, e.g. phone verification codes.
+///
+/// Implementations must never log the message body: it carries one-time codes.
+pub trait SmsSender: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn send<'a>(&'a self, phone: &'a str, message: &'a str) -> BoxFuture<'a, Result<(), String>>;
+}
+
+#[derive(Clone, Default)]
+pub struct SmsGateway {
+    sender: Option<Arc<dyn SmsSender>>,
+}
+
+impl SmsGateway {
+    pub fn new(sender: impl SmsSender + 'static) -> Self {
+        Self { sender: Some(Arc::new(sender)) }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    pub async fn send(&self, phone: &str, message: &str) -> Result<(), String> {
+        match &self.sender {
+            Some(sender) => sender.send(phone, message).await,
+            None => Err("No SMS sender configured".to_string()),
+        }
+    }
+
+    /// Sender enabled for this deployment, configured from the environment.
+    /// Without one, features that need SMS are reported as unavailable.
+    pub fn from_env() -> Self {
+        match webhook::WebhookSender::from_env() {
+            Some(sender) => Self::new(sender),
+            None => Self::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for SmsGateway {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmsGateway")
+            .field("sender", &self.sender.as_ref().map(|s| s.name()))
+            .finish()
+    }
+}
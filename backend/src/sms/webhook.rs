@@ -0,0 +1,54 @@
+use futures::future::BoxFuture;
+use std::env;
+
+use super::SmsSender;
+
+/// Hands each message to an HTTP endpoint (the provider's API or a relay in
+/// front of it) as `{"to": ..., "message": ...}`, authenticated with a bearer token.
+/// Enabled by `SMS_WEBHOOK_URL`; the token comes from `SMS_WEBHOOK_TOKEN`.
+pub struct WebhookSender {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+impl WebhookSender {
+    pub fn new(url: impl Into<String>, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            token,
+        }
+    }
+
+    pub fn from_env() -> Option<Self> {
+        let url = env::var("SMS_WEBHOOK_URL").ok().filter(|u| !u.trim().is_empty())?;
+        let token = env::var("SMS_WEBHOOK_TOKEN").ok().filter(|t| !t.trim().is_empty());
+        Some(Self::new(url, token))
+    }
+}
+
+impl SmsSender for WebhookSender {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn send<'a>(&'a self, phone: &'a str, message: &'a str) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let mut request = self
+                .client
+                .post(&self.url)
+                .json(&serde_json::json!({ "to": phone, "message": message }));
+            if let Some(token) = &self.token {
+                request = request.bearer_auth(token);
+            }
+
+            let resp = request.send().await.map_err(|e| e.to_string())?;
+            if resp.status().is_success() {
+                Ok(())
+            } else {
+                Err(format!("SMS webhook answered {}", resp.status()))
+            }
+        })
+    }
+}
@@ -1,6 +1,13 @@
 use sea_orm::DatabaseConnection;
 
+use crate::events::EventBus;
+use crate::payments::PaymentProviders;
+use crate::sms::SmsGateway;
+
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub conn: DatabaseConnection,
+    pub payments: PaymentProviders,
+    pub events: EventBus,
+    pub sms: SmsGateway,
 }
@@ -12,6 +12,19 @@ pub struct AuthenticatedUser {
     pub username: String,
 }
 
+impl AuthenticatedUser {
+    /// Decodes a session JWT.
+    pub fn from_token(token: &str) -> Option<Self> {
+        let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "secret".into());
+        decode::<Claims>(token, &DecodingKey::from_secret(secret.as_bytes()), &Validation::default())
+            .ok()
+            .map(|token_data| AuthenticatedUser {
+                id: token_data.claims.id,
+                username: token_data.claims.sub,
+            })
+    }
+}
+
 impl FromRequest for AuthenticatedUser {
     type Error = actix_web::Error;
     type Future = Ready<Result<Self, Self::Error>>;
@@ -23,20 +36,10 @@ impl FromRequest for AuthenticatedUser {
             if let Ok(auth_str) = auth_val.to_str() {
                 if auth_str.starts_with("Bearer ") {
                     let token = &auth_str[7..];
-                    let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "secret".into());
-
-                    match decode::<Claims>(
-                        token,
-                        &DecodingKey::from_secret(secret.as_bytes()),
-                        &Validation::default(),
-                    ) {
-                        Ok(token_data) => {
-                            return ready(Ok(AuthenticatedUser {
-                                id: token_data.claims.id,
-                                username: token_data.claims.sub,
-                            }));
-                        }
-                        Err(_) => return ready(Err(ErrorUnauthorized("Invalid token"))),
+
+                    match AuthenticatedUser::from_token(token) {
+                        Some(user) => return ready(Ok(user)),
+                        None => return ready(Err(ErrorUnauthorized("Invalid token"))),
                     }
                 }
             }
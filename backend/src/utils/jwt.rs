@@ -1,4 +1,4 @@
-use jsonwebtoken::{encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -31,3 +31,48 @@ pub fn sign_token(id: i32, username: &str) -> Result<String, actix_web::Error> {
     )
     .map_err(|e| ErrorInternalServerError(e.to_string()))
 }
+
+/// Long enough to open an `EventSource` right after asking for the ticket.
+const STREAM_TICKET_SECONDS: u64 = 60;
+
+/// A short-lived token that only opens one SSE stream. `EventSource` cannot send
+/// headers, so this goes in the URL instead of the session JWT.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StreamTicket {
+    pub id: i32,
+    pub stream: String,
+    pub exp: usize,
+}
+
+pub fn sign_stream_ticket(id: i32, stream: &str) -> Result<String, actix_web::Error> {
+    let secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+    let expiration = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() + STREAM_TICKET_SECONDS;
+
+    let ticket = StreamTicket {
+        id,
+        stream: stream.to_string(),
+        exp: expiration as usize,
+    };
+
+    encode(
+        &Header::default(),
+        &ticket,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .map_err(|e| ErrorInternalServerError(e.to_string()))
+}
+
+/// User the ticket was issued to, if it is valid for `stream` and not expired.
+pub fn verify_stream_ticket(ticket: &str, stream: &str) -> Option<i32> {
+    let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "secret".into());
+    let mut validation = Validation::default();
+    validation.leeway = 0;
+    decode::<StreamTicket>(ticket, &DecodingKey::from_secret(secret.as_bytes()), &validation)
+        .ok()
+        .map(|data| data.claims)
+        .filter(|claims| claims.stream == stream)
+        .map(|claims| claims.id)
+}
@@ -1,3 +1,6 @@
 pub mod hash;
 pub mod jwt;
 pub mod auth;
+pub mod signature;
+pub mod pdf;
+pub mod time;
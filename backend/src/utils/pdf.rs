@@ -0,0 +1,374 @@
+//! Page layout for printable reports: headings, tables and automatic page breaks.
+//! The PDF itself is written by `printpdf` with the built-in Helvetica fonts
+//! (WinAnsi encoding, so Spanish accents work), so no font files are needed.
+
+use printpdf::{
+    BuiltinFont, Color, CustomPdfConformance, IndirectFontRef, Line, Mm, PdfConformance, PdfLayerReference, Point,
+    Pt, Rect, Rgb,
+};
+
+/// A4 portrait, in points.
+pub const A4: (f32, f32) = (595.0, 842.0);
+/// A5 portrait, for receipts.
+pub const A5: (f32, f32) = (420.0, 595.0);
+
+/// Glyph widths (1/1000 em) for ASCII 32..=126, from the Adobe core font metrics.
+/// `printpdf` does not expose metrics for the built-in fonts, and layout needs
+/// them to right-align and truncate cells.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556, 556, 556, 556,
+    556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667,
+    556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556, 333, 556,
+    556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722,
+    500, 500, 500, 334, 260, 334, 584,
+];
+
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556, 556, 556, 556,
+    556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722,
+    611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556, 333, 556,
+    611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778,
+    556, 556, 500, 389, 280, 389, 584,
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// Maps text to WinAnsi bytes, only used to measure it.
+/// Characters outside Latin-1 (e.g. emoji) are dropped.
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .filter_map(|c| match c as u32 {
+            0x20..=0x7E | 0xA0..=0xFF => Some(c as u32 as u8),
+            0x2013 | 0x2014 => Some(b'-'),
+            0x2022 => Some(0x95),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Width of `text` in points.
+pub fn text_width(text: &str, font: Font, size: f32) -> f32 {
+    let table = match font {
+        Font::Regular => &HELVETICA_WIDTHS,
+        Font::Bold => &HELVETICA_BOLD_WIDTHS,
+    };
+    let units: u32 = encode(text)
+        .into_iter()
+        .map(|b| match b {
+            0x20..=0x7E => table[(b - 0x20) as usize] as u32,
+            // Accented Latin-1 letters are close to their base letter's width
+            _ => 556,
+        })
+        .sum();
+    units as f32 * size / 1000.0
+}
+
+/// Keeps what the built-in fonts can print: Latin-1, with dashes and bullets
+/// replaced by their WinAnsi look-alikes.
+fn printable(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| match c as u32 {
+            0x20..=0x7E | 0xA0..=0xFF | 0x2022 => Some(c),
+            0x2013 | 0x2014 => Some('-'),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A drawing operation, in points from the bottom-left corner of the page.
+enum Op {
+    Text { x: f32, y: f32, text: String, font: Font, size: f32, gray: f32 },
+    Rule { y: f32, gray: f32 },
+    Rect { x: f32, y: f32, width: f32, height: f32, rgb: (f32, f32, f32) },
+}
+
+/// A table column: header, width in points and alignment.
+pub struct Column<'a> {
+    pub title: &'a str,
+    pub width: f32,
+    pub align: Align,
+}
+
+pub struct PdfDocument {
+    width: f32,
+    height: f32,
+    margin: f32,
+    pages: Vec<Vec<Op>>,
+    current: Vec<Op>,
+    /// Baseline of the next line, from the bottom of the page
+    y: f32,
+    footer: String,
+}
+
+impl PdfDocument {
+    pub fn new(footer: &str) -> Self {
+        Self::with_page(footer, A4, 50.0)
+    }
+
+    pub fn with_page(footer: &str, (width, height): (f32, f32), margin: f32) -> Self {
+        PdfDocument {
+            width,
+            height,
+            margin,
+            pages: Vec::new(),
+            current: Vec::new(),
+            y: height - margin,
+            footer: footer.to_string(),
+        }
+    }
+
+    pub fn content_width(&self) -> f32 {
+        self.width - 2.0 * self.margin
+    }
+
+    fn new_page(&mut self) {
+        let page = std::mem::take(&mut self.current);
+        self.pages.push(page);
+        self.y = self.height - self.margin;
+    }
+
+    /// Starts a new page unless `height` more points fit on this one.
+    pub fn ensure_space(&mut self, height: f32) {
+        if self.y - height < self.margin + 20.0 {
+            self.new_page();
+        }
+    }
+
+    fn draw_text(&mut self, x: f32, y: f32, text: &str, font: Font, size: f32) {
+        self.current.push(Op::Text { x, y, text: printable(text), font, size, gray: 0.0 });
+    }
+
+    /// Text on the current line, fitted into a `width` wide cell starting at `x`.
+    fn draw_aligned(&mut self, x: f32, width: f32, text: &str, font: Font, size: f32, align: Align) {
+        let text = truncate(text, width - 4.0, font, size);
+        let x = match align {
+            Align::Left => x,
+            Align::Right => x + width - text_width(&text, font, size),
+        };
+        self.draw_text(x, self.y, &text, font, size);
+    }
+
+    fn rule(&mut self, y: f32, gray: f32) {
+        self.current.push(Op::Rule { y, gray });
+    }
+
+    pub fn title(&mut self, text: &str) {
+        self.ensure_space(30.0);
+        self.y -= 18.0;
+        self.draw_text(self.margin, self.y, text, Font::Bold, 18.0);
+        self.y -= 8.0;
+    }
+
+    pub fn heading(&mut self, text: &str) {
+        self.ensure_space(40.0);
+        self.y -= 22.0;
+        self.draw_text(self.margin, self.y, text, Font::Bold, 13.0);
+        self.y -= 5.0;
+        self.rule(self.y, 0.6);
+        self.y -= 4.0;
+    }
+
+    /// Wrapped paragraph.
+    pub fn paragraph(&mut self, text: &str, size: f32) {
+        let max = self.content_width();
+        for line in wrap(text, max, Font::Regular, size) {
+            self.ensure_space(size + 4.0);
+            self.y -= size + 4.0;
+            self.draw_text(self.margin, self.y, &line, Font::Regular, size);
+        }
+    }
+
+    /// Label on the left, value right-aligned.
+    pub fn key_value(&mut self, label: &str, value: &str, bold: bool) {
+        let font = if bold { Font::Bold } else { Font::Regular };
+        self.ensure_space(16.0);
+        self.y -= 16.0;
+        self.draw_text(self.margin, self.y, label, font, 10.0);
+        let width = self.content_width();
+        self.draw_aligned(self.margin, width, value, font, 10.0, Align::Right);
+    }
+
+    /// Single line centered on the page.
+    pub fn centered(&mut self, text: &str, size: f32, bold: bool) {
+        let font = if bold { Font::Bold } else { Font::Regular };
+        self.ensure_space(size + 6.0);
+        self.y -= size + 6.0;
+        let x = (self.width - text_width(text, font, size)) / 2.0;
+        self.draw_text(x, self.y, text, font, size);
+    }
+
+    /// QR code of `data`, `size` points wide and centered.
+    pub fn qr_code(&mut self, data: &str, size: f32) {
+        let Ok(code) = qrcode::QrCode::new(data.as_bytes()) else { return };
+        let modules = code.width();
+        // Two modules of quiet zone on every side
+        let cell = size / (modules + 4) as f32;
+        self.ensure_space(size + 4.0);
+        self.y -= size + 4.0;
+        let left = (self.width - size) / 2.0 + 2.0 * cell;
+        let top = self.y + size - 2.0 * cell;
+        for (index, color) in code.to_colors().into_iter().enumerate() {
+            if color == qrcode::Color::Dark {
+                let (row, col) = (index / modules, index % modules);
+                self.current.push(Op::Rect {
+                    x: left + col as f32 * cell,
+                    y: top - (row + 1) as f32 * cell,
+                    width: cell,
+                    height: cell,
+                    rgb: (0.0, 0.0, 0.0),
+                });
+            }
+        }
+    }
+
+    /// Horizontal bar filled to `fraction` (0..=1).
+    pub fn progress_bar(&mut self, fraction: f64) {
+        let fraction = fraction.clamp(0.0, 1.0) as f32;
+        let width = self.content_width();
+        self.ensure_space(20.0);
+        self.y -= 16.0;
+        let (x, y) = (self.margin, self.y);
+        self.current.push(Op::Rect { x, y, width, height: 10.0, rgb: (0.9, 0.9, 0.9) });
+        self.current.push(Op::Rect { x, y, width: width * fraction, height: 10.0, rgb: (0.2, 0.6, 0.4) });
+    }
+
+    /// Table with a bold header row, repeated after page breaks.
+    pub fn table(&mut self, columns: &[Column], rows: &[Vec<String>]) {
+        let row_height = 14.0;
+        self.ensure_space(row_height * 2.0 + 4.0);
+        self.table_header(columns);
+        for row in rows {
+            if self.y - row_height < self.margin + 20.0 {
+                self.new_page();
+                self.table_header(columns);
+            }
+            self.y -= row_height;
+            let mut x = self.margin;
+            for (column, cell) in columns.iter().zip(row) {
+                self.draw_aligned(x, column.width, cell, Font::Regular, 9.0, column.align);
+                x += column.width;
+            }
+        }
+        self.y -= 4.0;
+    }
+
+    fn table_header(&mut self, columns: &[Column]) {
+        self.y -= 14.0;
+        let mut x = self.margin;
+        for column in columns {
+            self.draw_aligned(x, column.width, column.title, Font::Bold, 9.0, column.align);
+            x += column.width;
+        }
+        self.rule(self.y - 4.0, 0.8);
+        self.y -= 4.0;
+    }
+
+    pub fn finish(mut self) -> Result<Vec<u8>, String> {
+        if !self.current.is_empty() || self.pages.is_empty() {
+            self.new_page();
+        }
+        let page_count = self.pages.len();
+        let (width, height) = (Mm::from(Pt(self.width)), Mm::from(Pt(self.height)));
+
+        let (doc, first_page, first_layer) = printpdf::PdfDocument::new(self.footer.as_str(), width, height, "Contenido");
+        // Plain PDF: PDF/A would require embedding fonts and an ICC profile
+        let doc = doc.with_conformance(PdfConformance::Custom(CustomPdfConformance {
+            requires_icc_profile: false,
+            requires_xmp_metadata: false,
+            ..Default::default()
+        }));
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| e.to_string())?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(|e| e.to_string())?;
+
+        for (index, ops) in self.pages.iter().enumerate() {
+            let (page, layer) = if index == 0 {
+                (first_page, first_layer)
+            } else {
+                doc.add_page(width, height, "Contenido")
+            };
+            let layer = doc.get_page(page).get_layer(layer);
+            for op in ops {
+                self.render(&layer, op, &regular, &bold);
+            }
+            let footer = Op::Text {
+                x: self.margin,
+                y: self.margin - 20.0,
+                text: printable(&format!("{} - Página {} de {}", self.footer, index + 1, page_count)),
+                font: Font::Regular,
+                size: 8.0,
+                gray: 0.4,
+            };
+            self.render(&layer, &footer, &regular, &bold);
+        }
+
+        doc.save_to_bytes().map_err(|e| e.to_string())
+    }
+
+    fn render(&self, layer: &PdfLayerReference, op: &Op, regular: &IndirectFontRef, bold: &IndirectFontRef) {
+        let pt = |v: f32| Mm::from(Pt(v));
+        match op {
+            Op::Text { x, y, text, font, size, gray } => {
+                layer.set_fill_color(rgb(*gray, *gray, *gray));
+                let font = if *font == Font::Bold { bold } else { regular };
+                layer.use_text(text.as_str(), *size, pt(*x), pt(*y), font);
+            }
+            Op::Rule { y, gray } => {
+                layer.set_outline_color(rgb(*gray, *gray, *gray));
+                layer.set_outline_thickness(0.5);
+                layer.add_line(Line {
+                    points: vec![
+                        (Point::new(pt(self.margin), pt(*y)), false),
+                        (Point::new(pt(self.width - self.margin), pt(*y)), false),
+                    ],
+                    is_closed: false,
+                });
+            }
+            Op::Rect { x, y, width, height, rgb: (r, g, b) } => {
+                layer.set_fill_color(rgb(*r, *g, *b));
+                layer.add_rect(Rect::new(pt(*x), pt(*y), pt(x + width), pt(y + height)));
+            }
+        }
+    }
+}
+
+fn rgb(r: f32, g: f32, b: f32) -> Color {
+    Color::Rgb(Rgb::new(r, g, b, None))
+}
+
+fn truncate(text: &str, width: f32, font: Font, size: f32) -> String {
+    if text_width(text, font, size) <= width {
+        return text.to_string();
+    }
+    let mut out: String = text.to_string();
+    while !out.is_empty() && text_width(&format!("{}...", out), font, size) > width {
+        out.pop();
+    }
+    format!("{}...", out)
+}
+
+fn wrap(text: &str, width: f32, font: Font, size: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+        if text_width(&candidate, font, size) > width && !line.is_empty() {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        } else {
+            line = candidate;
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
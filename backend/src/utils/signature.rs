@@ -0,0 +1,42 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Constant-time comparison against a hex encoded signature
+pub fn verify_hmac_sha256(secret: &str, payload: &[u8], signature: &str) -> bool {
+    let signature = signature.trim().trim_start_matches("sha256=");
+    let expected = match hex::decode(signature) {
+        Ok(bytes) => bytes,
+        Err(_) => return false,
+    };
+
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(payload);
+    mac.verify_slice(&expected).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // HMAC-SHA256("key", "The quick brown fox jumps over the lazy dog")
+    const SIGNATURE: &str = "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8";
+    const PAYLOAD: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+    #[test]
+    fn accepts_a_valid_signature() {
+        assert!(verify_hmac_sha256("key", PAYLOAD, SIGNATURE));
+        assert!(verify_hmac_sha256("key", PAYLOAD, &format!("sha256={}", SIGNATURE)));
+        assert!(verify_hmac_sha256("key", PAYLOAD, &format!(" {} ", SIGNATURE.to_uppercase())));
+    }
+
+    #[test]
+    fn rejects_a_wrong_secret_payload_or_signature() {
+        assert!(!verify_hmac_sha256("other", PAYLOAD, SIGNATURE));
+        assert!(!verify_hmac_sha256("key", b"tampered", SIGNATURE));
+        assert!(!verify_hmac_sha256("key", PAYLOAD, &SIGNATURE[..62]));
+        assert!(!verify_hmac_sha256("key", PAYLOAD, "not-hex"));
+        assert!(!verify_hmac_sha256("key", PAYLOAD, ""));
+    }
+}
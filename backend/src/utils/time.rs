@@ -0,0 +1,38 @@
+use std::env;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+
+/// Bolivia has no daylight saving time, so a fixed offset is enough.
+const DEFAULT_UTC_OFFSET: &str = "-04:00";
+
+/// UTC offset where the kermesses take place, from `KERMESSE_UTC_OFFSET`
+/// (e.g. `-04:00`). Every "today", day boundary and displayed time in the API
+/// goes through here so they do not depend on the server's own time zone.
+pub fn kermesse_offset() -> FixedOffset {
+    static OFFSET: OnceLock<FixedOffset> = OnceLock::new();
+    *OFFSET.get_or_init(|| {
+        env::var("KERMESSE_UTC_OFFSET")
+            .ok()
+            .and_then(|value| parse_offset(&value))
+            .or_else(|| parse_offset(DEFAULT_UTC_OFFSET))
+            .expect("default UTC offset is valid")
+    })
+}
+
+fn parse_offset(value: &str) -> Option<FixedOffset> {
+    value.trim().parse().ok()
+}
+
+pub fn now() -> DateTime<FixedOffset> {
+    Utc::now().with_timezone(&kermesse_offset())
+}
+
+pub fn today() -> NaiveDate {
+    now().date_naive()
+}
+
+/// Start of `date` in the kermesses' time zone.
+pub fn start_of_day(date: NaiveDate) -> Option<DateTime<FixedOffset>> {
+    date.and_hms_opt(0, 0, 0)?.and_local_timezone(kermesse_offset()).single()
+}
@@ -0,0 +1,270 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use serde::Deserialize;
+use gloo_storage::{LocalStorage, Storage};
+use crate::context::UserContext;
+use crate::components::organizer_dashboard::deserialize_price;
+
+/// Tracking tokens of orders placed on this device without an account.
+pub const GUEST_TOKENS_KEY: &str = "guest_order_tokens";
+
+#[derive(Deserialize)]
+struct ClaimResponse {
+    claimed: usize,
+}
+
+/// What the public tracking endpoint tells about a remembered order.
+#[derive(Clone, PartialEq, Deserialize)]
+struct RememberedOrder {
+    sale_id: i32,
+    kermesse_name: String,
+    #[serde(deserialize_with = "deserialize_price")]
+    total_amount: f64,
+    created_at: String,
+}
+
+/// Remembers a guest order so it can be claimed once the buyer signs in.
+pub fn remember_guest_order(token: &str) {
+    let mut tokens: Vec<String> = LocalStorage::get(GUEST_TOKENS_KEY).unwrap_or_default();
+    tokens.push(token.to_string());
+    let _ = LocalStorage::set(GUEST_TOKENS_KEY, tokens);
+}
+
+/// Drops the remembered guest orders, e.g. on logout, so the next account on a
+/// shared device is never offered someone else's orders.
+pub fn forget_guest_orders() {
+    LocalStorage::delete(GUEST_TOKENS_KEY);
+}
+
+async fn post_claim(path: &str, token: &str, body: serde_json::Value) -> Result<usize, String> {
+    let resp = Request::post(&format!("http://127.0.0.1:8080{}", path))
+        .header("Authorization", &format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|_| "Error de conexión.".to_string())?;
+    if !resp.ok() {
+        return Err(resp.text().await.unwrap_or_default());
+    }
+    Ok(resp.json::<ClaimResponse>().await.map(|r| r.claimed).unwrap_or(0))
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub on_claimed: Callback<()>,
+}
+
+#[function_component(ClaimOrders)]
+pub fn claim_orders(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let link_ref = use_node_ref();
+    let phone_ref = use_node_ref();
+    let code_ref = use_node_ref();
+    let code_sent = use_state(|| false);
+    let message = use_state(|| None::<String>);
+    let remembered = use_state(Vec::<(String, RememberedOrder)>::new);
+
+    let token = user_ctx.user.as_ref().map(|u| u.token.clone()).unwrap_or_default();
+
+    // Guest orders placed on this device are only shown; the user decides whether they are theirs
+    {
+        let remembered = remembered.clone();
+        use_effect_with((), move |_| {
+            let tokens: Vec<String> = LocalStorage::get(GUEST_TOKENS_KEY).unwrap_or_default();
+            if !tokens.is_empty() {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let mut found = Vec::new();
+                    for tracking_token in tokens {
+                        let url = format!("http://127.0.0.1:8080/orders/track/{}", tracking_token);
+                        if let Ok(resp) = Request::get(&url).send().await {
+                            if let Ok(order) = resp.json::<RememberedOrder>().await {
+                                found.push((tracking_token, order));
+                            }
+                        }
+                    }
+                    remembered.set(found);
+                });
+            }
+            || ()
+        });
+    }
+
+    let report = {
+        let message = message.clone();
+        let on_claimed = props.on_claimed.clone();
+        move |result: Result<usize, String>| match result {
+            Ok(0) => message.set(Some("No se encontraron pedidos nuevos para vincular.".to_string())),
+            Ok(n) => {
+                message.set(Some(format!("{} pedido(s) vinculados a tu cuenta.", n)));
+                on_claimed.emit(());
+            }
+            Err(e) => message.set(Some(e)),
+        }
+    };
+
+    let on_claim_link = {
+        let link_ref = link_ref.clone();
+        let token = token.clone();
+        let report = report.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let input = link_ref.cast::<web_sys::HtmlInputElement>().unwrap();
+            let tracking_token = input.value();
+            if tracking_token.trim().is_empty() {
+                return;
+            }
+            let token = token.clone();
+            let report = report.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = post_claim("/my-orders/claim", &token, serde_json::json!({ "tracking_token": tracking_token })).await;
+                if result.is_ok() {
+                    input.set_value("");
+                }
+                report(result);
+            });
+        })
+    };
+
+    let on_claim_remembered = {
+        let token = token.clone();
+        let remembered = remembered.clone();
+        let report = report.clone();
+        Callback::from(move |_| {
+            let token = token.clone();
+            let remembered = remembered.clone();
+            let report = report.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut claimed = 0;
+                let mut failed = Vec::new();
+                for (tracking_token, _) in remembered.iter() {
+                    match post_claim("/my-orders/claim", &token, serde_json::json!({ "tracking_token": tracking_token })).await {
+                        Ok(n) => claimed += n,
+                        Err(_) => failed.push(tracking_token.clone()),
+                    }
+                }
+                // Keep only what could not be claimed, so it can be retried
+                if failed.is_empty() {
+                    forget_guest_orders();
+                } else {
+                    let _ = LocalStorage::set(GUEST_TOKENS_KEY, &failed);
+                }
+                remembered.set(remembered.iter().filter(|(t, _)| failed.contains(t)).cloned().collect());
+                report(Ok(claimed));
+            });
+        })
+    };
+
+    let on_forget_remembered = {
+        let remembered = remembered.clone();
+        Callback::from(move |_| {
+            forget_guest_orders();
+            remembered.set(Vec::new());
+        })
+    };
+
+    let on_send_code = {
+        let phone_ref = phone_ref.clone();
+        let token = token.clone();
+        let code_sent = code_sent.clone();
+        let message = message.clone();
+        Callback::from(move |_| {
+            let phone = phone_ref.cast::<web_sys::HtmlInputElement>().unwrap().value();
+            let token = token.clone();
+            let code_sent = code_sent.clone();
+            let message = message.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let resp = Request::post("http://127.0.0.1:8080/my-orders/claim/phone/code")
+                    .header("Authorization", &format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .body(serde_json::json!({ "phone": phone }).to_string())
+                    .send()
+                    .await;
+                match resp {
+                    Ok(resp) if resp.ok() => {
+                        code_sent.set(true);
+                        message.set(Some("Te enviamos un código por SMS.".to_string()));
+                    }
+                    Ok(resp) if resp.status() == 503 => {
+                        message.set(Some("La verificación por SMS no está disponible. Usa el enlace de seguimiento.".to_string()));
+                    }
+                    Ok(resp) => message.set(Some(resp.text().await.unwrap_or_default())),
+                    Err(_) => message.set(Some("Error de conexión.".to_string())),
+                }
+            });
+        })
+    };
+
+    let on_verify = {
+        let phone_ref = phone_ref.clone();
+        let code_ref = code_ref.clone();
+        let code_sent = code_sent.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let phone = phone_ref.cast::<web_sys::HtmlInputElement>().unwrap().value();
+            let code = code_ref.cast::<web_sys::HtmlInputElement>().map(|i| i.value()).unwrap_or_default();
+            let token = token.clone();
+            let report = report.clone();
+            let code_sent = code_sent.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = post_claim("/my-orders/claim/phone", &token, serde_json::json!({ "phone": phone, "code": code })).await;
+                if result.is_ok() {
+                    code_sent.set(false);
+                }
+                report(result);
+            });
+        })
+    };
+
+    html! {
+        <div class="bg-white rounded-xl shadow-md p-6 mb-8">
+            <h2 class="text-lg font-bold text-gray-800 mb-1">{ "¿Pediste sin cuenta?" }</h2>
+            <p class="text-sm text-gray-500 mb-4">{ "Vincula tus pedidos anteriores con el enlace de seguimiento o verificando tu teléfono." }</p>
+
+            if !remembered.is_empty() {
+                <div class="bg-yellow-50 border border-yellow-200 rounded-lg p-4 mb-6">
+                    <p class="font-bold text-gray-800">{ "Pedidos hechos sin cuenta en este dispositivo" }</p>
+                    <p class="text-sm text-gray-600 mb-3">{ "Vincúlalos solo si los hiciste tú. En un equipo compartido, elige \"No son míos\"." }</p>
+                    <ul class="text-sm divide-y divide-yellow-100 mb-3">
+                        {
+                            remembered.iter().map(|(_, order)| html! {
+                                <li class="flex justify-between py-1">
+                                    <span>{ format!("#{} · {} · {}", order.sale_id, order.kermesse_name, order.created_at.split('T').next().unwrap_or_default()) }</span>
+                                    <span class="font-bold">{ format!("Bs. {:.2}", order.total_amount) }</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                    <div class="flex gap-2">
+                        <button onclick={on_claim_remembered} class="bg-gray-800 text-white font-bold px-4 py-2 rounded-lg hover:bg-gray-700 transition text-sm">{ "Sí, vincular a mi cuenta" }</button>
+                        <button onclick={on_forget_remembered} class="bg-white border text-gray-700 font-bold px-4 py-2 rounded-lg hover:bg-gray-100 transition text-sm">{ "No son míos" }</button>
+                    </div>
+                </div>
+            }
+
+            <div class="grid grid-cols-1 md:grid-cols-2 gap-6">
+                <form onsubmit={on_claim_link} class="space-y-2">
+                    <label class="block text-sm font-medium text-gray-700">{ "Enlace de seguimiento" }</label>
+                    <input ref={link_ref} type="text" placeholder="https://.../orders/track/..." class="w-full rounded-lg border border-gray-300 p-2" />
+                    <button type="submit" class="w-full bg-gray-800 text-white font-bold py-2 rounded-lg hover:bg-gray-700 transition">{ "Vincular pedido" }</button>
+                </form>
+
+                <form onsubmit={on_verify} class="space-y-2">
+                    <label class="block text-sm font-medium text-gray-700">{ "Teléfono usado en el pedido" }</label>
+                    <div class="flex gap-2">
+                        <input ref={phone_ref} type="tel" placeholder="70012345" class="flex-1 rounded-lg border border-gray-300 p-2" />
+                        <button type="button" onclick={on_send_code} class="px-3 bg-gray-100 text-gray-800 font-bold rounded-lg hover:bg-gray-200 transition text-sm">{ "Enviar código" }</button>
+                    </div>
+                    if *code_sent {
+                        <input ref={code_ref} type="text" inputmode="numeric" maxlength="6" placeholder="Código de 6 dígitos" class="w-full rounded-lg border border-gray-300 p-2 font-mono tracking-widest" />
+                        <button type="submit" class="w-full bg-primary text-white font-bold py-2 rounded-lg hover:bg-red-600 transition">{ "Verificar y vincular" }</button>
+                    }
+                </form>
+            </div>
+
+            if let Some(msg) = (*message).clone() {
+                <p class="mt-4 text-sm font-medium text-gray-700">{ msg }</p>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,159 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use serde::{Deserialize, Serialize};
+use crate::context::UserContext;
+use crate::components::organizer_dashboard::deserialize_price;
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct DonorWallEntry {
+    pub donor_name: String,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub amount: f64,
+    pub message: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Serialize)]
+struct CreateDonationRequest {
+    amount: f64,
+    donor_name: Option<String>,
+    is_anonymous: bool,
+    message: Option<String>,
+    payment_method: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(DonorWall)]
+pub fn donor_wall(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let entries = use_state(Vec::<DonorWallEntry>::new);
+    let show_form = use_state(|| false);
+    let kermesse_id = props.kermesse_id;
+
+    let amount_ref = use_node_ref();
+    let name_ref = use_node_ref();
+    let anonymous_ref = use_node_ref();
+    let message_ref = use_node_ref();
+    let method_ref = use_node_ref();
+
+    {
+        let entries = entries.clone();
+        use_effect_with(kermesse_id, move |id| {
+            let id = *id;
+            wasm_bindgen_futures::spawn_local(async move {
+                let url = format!("http://127.0.0.1:8080/kermesses/{}/donations/wall", id);
+                if let Ok(resp) = Request::get(&url).send().await {
+                    if let Ok(data) = resp.json::<Vec<DonorWallEntry>>().await {
+                        entries.set(data);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_submit = {
+        let show_form = show_form.clone();
+        let token = user_ctx.user.as_ref().map(|u| u.token.clone());
+        let amount_ref = amount_ref.clone();
+        let name_ref = name_ref.clone();
+        let anonymous_ref = anonymous_ref.clone();
+        let message_ref = message_ref.clone();
+        let method_ref = method_ref.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let amount = amount_ref.cast::<HtmlInputElement>().unwrap().value().parse::<f64>().unwrap_or(0.0);
+            if amount <= 0.0 {
+                gloo_dialogs::alert("Ingresa un monto válido.");
+                return;
+            }
+            let name = name_ref.cast::<HtmlInputElement>().unwrap().value();
+            let message = message_ref.cast::<HtmlTextAreaElement>().unwrap().value();
+            let request = CreateDonationRequest {
+                amount,
+                donor_name: if name.trim().is_empty() { None } else { Some(name) },
+                is_anonymous: anonymous_ref.cast::<HtmlInputElement>().unwrap().checked(),
+                message: if message.trim().is_empty() { None } else { Some(message) },
+                payment_method: method_ref.cast::<HtmlSelectElement>().unwrap().value(),
+            };
+            let token = token.clone();
+            let show_form = show_form.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let body = serde_json::to_string(&request).unwrap();
+                let mut req = Request::post(&format!("http://127.0.0.1:8080/kermesses/{}/donations", kermesse_id))
+                    .header("Content-Type", "application/json")
+                    .body(body);
+                if let Some(token) = &token {
+                    req = req.header("Authorization", &format!("Bearer {}", token));
+                }
+
+                match req.send().await {
+                    Ok(resp) if resp.ok() => {
+                        gloo_dialogs::alert("¡Gracias por tu donación! Aparecerá cuando el organizador la confirme.");
+                        show_form.set(false);
+                    }
+                    _ => gloo_dialogs::alert("Error al registrar la donación."),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="bg-white rounded-3xl shadow-xl p-8">
+            <h3 class="text-xl font-bold mb-6 text-gray-800 flex items-center gap-2">
+                <span class="text-pink-500">{"💝"}</span> { "Muro de Donantes" }
+            </h3>
+
+            if *show_form {
+                <form onsubmit={on_submit} class="space-y-3 mb-6">
+                    <input ref={amount_ref} type="number" min="1" step="0.5" required=true class="w-full rounded-lg border-gray-300 p-2 border" placeholder="Monto (Bs.)" />
+                    <input ref={name_ref} type="text" class="w-full rounded-lg border-gray-300 p-2 border" placeholder="Tu nombre (opcional)" />
+                    <label class="flex items-center gap-2 text-sm text-gray-600">
+                        <input ref={anonymous_ref} type="checkbox" />
+                        { "Donar de forma anónima" }
+                    </label>
+                    <textarea ref={message_ref} rows="2" class="w-full rounded-lg border-gray-300 p-2 border" placeholder="Mensaje para el beneficiario (opcional)"></textarea>
+                    <select ref={method_ref} class="w-full rounded-lg border-gray-300 p-2 border">
+                        <option value="QR">{ "📱 Pago QR" }</option>
+                        <option value="TRANSFER">{ "🏦 Transferencia" }</option>
+                        <option value="CASH">{ "💵 Efectivo" }</option>
+                    </select>
+                    <div class="flex gap-2">
+                        <button type="submit" class="flex-1 bg-pink-600 text-white font-bold py-2 rounded-lg hover:bg-pink-700 transition">{ "Donar" }</button>
+                        <button type="button" onclick={let sf = show_form.clone(); Callback::from(move |_| sf.set(false))} class="flex-1 bg-gray-100 text-gray-700 font-bold py-2 rounded-lg hover:bg-gray-200 transition">{ "Cancelar" }</button>
+                    </div>
+                </form>
+            } else {
+                <button onclick={let sf = show_form.clone(); Callback::from(move |_| sf.set(true))} class="w-full bg-pink-50 text-pink-700 font-bold py-3 rounded-xl hover:bg-pink-100 transition mb-6">
+                    { "Quiero donar sin comprar" }
+                </button>
+            }
+
+            if entries.is_empty() {
+                <p class="text-center text-gray-400 text-sm">{ "Sé el primero en donar." }</p>
+            } else {
+                <div class="space-y-3 max-h-80 overflow-y-auto">
+                    {
+                        entries.iter().map(|entry| html! {
+                            <div class="p-3 bg-gray-50 rounded-xl">
+                                <div class="flex justify-between">
+                                    <span class="font-bold text-gray-800">{ &entry.donor_name }</span>
+                                    <span class="text-pink-600 font-bold">{ format!("Bs. {:.2}", entry.amount) }</span>
+                                </div>
+                                if let Some(msg) = &entry.message {
+                                    <p class="text-sm text-gray-500 italic mt-1">{ format!("\"{}\"", msg) }</p>
+                                }
+                            </div>
+                        }).collect::<Html>()
+                    }
+                </div>
+            }
+        </div>
+    }
+}
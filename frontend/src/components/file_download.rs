@@ -0,0 +1,48 @@
+use reqwasm::http::Request;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlAnchorElement;
+
+/// Fetches a protected file with the user's token and returns an object URL
+/// for it. Revoke it with `web_sys::Url::revoke_object_url` once unused.
+pub async fn fetch_object_url(url: &str, token: &str) -> Option<String> {
+    let resp = Request::get(url)
+        .header("Authorization", &format!("Bearer {}", token))
+        .send()
+        .await
+        .ok()?;
+    if !resp.ok() {
+        return None;
+    }
+    let blob = wasm_bindgen_futures::JsFuture::from(resp.as_raw().blob().ok()?)
+        .await
+        .ok()?
+        .dyn_into::<web_sys::Blob>()
+        .ok()?;
+    web_sys::Url::create_object_url_with_blob(&blob).ok()
+}
+
+/// Saves a protected file (exports, reports, receipts) as `file_name`.
+/// Plain links cannot send the `Authorization` header, so the file is
+/// downloaded first and handed to the browser as a blob.
+pub fn download(url: String, token: String, file_name: String) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let Some(object_url) = fetch_object_url(&url, &token).await else {
+            gloo_dialogs::alert("No se pudo descargar el archivo.");
+            return;
+        };
+        let link = web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.create_element("a").ok())
+            .and_then(|e| e.dyn_into::<HtmlAnchorElement>().ok());
+        if let Some(link) = link {
+            link.set_href(&object_url);
+            link.set_download(&file_name);
+            link.click();
+        }
+        // Give the browser a moment to start the download before releasing the blob
+        gloo_timers::callback::Timeout::new(10_000, move || {
+            let _ = web_sys::Url::revoke_object_url(&object_url);
+        })
+        .forget();
+    });
+}
@@ -0,0 +1,134 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use web_sys::HtmlInputElement;
+use serde::Deserialize;
+use crate::components::organizer_dashboard::{deserialize_option_price, deserialize_price};
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct FundraisingProgress {
+    pub name: String,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub total_raised: f64,
+    #[serde(default, deserialize_with = "deserialize_option_price")]
+    pub financial_goal: Option<f64>,
+    pub progress_percentage: Option<f64>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+fn summary(total_raised: f64, financial_goal: Option<f64>, progress_percentage: Option<f64>) -> String {
+    match (financial_goal, progress_percentage) {
+        (Some(goal), Some(percentage)) => format!("Bs. {:.0} de Bs. {:.0} · {:.0}%", total_raised, goal, percentage),
+        _ => format!("Bs. {:.0} recaudados", total_raised),
+    }
+}
+
+fn bar_width(progress_percentage: Option<f64>) -> f64 {
+    progress_percentage.unwrap_or(0.0).clamp(0.0, 100.0)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SummaryProps {
+    pub total_raised: f64,
+    pub financial_goal: Option<f64>,
+    pub progress_percentage: Option<f64>,
+}
+
+/// Slim progress for the kermesse cards, drawn from figures the listing already carries.
+#[function_component(FundraisingProgressSummary)]
+pub fn fundraising_progress_summary(props: &SummaryProps) -> Html {
+    // Nothing worth showing on a card until there is a goal or some money raised
+    if props.progress_percentage.is_none() && props.total_raised <= 0.0 {
+        return html! {};
+    }
+    html! {
+        <div class="mb-4">
+            if props.progress_percentage.is_some() {
+                <div class="w-full bg-gray-100 rounded-full h-2 overflow-hidden mb-1">
+                    <div class="bg-green-500 h-2 rounded-full" style={format!("width: {:.1}%", bar_width(props.progress_percentage))}></div>
+                </div>
+            }
+            <p class="text-xs font-medium text-gray-500">{ summary(props.total_raised, props.financial_goal, props.progress_percentage) }</p>
+        </div>
+    }
+}
+
+/// Public progress towards the financial goal, from the cacheable `/progress` endpoint.
+#[function_component(FundraisingProgressBar)]
+pub fn fundraising_progress_bar(props: &Props) -> Html {
+    let progress = use_state(|| None::<FundraisingProgress>);
+    let kermesse_id = props.kermesse_id;
+
+    {
+        let progress = progress.clone();
+        use_effect_with(kermesse_id, move |id| {
+            let url = format!("http://127.0.0.1:8080/kermesses/{}/progress", id);
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(resp) = Request::get(&url).send().await {
+                    if let Ok(data) = resp.json::<FundraisingProgress>().await {
+                        progress.set(Some(data));
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let Some(data) = &*progress else {
+        return html! {};
+    };
+
+    let bar_width = bar_width(data.progress_percentage);
+    let summary = summary(data.total_raised, data.financial_goal, data.progress_percentage);
+
+    let badge_url = format!("http://127.0.0.1:8080/kermesses/{}/progress/badge.svg", kermesse_id);
+    let embed_url = format!("http://127.0.0.1:8080/kermesses/{}/progress/embed", kermesse_id);
+    let page_url = format!("http://127.0.0.1:8000/kermesses/{}", kermesse_id);
+    let badge_code = format!(
+        "<a href=\"{}\"><img src=\"{}\" alt=\"Progreso de {}\" width=\"320\" height=\"68\"></a>",
+        page_url, badge_url, data.name.replace('"', "&quot;")
+    );
+    let iframe_code = format!(
+        "<iframe src=\"{}\" width=\"340\" height=\"150\" style=\"border:0\" title=\"Progreso de la kermesse\"></iframe>",
+        embed_url
+    );
+    let select_all = Callback::from(|e: FocusEvent| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        input.select();
+    });
+
+    html! {
+        <div class="bg-white rounded-3xl shadow-xl p-8 border border-gray-100">
+            <h3 class="text-xl font-bold mb-4 text-gray-800 flex items-center gap-2">
+                <span>{"🎯"}</span> { "Meta de Recaudación" }
+            </h3>
+            if data.progress_percentage.is_some() {
+                <div class="w-full bg-gray-200 rounded-full h-4 overflow-hidden mb-2">
+                    <div class="bg-gradient-to-r from-green-400 to-green-600 h-4 rounded-full transition-all duration-500" style={format!("width: {:.1}%", bar_width)}></div>
+                </div>
+            }
+            <p class="text-sm font-bold text-gray-700">{ summary }</p>
+            if data.progress_percentage.is_some_and(|p| p >= 100.0) {
+                <p class="text-sm text-green-600 font-bold mt-1">{ "¡Meta alcanzada! 🎉" }</p>
+            }
+
+            <details class="mt-4 text-sm">
+                <summary class="cursor-pointer text-gray-500 hover:text-gray-700 font-medium">{ "Insertar en tu sitio web" }</summary>
+                <div class="mt-3 space-y-3">
+                    <img src={badge_url} alt="Vista previa" class="max-w-full" />
+                    <div>
+                        <label class="block text-xs font-medium text-gray-500 mb-1">{ "Imagen (HTML)" }</label>
+                        <input readonly=true value={badge_code} onfocus={select_all.clone()} class="w-full border rounded p-2 text-xs font-mono bg-gray-50" />
+                    </div>
+                    <div>
+                        <label class="block text-xs font-medium text-gray-500 mb-1">{ "Widget (iframe)" }</label>
+                        <input readonly=true value={iframe_code} onfocus={select_all} class="w-full border rounded p-2 text-xs font-mono bg-gray-50" />
+                    </div>
+                </div>
+            </details>
+        </div>
+    }
+}
@@ -0,0 +1,109 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use serde::Deserialize;
+use crate::context::UserContext;
+use crate::components::live_events::use_live_events;
+use crate::components::organizer_time_slots::TimeSlot;
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct KitchenDishTotal {
+    pub dish_name: String,
+    pub quantity: i32,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct KitchenOrder {
+    pub id: i32,
+    pub customer_name: String,
+    pub status: String,
+    pub delivery_method: String,
+    pub items: Vec<KitchenDishTotal>,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct KitchenSlotGroup {
+    pub slot: Option<TimeSlot>,
+    pub dish_totals: Vec<KitchenDishTotal>,
+    pub orders: Vec<KitchenOrder>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(KitchenSlots)]
+pub fn kitchen_slots(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let groups = use_state(Vec::<KitchenSlotGroup>::new);
+    let kermesse_id = props.kermesse_id;
+    let refresh_trigger = use_state(|| 0);
+    let live = use_live_events(
+        Some(format!("http://127.0.0.1:8080/kermesses/{}/events", kermesse_id)),
+        user_ctx.user.as_ref().map(|u| u.token.clone()),
+    );
+
+    {
+        let groups = groups.clone();
+        let user_ctx = user_ctx.clone();
+        use_effect_with((refresh_trigger.clone(), live.version), move |_| {
+            if let Some(user) = &user_ctx.user {
+                let token = user.token.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let url = format!("http://127.0.0.1:8080/kermesses/{}/kitchen/slots", kermesse_id);
+                    if let Ok(resp) = Request::get(&url)
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await
+                    {
+                        if let Ok(data) = resp.json::<Vec<KitchenSlotGroup>>().await {
+                            groups.set(data);
+                        }
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    html! {
+        <div class="space-y-4">
+            <div class="flex justify-between items-center">
+                <h2 class="text-lg font-bold text-gray-800">{ "🕒 Cocina por Horario" }</h2>
+                <button onclick={let rt = refresh_trigger.clone(); Callback::from(move |_| rt.set(*rt + 1))} class="text-secondary hover:text-teal-700 font-bold text-sm">
+                    { "🔄 Actualizar" }
+                </button>
+            </div>
+            {
+                groups.iter().filter(|g| !g.orders.is_empty()).map(|group| html! {
+                    <div class="bg-gray-50 rounded-lg p-4 border border-gray-100">
+                        <div class="flex justify-between items-center mb-3">
+                            <h3 class="font-bold text-gray-800">
+                                { group.slot.as_ref().map(|s| format!("{} - {}", s.starts_at, s.ends_at)).unwrap_or_else(|| "Sin horario".to_string()) }
+                            </h3>
+                            <span class="text-xs text-gray-500">{ format!("{} pedidos", group.orders.len()) }</span>
+                        </div>
+                        <div class="flex flex-wrap gap-2 mb-3">
+                            {
+                                group.dish_totals.iter().map(|d| html! {
+                                    <span class="bg-orange-100 text-orange-800 px-3 py-1 rounded-full text-sm font-bold">{ format!("{} × {}", d.quantity, d.dish_name) }</span>
+                                }).collect::<Html>()
+                            }
+                        </div>
+                        <ul class="text-sm text-gray-600 space-y-1">
+                            {
+                                group.orders.iter().map(|o| html! {
+                                    <li>
+                                        <span class="font-mono text-gray-400">{ format!("#{} ", o.id) }</span>
+                                        <span class="font-medium">{ &o.customer_name }</span>
+                                        { format!(" — {}", o.items.iter().map(|i| format!("{} {}", i.quantity, i.dish_name)).collect::<Vec<_>>().join(", ")) }
+                                    </li>
+                                }).collect::<Html>()
+                            }
+                        </ul>
+                    </div>
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
@@ -0,0 +1,122 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use yew::prelude::*;
+use futures::StreamExt;
+use gloo_net::eventsource::futures::EventSource;
+use gloo_timers::future::TimeoutFuture;
+use reqwasm::http::Request;
+use serde::Deserialize;
+
+/// Wait before reopening a dropped stream with a fresh ticket.
+const RECONNECT_DELAY_MS: u32 = 5_000;
+
+const EVENT_NAMES: [&str; 4] = ["sale_created", "sale_status_changed", "order_ready", "stock_changed"];
+
+/// One server-sent event; fields not relevant to `kind` are `None`.
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct LiveEvent {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub kermesse_id: i32,
+    pub sale_id: Option<i32>,
+    pub status: Option<String>,
+    pub dish_id: Option<i32>,
+    pub quantity_available: Option<i32>,
+}
+
+/// `version` bumps on every event so components can use it as an effect dependency.
+#[derive(Clone, PartialEq, Default)]
+pub struct LiveFeed {
+    pub version: u32,
+    pub last: Option<LiveEvent>,
+}
+
+impl Reducible for LiveFeed {
+    type Action = LiveEvent;
+
+    fn reduce(self: Rc<Self>, event: LiveEvent) -> Rc<Self> {
+        Rc::new(LiveFeed {
+            version: self.version.wrapping_add(1),
+            last: Some(event),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct TicketResponse {
+    ticket: String,
+}
+
+/// Trades the session token for a short-lived ticket scoped to `url`'s stream.
+async fn fetch_ticket(url: &str, token: &str) -> Option<String> {
+    let resp = Request::post(&format!("{}/ticket", url))
+        .header("Authorization", &format!("Bearer {}", token))
+        .send()
+        .await
+        .ok()?;
+    if !resp.ok() {
+        return None;
+    }
+    resp.json::<TicketResponse>().await.ok().map(|t| t.ticket)
+}
+
+/// Subscribes to an SSE endpoint for as long as the component is mounted and `url` stays the same.
+/// With a `token`, each connection opens with a fresh stream ticket instead of the session token,
+/// so a dropped stream is reopened here rather than by the browser's retry on a stale URL.
+#[hook]
+pub fn use_live_events(url: Option<String>, token: Option<String>) -> UseReducerHandle<LiveFeed> {
+    let feed = use_reducer(LiveFeed::default);
+
+    {
+        let dispatcher = feed.dispatcher();
+        use_effect_with((url, token), move |(url, token)| {
+            let mounted = Rc::new(Cell::new(true));
+            let current = Rc::new(RefCell::new(None::<EventSource>));
+            if let Some(url) = url.clone() {
+                let token = token.clone();
+                let mounted = mounted.clone();
+                let current = current.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    while mounted.get() {
+                        let ticket = match &token {
+                            Some(token) => fetch_ticket(&url, token).await,
+                            None => None,
+                        };
+                        let stream_url = match ticket {
+                            Some(ticket) => format!("{}?ticket={}", url, ticket),
+                            None => url.clone(),
+                        };
+                        // Unmounted while waiting for the ticket
+                        if !mounted.get() {
+                            break;
+                        }
+                        if let Ok(mut source) = EventSource::new(&stream_url) {
+                            let streams: Vec<_> = EVENT_NAMES.iter().filter_map(|name| source.subscribe(*name).ok()).collect();
+                            *current.borrow_mut() = Some(source);
+                            let mut events = futures::stream::select_all(streams);
+                            while let Some(Ok((_, message))) = events.next().await {
+                                if let Some(data) = message.data().as_string() {
+                                    if let Ok(event) = serde_json::from_str::<LiveEvent>(&data) {
+                                        dispatcher.dispatch(event);
+                                    }
+                                }
+                            }
+                            if let Some(source) = current.borrow_mut().take() {
+                                source.close();
+                            }
+                        }
+                        TimeoutFuture::new(RECONNECT_DELAY_MS).await;
+                    }
+                });
+            }
+            move || {
+                mounted.set(false);
+                if let Some(source) = current.borrow_mut().take() {
+                    source.close();
+                }
+            }
+        });
+    }
+
+    feed
+}
@@ -7,3 +7,15 @@ pub mod organizer_orders;
 pub mod organizer_collaborators;
 pub mod footer;
 pub mod navbar;
+pub mod donor_wall;
+pub mod organizer_donations;
+pub mod organizer_time_slots;
+pub mod kitchen_slots;
+pub mod live_events;
+pub mod organizer_delivery_zones;
+pub mod claim_orders;
+pub mod organizer_expenses;
+pub mod organizer_overview;
+pub mod organizer_transparency;
+pub mod fundraising_progress;
+pub mod file_download;
@@ -4,6 +4,12 @@ use reqwasm::http::Request;
 use crate::context::UserContext;
 use crate::components::organizer_orders::OrganizerOrders;
 use crate::components::organizer_collaborators::OrganizerCollaborators;
+use crate::components::organizer_donations::OrganizerDonations;
+use crate::components::organizer_time_slots::OrganizerTimeSlots;
+use crate::components::organizer_delivery_zones::OrganizerDeliveryZones;
+use crate::components::organizer_expenses::OrganizerExpenses;
+use crate::components::organizer_transparency::OrganizerTransparency;
+use crate::components::file_download::download;
 
 use serde::{de::Error, Deserializer, Deserialize};
 use serde_json::Value;
@@ -46,12 +52,199 @@ pub struct DashboardStats {
     pub financial_goal: Option<f64>,
     #[serde(deserialize_with = "deserialize_price")]
     pub total_raised: f64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub total_refunded: f64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub sales_revenue: f64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub donations_total: f64,
     pub progress_percentage: f64,
     pub total_orders: i64,
     pub pending_orders: i64,
     pub paid_orders: i64,
     pub delivered_orders: i64,
     pub ingredient_coverage_percentage: f64,
+    #[serde(default)]
+    pub seller_totals: Vec<SellerTotal>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct SellerTotal {
+    pub seller_id: i32,
+    pub seller_name: String,
+    pub sales_count: i64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub total: f64,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct SalesAnalytics {
+    pub dishes: Vec<DishAnalytics>,
+    pub hourly: Vec<HourlySales>,
+    pub payment_methods: Vec<MethodBreakdown>,
+    pub delivery_methods: Vec<MethodBreakdown>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct DishAnalytics {
+    pub dish_id: i32,
+    pub name: String,
+    pub units_sold: i64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub revenue: f64,
+    pub stock_remaining: i32,
+    pub sell_through_percentage: f64,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct HourlySales {
+    pub hour: String,
+    pub orders: i64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub revenue: f64,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct MethodBreakdown {
+    pub method: String,
+    pub orders: i64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub revenue: f64,
+}
+
+fn method_label(method: &str) -> &str {
+    match method {
+        "CASH" => "Efectivo",
+        "QR" => "QR",
+        "PICKUP" => "Recojo",
+        "DELIVERY" => "Delivery",
+        other => other,
+    }
+}
+
+/// Share of `value` over `max` as a CSS width, keeping tiny non-zero values visible.
+fn bar_width(value: f64, max: f64) -> String {
+    if max <= 0.0 || value <= 0.0 {
+        "width: 0%".to_string()
+    } else {
+        format!("width: {:.1}%", (value / max * 100.0).max(2.0))
+    }
+}
+
+fn render_split(title: &str, rows: &[MethodBreakdown], colors: &[&str]) -> Html {
+    let total: f64 = rows.iter().map(|r| r.revenue).sum();
+    html! {
+        <div class="p-6 bg-white rounded-xl border border-gray-100 shadow-sm">
+            <h3 class="text-lg font-semibold mb-4 text-gray-700">{ title }</h3>
+            if rows.is_empty() {
+                <p class="text-gray-500 italic text-sm">{ "Sin ventas cobradas todavía." }</p>
+            } else {
+                <div class="flex w-full h-6 rounded-full overflow-hidden bg-gray-200 mb-3">
+                    {
+                        rows.iter().enumerate().map(|(i, r)| html! {
+                            <div class={colors[i % colors.len()].to_string()} style={bar_width(r.revenue, total)} title={method_label(&r.method).to_string()}></div>
+                        }).collect::<Html>()
+                    }
+                </div>
+                <ul class="space-y-1 text-sm">
+                    {
+                        rows.iter().enumerate().map(|(i, r)| html! {
+                            <li class="flex items-center justify-between">
+                                <span class="flex items-center gap-2">
+                                    <span class={format!("inline-block w-3 h-3 rounded-full {}", colors[i % colors.len()])}></span>
+                                    { method_label(&r.method) }
+                                </span>
+                                <span class="text-gray-600">
+                                    { format!("{} pedidos · Bs. {:.2} ({:.0}%)", r.orders, r.revenue, if total > 0.0 { r.revenue / total * 100.0 } else { 0.0 }) }
+                                </span>
+                            </li>
+                        }).collect::<Html>()
+                    }
+                </ul>
+            }
+        </div>
+    }
+}
+
+fn render_analytics(analytics: &SalesAnalytics) -> Html {
+    let max_dish_revenue = analytics.dishes.iter().map(|d| d.revenue).fold(0.0, f64::max);
+    let max_hour_revenue = analytics.hourly.iter().map(|h| h.revenue).fold(0.0, f64::max);
+    html! {
+        <div class="mt-8">
+            <h2 class="text-xl font-bold mb-6 text-gray-800">{ "Análisis de Ventas" }</h2>
+
+            // Per dish: revenue share and how much of the stock has been sold
+            <div class="mb-6 p-6 bg-white rounded-xl border border-gray-100 shadow-sm">
+                <h3 class="text-lg font-semibold mb-4 text-gray-700">{ "🍽️ Ventas por Plato" }</h3>
+                <table class="w-full text-sm">
+                    <thead class="text-gray-500 border-b">
+                        <tr>
+                            <th class="text-left py-2">{ "Plato" }</th>
+                            <th class="text-center py-2">{ "Unidades" }</th>
+                            <th class="text-left py-2 w-1/3">{ "Ingresos" }</th>
+                            <th class="text-left py-2 w-1/4">{ "Stock vendido" }</th>
+                        </tr>
+                    </thead>
+                    <tbody class="divide-y divide-gray-100">
+                        {
+                            analytics.dishes.iter().map(|d| html! {
+                                <tr key={d.dish_id}>
+                                    <td class="py-2 font-medium">{ &d.name }</td>
+                                    <td class="py-2 text-center">{ d.units_sold }</td>
+                                    <td class="py-2 pr-4">
+                                        <div class="flex items-center gap-2">
+                                            <div class="flex-grow bg-gray-100 rounded-full h-3 overflow-hidden">
+                                                <div class="bg-green-500 h-3 rounded-full" style={bar_width(d.revenue, max_dish_revenue)}></div>
+                                            </div>
+                                            <span class="font-bold whitespace-nowrap">{ format!("Bs. {:.2}", d.revenue) }</span>
+                                        </div>
+                                    </td>
+                                    <td class="py-2">
+                                        <div class="flex items-center gap-2">
+                                            <div class="flex-grow bg-gray-100 rounded-full h-3 overflow-hidden">
+                                                <div class="bg-blue-500 h-3 rounded-full" style={format!("width: {:.1}%", d.sell_through_percentage)}></div>
+                                            </div>
+                                            <span class="text-xs text-gray-600 whitespace-nowrap" title={format!("Quedan {}", d.stock_remaining)}>
+                                                { format!("{:.0}%", d.sell_through_percentage) }
+                                            </span>
+                                        </div>
+                                    </td>
+                                </tr>
+                            }).collect::<Html>()
+                        }
+                    </tbody>
+                </table>
+            </div>
+
+            // Hourly timeline
+            <div class="mb-6 p-6 bg-white rounded-xl border border-gray-100 shadow-sm">
+                <h3 class="text-lg font-semibold mb-4 text-gray-700">{ "⏱️ Ventas por Hora" }</h3>
+                if analytics.hourly.is_empty() {
+                    <p class="text-gray-500 italic text-sm">{ "Sin ventas cobradas todavía." }</p>
+                } else {
+                    <div class="flex items-end gap-1 h-40 overflow-x-auto">
+                        {
+                            analytics.hourly.iter().map(|h| {
+                                let height = if max_hour_revenue > 0.0 { (h.revenue / max_hour_revenue * 100.0).max(2.0) } else { 0.0 };
+                                html! {
+                                    <div class="flex-1 min-w-[1.5rem] h-full flex flex-col justify-end items-center"
+                                        title={format!("{} · {} pedidos · Bs. {:.2}", h.hour.chars().take(16).collect::<String>().replace('T', " "), h.orders, h.revenue)}>
+                                        <div class="w-full bg-gradient-to-t from-primary to-blue-400 rounded-t" style={format!("height: {:.1}%", height)}></div>
+                                        <span class="text-[10px] text-gray-500 mt-1">{ h.hour.chars().skip(11).take(5).collect::<String>() }</span>
+                                    </div>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </div>
+                }
+            </div>
+
+            <div class="grid md:grid-cols-2 gap-6">
+                { render_split("💳 Por Método de Pago", &analytics.payment_methods, &["bg-green-500", "bg-blue-500", "bg-purple-500"]) }
+                { render_split("🛵 Por Tipo de Entrega", &analytics.delivery_methods, &["bg-orange-500", "bg-teal-500", "bg-gray-500"]) }
+            </div>
+        </div>
+    }
 }
 
 #[derive(Properties, PartialEq)]
@@ -64,6 +257,11 @@ enum DashboardTab {
     Overview,
     Orders,
     Collaborators,
+    Donations,
+    TimeSlots,
+    DeliveryZones,
+    Expenses,
+    Transparency,
 }
 
 #[function_component(OrganizerDashboardV2)]
@@ -71,17 +269,20 @@ pub fn organizer_dashboard_v2(props: &Props) -> Html {
     let _navigator = use_navigator().unwrap();
     let user_ctx = use_context::<UserContext>().expect("No UserContext found");
     let stats = use_state(|| None::<DashboardStats>);
+    let analytics = use_state(|| None::<SalesAnalytics>);
     let kermesse_id = props.kermesse_id;
     let active_tab = use_state(|| DashboardTab::Overview);
 
     // Fetch Stats Effect
     {
         let stats = stats.clone();
+        let analytics = analytics.clone();
         let token = user_ctx.user.as_ref().map(|u| u.token.clone());
         use_effect_with((kermesse_id, *active_tab == DashboardTab::Overview), move |(id, is_overview)| {
             if *is_overview {
                 let id = *id;
                 let stats = stats.clone();
+                let analytics = analytics.clone();
                 if let Some(token) = token {
                     wasm_bindgen_futures::spawn_local(async move {
                         let url = format!("http://127.0.0.1:8080/kermesses/{}/dashboard/stats", id);
@@ -94,6 +295,17 @@ pub fn organizer_dashboard_v2(props: &Props) -> Html {
                                 stats.set(Some(fetched));
                             }
                         }
+
+                        let url = format!("http://127.0.0.1:8080/kermesses/{}/dashboard/analytics", id);
+                        if let Ok(resp) = Request::get(&url)
+                            .header("Authorization", &format!("Bearer {}", token))
+                            .send()
+                            .await
+                        {
+                            if let Ok(fetched) = resp.json().await {
+                                analytics.set(Some(fetched));
+                            }
+                        }
                     });
                 }
             }
@@ -129,6 +341,19 @@ pub fn organizer_dashboard_v2(props: &Props) -> Html {
                         html! { <p class="text-gray-500 italic">{ "No se ha definido una meta financiera." }</p> }
                     }
                 }
+                <div class="grid grid-cols-2 gap-4 mt-4 text-sm">
+                    <div class="bg-white/70 rounded-lg p-3 border border-green-100">
+                        <p class="text-gray-500">{ "Ventas de platos" }</p>
+                        <p class="font-bold text-green-700">{ format!("Bs. {:.2}", stats_data.sales_revenue) }</p>
+                    </div>
+                    <div class="bg-white/70 rounded-lg p-3 border border-purple-100">
+                        <p class="text-gray-500">{ "Donaciones" }</p>
+                        <p class="font-bold text-purple-700">{ format!("Bs. {:.2}", stats_data.donations_total) }</p>
+                    </div>
+                </div>
+                if stats_data.total_refunded > 0.0 {
+                    <p class="text-xs text-gray-500 mt-3">{ format!("Incluye Bs. {:.2} descontados por reembolsos.", stats_data.total_refunded) }</p>
+                }
             </div>
 
             // Order Statistics
@@ -151,6 +376,33 @@ pub fn organizer_dashboard_v2(props: &Props) -> Html {
                 </div>
             </div>
 
+            // Sales per seller (POS sales are credited to the seller at the stand)
+            if !stats_data.seller_totals.is_empty() {
+                <div class="mb-6 p-6 bg-white rounded-xl border border-gray-100 shadow-sm">
+                    <h3 class="text-lg font-semibold mb-4 text-gray-700">{ "🧑‍💼 Ventas por Vendedor" }</h3>
+                    <table class="w-full text-sm">
+                        <thead class="text-gray-500 border-b">
+                            <tr>
+                                <th class="text-left py-2">{ "Vendedor" }</th>
+                                <th class="text-center py-2">{ "Ventas" }</th>
+                                <th class="text-right py-2">{ "Total" }</th>
+                            </tr>
+                        </thead>
+                        <tbody class="divide-y divide-gray-100">
+                            {
+                                stats_data.seller_totals.iter().map(|s| html! {
+                                    <tr>
+                                        <td class="py-2 font-medium">{ &s.seller_name }</td>
+                                        <td class="py-2 text-center">{ s.sales_count }</td>
+                                        <td class="py-2 text-right font-bold">{ format!("Bs. {:.2}", s.total) }</td>
+                                    </tr>
+                                }).collect::<Html>()
+                            }
+                        </tbody>
+                    </table>
+                </div>
+            }
+
             // Ingredient Coverage
             <div class="p-6 bg-white rounded-xl border border-gray-100 shadow-sm">
                 <h3 class="text-lg font-semibold mb-4 text-gray-700">{ "🥕 Cobertura de Insumos" }</h3>
@@ -172,7 +424,21 @@ pub fn organizer_dashboard_v2(props: &Props) -> Html {
         <div class="bg-white rounded-2xl shadow-xl border border-gray-100 overflow-hidden mb-8 transform transition-all">
             <div class="flex justify-between items-center bg-white rounded-t-2xl p-6 border-b border-gray-100">
                 <h2 class="text-2xl font-bold text-gray-800">{ "Dashboard del Organizador" }</h2>
-                <div>
+                <div class="flex gap-2">
+                    <button
+                        onclick={
+                            let token = user_ctx.user.as_ref().map(|u| u.token.clone()).unwrap_or_default();
+                            Callback::from(move |_| download(
+                                format!("http://127.0.0.1:8080/kermesses/{}/report.pdf", kermesse_id),
+                                token.clone(),
+                                format!("informe-kermesse-{}.pdf", kermesse_id),
+                            ))
+                        }
+                        class="bg-gray-100 text-gray-700 hover:bg-gray-200 px-4 py-2 rounded-lg font-medium transition flex items-center gap-2"
+                    >
+                        <span>{"📄"}</span>
+                        { "Informe de Cierre" }
+                    </button>
                     <button 
                         onclick={Callback::from(move |_| {
                             let window = web_sys::window().unwrap();
@@ -209,6 +475,41 @@ pub fn organizer_dashboard_v2(props: &Props) -> Html {
                 >
                     { "👥 Equipo" }
                 </button>
+                <button 
+                    onclick={let at = active_tab.clone(); Callback::from(move |_| at.set(DashboardTab::Donations))}
+                    class={format!("flex-1 py-4 text-sm font-bold uppercase tracking-wide transition border-b-2 hover:bg-gray-50 {}", 
+                        if *active_tab == DashboardTab::Donations { "text-primary border-primary bg-white" } else { "text-gray-500 border-transparent hover:text-gray-700" })}
+                >
+                    { "💝 Donaciones" }
+                </button>
+                <button 
+                    onclick={let at = active_tab.clone(); Callback::from(move |_| at.set(DashboardTab::TimeSlots))}
+                    class={format!("flex-1 py-4 text-sm font-bold uppercase tracking-wide transition border-b-2 hover:bg-gray-50 {}", 
+                        if *active_tab == DashboardTab::TimeSlots { "text-primary border-primary bg-white" } else { "text-gray-500 border-transparent hover:text-gray-700" })}
+                >
+                    { "🕒 Horarios" }
+                </button>
+                <button 
+                    onclick={let at = active_tab.clone(); Callback::from(move |_| at.set(DashboardTab::DeliveryZones))}
+                    class={format!("flex-1 py-4 text-sm font-bold uppercase tracking-wide transition border-b-2 hover:bg-gray-50 {}", 
+                        if *active_tab == DashboardTab::DeliveryZones { "text-primary border-primary bg-white" } else { "text-gray-500 border-transparent hover:text-gray-700" })}
+                >
+                    { "🛵 Delivery" }
+                </button>
+                <button 
+                    onclick={let at = active_tab.clone(); Callback::from(move |_| at.set(DashboardTab::Expenses))}
+                    class={format!("flex-1 py-4 text-sm font-bold uppercase tracking-wide transition border-b-2 hover:bg-gray-50 {}", 
+                        if *active_tab == DashboardTab::Expenses { "text-primary border-primary bg-white" } else { "text-gray-500 border-transparent hover:text-gray-700" })}
+                >
+                    { "🧾 Gastos" }
+                </button>
+                <button 
+                    onclick={let at = active_tab.clone(); Callback::from(move |_| at.set(DashboardTab::Transparency))}
+                    class={format!("flex-1 py-4 text-sm font-bold uppercase tracking-wide transition border-b-2 hover:bg-gray-50 {}", 
+                        if *active_tab == DashboardTab::Transparency { "text-primary border-primary bg-white" } else { "text-gray-500 border-transparent hover:text-gray-700" })}
+                >
+                    { "🔍 Transparencia" }
+                </button>
             </div>
 
             <div class="p-6">
@@ -216,13 +517,25 @@ pub fn organizer_dashboard_v2(props: &Props) -> Html {
                     match *active_tab {
                         DashboardTab::Overview => {
                             if let Some(s) = &*stats {
-                                render_overview(s)
+                                html! {
+                                    <>
+                                        { render_overview(s) }
+                                        if let Some(a) = &*analytics {
+                                            { render_analytics(a) }
+                                        }
+                                    </>
+                                }
                             } else {
                                 html! { <div class="text-center py-10 text-gray-400 animate-pulse">{ "Cargando estadísticas..." }</div> }
                             }
                         },
                         DashboardTab::Orders => html! { <OrganizerOrders kermesse_id={kermesse_id} /> },
                         DashboardTab::Collaborators => html! { <OrganizerCollaborators kermesse_id={kermesse_id} /> },
+                        DashboardTab::Donations => html! { <OrganizerDonations kermesse_id={kermesse_id} /> },
+                        DashboardTab::TimeSlots => html! { <OrganizerTimeSlots kermesse_id={kermesse_id} /> },
+                        DashboardTab::DeliveryZones => html! { <OrganizerDeliveryZones kermesse_id={kermesse_id} /> },
+                        DashboardTab::Expenses => html! { <OrganizerExpenses kermesse_id={kermesse_id} /> },
+                        DashboardTab::Transparency => html! { <OrganizerTransparency kermesse_id={kermesse_id} /> },
                     }
                 }
             </div>
@@ -0,0 +1,171 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use web_sys::HtmlInputElement;
+use serde::{Deserialize, Serialize};
+use crate::context::UserContext;
+use crate::components::organizer_dashboard::{deserialize_price, deserialize_option_price};
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct DeliveryZone {
+    pub id: i32,
+    pub name: String,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub fee: f64,
+    #[serde(default, deserialize_with = "deserialize_option_price")]
+    pub min_order: Option<f64>,
+    #[serde(default)]
+    pub active: bool,
+}
+
+#[derive(Serialize)]
+struct CreateDeliveryZoneRequest {
+    name: String,
+    fee: f64,
+    min_order: Option<f64>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(OrganizerDeliveryZones)]
+pub fn organizer_delivery_zones(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let zones = use_state(Vec::<DeliveryZone>::new);
+    let has_removed_zones = use_state(|| false);
+    let kermesse_id = props.kermesse_id;
+    let refresh_trigger = use_state(|| 0);
+    let token = user_ctx.user.as_ref().map(|u| u.token.clone());
+
+    let name_ref = use_node_ref();
+    let fee_ref = use_node_ref();
+    let min_ref = use_node_ref();
+
+    {
+        let zones = zones.clone();
+        let has_removed_zones = has_removed_zones.clone();
+        use_effect_with(refresh_trigger.clone(), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                let url = format!("http://127.0.0.1:8080/kermesses/{}/delivery-zones?include_inactive=true", kermesse_id);
+                if let Ok(resp) = Request::get(&url).send().await {
+                    if let Ok(data) = resp.json::<Vec<DeliveryZone>>().await {
+                        let (active, removed): (Vec<DeliveryZone>, Vec<DeliveryZone>) = data.into_iter().partition(|z| z.active);
+                        has_removed_zones.set(!removed.is_empty());
+                        zones.set(active);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_create = {
+        let token = token.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        let name_ref = name_ref.clone();
+        let fee_ref = fee_ref.clone();
+        let min_ref = min_ref.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let request = CreateDeliveryZoneRequest {
+                name: name_ref.cast::<HtmlInputElement>().unwrap().value(),
+                fee: fee_ref.cast::<HtmlInputElement>().unwrap().value().parse().unwrap_or(0.0),
+                min_order: min_ref.cast::<HtmlInputElement>().unwrap().value().parse().ok(),
+            };
+            let token = token.clone();
+            let refresh_trigger = refresh_trigger.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(token) = token {
+                    let body = serde_json::to_string(&request).unwrap();
+                    let resp = Request::post(&format!("http://127.0.0.1:8080/kermesses/{}/delivery-zones", kermesse_id))
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    match resp {
+                        Ok(resp) if resp.ok() => refresh_trigger.set(*refresh_trigger + 1),
+                        Ok(resp) => gloo_dialogs::alert(&resp.text().await.unwrap_or_default()),
+                        Err(_) => gloo_dialogs::alert("Error de conexión."),
+                    }
+                }
+            });
+        })
+    };
+
+    let on_remove = {
+        let token = token.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        Callback::from(move |zone_id: i32| {
+            if !gloo_dialogs::confirm("¿Dejar de ofrecer delivery a esta zona?") {
+                return;
+            }
+            let token = token.clone();
+            let refresh_trigger = refresh_trigger.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(token) = token {
+                    let resp = Request::delete(&format!("http://127.0.0.1:8080/delivery-zones/{}", zone_id))
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await;
+                    match resp {
+                        Ok(resp) if resp.ok() => refresh_trigger.set(*refresh_trigger + 1),
+                        _ => gloo_dialogs::alert("No se pudo quitar la zona."),
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="space-y-6">
+            <form onsubmit={on_create} class="grid grid-cols-2 md:grid-cols-4 gap-3 items-end bg-gray-50 p-4 rounded-lg">
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Zona" }</label>
+                    <input ref={name_ref} type="text" required=true placeholder="Zona Norte" class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Costo de envío (Bs.)" }</label>
+                    <input ref={fee_ref} type="number" min="0" step="0.5" required=true class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Pedido mínimo (opcional)" }</label>
+                    <input ref={min_ref} type="number" min="0" step="0.5" class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <button type="submit" class="bg-primary text-white font-bold py-2 rounded-lg hover:bg-red-600 transition">{ "Agregar Zona" }</button>
+            </form>
+
+            if zones.is_empty() {
+                <div class="text-center py-8 text-gray-500 bg-white rounded-lg border border-dashed border-gray-300">
+                    if *has_removed_zones {
+                        { "Sin zonas de delivery: los compradores solo podrán recoger o comer aquí." }
+                    } else {
+                        { "Sin zonas configuradas: el delivery se ofrece sin costo de envío ni restricción de zona." }
+                    }
+                </div>
+            } else {
+                <div class="grid grid-cols-1 md:grid-cols-3 gap-3">
+                    {
+                        zones.iter().map(|zone| {
+                            let id = zone.id;
+                            let on_remove = on_remove.clone();
+                            html! {
+                                <div class="p-4 bg-white rounded-lg border border-gray-100 shadow-sm flex justify-between items-start">
+                                    <div>
+                                        <p class="font-bold text-gray-800">{ &zone.name }</p>
+                                        <p class="text-sm">{ format!("Envío Bs. {:.2}", zone.fee) }</p>
+                                        if let Some(min) = zone.min_order {
+                                            <p class="text-xs text-gray-500">{ format!("Pedido mínimo Bs. {:.2}", min) }</p>
+                                        }
+                                    </div>
+                                    <button onclick={Callback::from(move |_| on_remove.emit(id))} class="text-red-500 hover:text-red-700 text-sm" title="Quitar zona">{ "✕" }</button>
+                                </div>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,133 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use crate::context::UserContext;
+use crate::components::organizer_dashboard::deserialize_price;
+use serde::Deserialize;
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct DonationResponse {
+    pub id: i32,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub amount: f64,
+    pub donor_name: Option<String>,
+    pub is_anonymous: bool,
+    pub message: Option<String>,
+    pub payment_method: String,
+    pub status: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(OrganizerDonations)]
+pub fn organizer_donations(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let donations = use_state(Vec::<DonationResponse>::new);
+    let loading = use_state(|| true);
+    let kermesse_id = props.kermesse_id;
+    let refresh_trigger = use_state(|| 0);
+
+    {
+        let donations = donations.clone();
+        let loading = loading.clone();
+        let user_ctx = user_ctx.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        use_effect_with(refresh_trigger, move |_| {
+            if let Some(user) = &user_ctx.user {
+                let token = user.token.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let url = format!("http://127.0.0.1:8080/kermesses/{}/donations", kermesse_id);
+                    if let Ok(resp) = Request::get(&url)
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await
+                    {
+                        if let Ok(data) = resp.json::<Vec<DonationResponse>>().await {
+                            donations.set(data);
+                        }
+                    }
+                    loading.set(false);
+                });
+            }
+            || ()
+        });
+    }
+
+    let update_status = {
+        let user_ctx = user_ctx.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        Callback::from(move |(donation_id, status): (i32, &'static str)| {
+            let token = user_ctx.user.as_ref().unwrap().token.clone();
+            let refresh_trigger = refresh_trigger.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let url = format!("http://127.0.0.1:8080/donations/{}/status", donation_id);
+                let body = serde_json::json!({ "status": status });
+                let _ = Request::put(&url)
+                    .header("Authorization", &format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .body(body.to_string())
+                    .send()
+                    .await;
+                refresh_trigger.set(*refresh_trigger + 1);
+            });
+        })
+    };
+
+    if *loading {
+        return html! { <div class="text-center py-8">{ "Cargando donaciones..." }</div> };
+    }
+
+    if donations.is_empty() {
+        return html! {
+            <div class="text-center py-8 text-gray-500 bg-white rounded-lg border border-dashed border-gray-300">
+                { "Aún no se registraron donaciones." }
+            </div>
+        };
+    }
+
+    html! {
+        <div class="bg-white rounded-lg shadow overflow-hidden">
+            <table class="w-full text-left text-sm">
+                <thead class="bg-gray-50 text-gray-600 border-b">
+                    <tr>
+                        <th class="p-3 font-semibold">{ "Donante" }</th>
+                        <th class="p-3 font-semibold">{ "Método" }</th>
+                        <th class="p-3 font-semibold text-right">{ "Monto" }</th>
+                        <th class="p-3 font-semibold text-center">{ "Estado" }</th>
+                        <th class="p-3 font-semibold text-center">{ "Acciones" }</th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-100">
+                    {
+                        donations.iter().map(|d| {
+                            let id = d.id;
+                            let update_status = update_status.clone();
+                            let name = d.donor_name.clone().unwrap_or_else(|| "Sin nombre".to_string());
+                            html! {
+                                <tr class="hover:bg-gray-50 transition">
+                                    <td class="p-3">
+                                        <p class="font-medium">{ if d.is_anonymous { format!("{} (anónimo)", name) } else { name } }</p>
+                                        if let Some(msg) = &d.message {
+                                            <p class="text-xs text-gray-500 italic">{ msg }</p>
+                                        }
+                                    </td>
+                                    <td class="p-3">{ &d.payment_method }</td>
+                                    <td class="p-3 text-right font-bold">{ format!("Bs. {:.2}", d.amount) }</td>
+                                    <td class="p-3 text-center">{ &d.status }</td>
+                                    <td class="p-3 flex justify-center gap-2">
+                                        if d.status == "PENDING" {
+                                            <button onclick={let u = update_status.clone(); Callback::from(move |_| u.emit((id, "CONFIRMED")))} class="bg-green-100 text-green-700 hover:bg-green-200 px-2 py-1 rounded text-xs font-bold">{ "✔ Confirmar" }</button>
+                                            <button onclick={let u = update_status.clone(); Callback::from(move |_| u.emit((id, "REJECTED")))} class="bg-red-50 text-red-600 hover:bg-red-100 px-2 py-1 rounded text-xs">{ "✕" }</button>
+                                        }
+                                    </td>
+                                </tr>
+                            }
+                        }).collect::<Html>()
+                    }
+                </tbody>
+            </table>
+        </div>
+    }
+}
@@ -0,0 +1,237 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use serde::{Deserialize, Serialize};
+use crate::context::UserContext;
+use crate::components::organizer_dashboard::deserialize_price;
+use crate::components::file_download::download;
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct Expense {
+    pub id: i32,
+    pub description: String,
+    pub category: String,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub amount: f64,
+    pub spent_on: String,
+}
+
+#[derive(Serialize)]
+struct CreateExpenseRequest {
+    description: String,
+    category: String,
+    amount: f64,
+    spent_on: Option<String>,
+}
+
+const CATEGORIES: [(&str, &str); 4] = [
+    ("INGREDIENTS", "Insumos"),
+    ("RENTAL", "Alquiler"),
+    ("SUPPLIES", "Material"),
+    ("OTHER", "Otros"),
+];
+
+pub fn category_label(category: &str) -> &str {
+    CATEGORIES.iter().find(|(key, _)| *key == category).map(|(_, label)| *label).unwrap_or(category)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(OrganizerExpenses)]
+pub fn organizer_expenses(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let expenses = use_state(Vec::<Expense>::new);
+    let kermesse_id = props.kermesse_id;
+    let refresh_trigger = use_state(|| 0);
+    let token = user_ctx.user.as_ref().map(|u| u.token.clone());
+
+    let description_ref = use_node_ref();
+    let category_ref = use_node_ref();
+    let amount_ref = use_node_ref();
+    let date_ref = use_node_ref();
+
+    {
+        let expenses = expenses.clone();
+        let token = token.clone();
+        use_effect_with(refresh_trigger.clone(), move |_| {
+            if let Some(token) = token {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let url = format!("http://127.0.0.1:8080/kermesses/{}/expenses", kermesse_id);
+                    if let Ok(resp) = Request::get(&url)
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await
+                    {
+                        if let Ok(data) = resp.json::<Vec<Expense>>().await {
+                            expenses.set(data);
+                        }
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_create = {
+        let token = token.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        let description_ref = description_ref.clone();
+        let category_ref = category_ref.clone();
+        let amount_ref = amount_ref.clone();
+        let date_ref = date_ref.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let date = date_ref.cast::<HtmlInputElement>().unwrap().value();
+            let request = CreateExpenseRequest {
+                description: description_ref.cast::<HtmlInputElement>().unwrap().value(),
+                category: category_ref.cast::<HtmlSelectElement>().unwrap().value(),
+                amount: amount_ref.cast::<HtmlInputElement>().unwrap().value().parse().unwrap_or(0.0),
+                spent_on: if date.is_empty() { None } else { Some(date) },
+            };
+            let token = token.clone();
+            let refresh_trigger = refresh_trigger.clone();
+            let description_ref = description_ref.clone();
+            let amount_ref = amount_ref.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(token) = token {
+                    let body = serde_json::to_string(&request).unwrap();
+                    let resp = Request::post(&format!("http://127.0.0.1:8080/kermesses/{}/expenses", kermesse_id))
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    match resp {
+                        Ok(resp) if resp.ok() => {
+                            description_ref.cast::<HtmlInputElement>().unwrap().set_value("");
+                            amount_ref.cast::<HtmlInputElement>().unwrap().set_value("");
+                            refresh_trigger.set(*refresh_trigger + 1);
+                        }
+                        Ok(resp) => gloo_dialogs::alert(&resp.text().await.unwrap_or_default()),
+                        Err(_) => gloo_dialogs::alert("Error de conexión."),
+                    }
+                }
+            });
+        })
+    };
+
+    let on_remove = {
+        let token = token.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        Callback::from(move |expense_id: i32| {
+            if !gloo_dialogs::confirm("¿Eliminar este gasto?") {
+                return;
+            }
+            let token = token.clone();
+            let refresh_trigger = refresh_trigger.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(token) = token {
+                    let resp = Request::delete(&format!("http://127.0.0.1:8080/expenses/{}", expense_id))
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await;
+                    match resp {
+                        Ok(resp) if resp.ok() => refresh_trigger.set(*refresh_trigger + 1),
+                        _ => gloo_dialogs::alert("No se pudo eliminar el gasto."),
+                    }
+                }
+            });
+        })
+    };
+
+    // The exports need the organizer's token, so they are fetched rather than linked
+    let export_button = |file: &str, file_name: String, label: &str| -> Html {
+        let url = format!("http://127.0.0.1:8080/kermesses/{}/export/{}", kermesse_id, file);
+        let token = token.clone().unwrap_or_default();
+        let onclick = Callback::from(move |_| download(url.clone(), token.clone(), file_name.clone()));
+        html! {
+            <button {onclick} class="px-3 py-2 bg-white border rounded-lg text-sm font-bold text-gray-700 hover:bg-gray-100">{ label }</button>
+        }
+    };
+
+    let total: f64 = expenses.iter().map(|e| e.amount).sum();
+
+    html! {
+        <div class="space-y-6">
+            <div class="flex flex-wrap items-center gap-2 bg-gray-50 p-4 rounded-lg">
+                <span class="text-sm font-bold text-gray-600 mr-2">{ "📥 Exportar para tesorería:" }</span>
+                { export_button("workbook.xlsx", format!("kermesse-{}.xlsx", kermesse_id), "Excel (todo)") }
+                { export_button("sales.csv", format!("kermesse-{}-ventas.csv", kermesse_id), "Ventas CSV") }
+                { export_button("donations.csv", format!("kermesse-{}-donaciones.csv", kermesse_id), "Donaciones CSV") }
+                { export_button("ingredient-donations.csv", format!("kermesse-{}-donaciones-ingredientes.csv", kermesse_id), "Ingredientes CSV") }
+                { export_button("expenses.csv", format!("kermesse-{}-gastos.csv", kermesse_id), "Gastos CSV") }
+            </div>
+
+            <form onsubmit={on_create} class="grid grid-cols-2 md:grid-cols-5 gap-3 items-end bg-gray-50 p-4 rounded-lg">
+                <div class="col-span-2">
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Descripción" }</label>
+                    <input ref={description_ref} type="text" required=true placeholder="Garrafa de gas" class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Categoría" }</label>
+                    <select ref={category_ref} class="w-full rounded-lg border-gray-300 p-2 border">
+                        { CATEGORIES.iter().map(|(key, label)| html! { <option value={*key}>{ *label }</option> }).collect::<Html>() }
+                    </select>
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Monto (Bs.)" }</label>
+                    <input ref={amount_ref} type="number" min="0.01" step="0.01" required=true class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Fecha" }</label>
+                    <input ref={date_ref} type="date" class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <button type="submit" class="col-span-2 md:col-span-5 bg-primary text-white font-bold py-2 rounded-lg hover:bg-red-600 transition">{ "Registrar Gasto" }</button>
+            </form>
+
+            if expenses.is_empty() {
+                <div class="text-center py-8 text-gray-500 bg-white rounded-lg border border-dashed border-gray-300">
+                    { "Aún no se registraron gastos." }
+                </div>
+            } else {
+                <div class="bg-white rounded-lg shadow overflow-hidden">
+                    <table class="w-full text-left text-sm">
+                        <thead class="bg-gray-50 text-gray-600 border-b">
+                            <tr>
+                                <th class="p-3 font-semibold">{ "Fecha" }</th>
+                                <th class="p-3 font-semibold">{ "Descripción" }</th>
+                                <th class="p-3 font-semibold">{ "Categoría" }</th>
+                                <th class="p-3 font-semibold text-right">{ "Monto" }</th>
+                                <th class="p-3"></th>
+                            </tr>
+                        </thead>
+                        <tbody class="divide-y divide-gray-100">
+                            {
+                                expenses.iter().map(|expense| {
+                                    let id = expense.id;
+                                    let on_remove = on_remove.clone();
+                                    html! {
+                                        <tr>
+                                            <td class="p-3 text-gray-500">{ &expense.spent_on }</td>
+                                            <td class="p-3">{ &expense.description }</td>
+                                            <td class="p-3 text-gray-500">{ category_label(&expense.category) }</td>
+                                            <td class="p-3 text-right font-bold">{ format!("Bs. {:.2}", expense.amount) }</td>
+                                            <td class="p-3 text-center">
+                                                <button onclick={Callback::from(move |_| on_remove.emit(id))} class="text-red-500 hover:text-red-700" title="Eliminar gasto">{ "✕" }</button>
+                                            </td>
+                                        </tr>
+                                    }
+                                }).collect::<Html>()
+                            }
+                        </tbody>
+                        <tfoot class="bg-gray-50 border-t">
+                            <tr>
+                                <td colspan="3" class="p-3 font-bold text-right">{ "Total gastos" }</td>
+                                <td class="p-3 text-right font-bold">{ format!("Bs. {:.2}", total) }</td>
+                                <td></td>
+                            </tr>
+                        </tfoot>
+                    </table>
+                </div>
+            }
+        </div>
+    }
+}
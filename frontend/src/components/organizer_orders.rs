@@ -1,15 +1,95 @@
 use yew::prelude::*;
 use reqwasm::http::Request;
 use crate::context::UserContext;
+use crate::components::live_events::use_live_events;
+use crate::components::organizer_dashboard::deserialize_price;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, PartialEq, Deserialize, Serialize, Debug)]
 pub struct SaleResponse {
     pub id: i32,
     pub customer_name: String,
+    #[serde(deserialize_with = "deserialize_price")]
     pub total_amount: f64,
     pub status: String,
-    // Add more fields if needed for the dashboard details
+    #[serde(default)]
+    pub payment_method: String,
+    #[serde(default)]
+    pub delivery_method: String,
+    #[serde(default)]
+    pub delivery_person_id: Option<i32>,
+    #[serde(default)]
+    pub created_at: String,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct StatusCount {
+    pub status: String,
+    pub count: i64,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct SellerCount {
+    pub seller_id: i32,
+    pub full_name: String,
+    pub count: i64,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct SalesPage {
+    pub items: Vec<SaleResponse>,
+    pub next_cursor: Option<String>,
+    pub total: u64,
+    pub status_counts: Vec<StatusCount>,
+    pub sellers: Vec<SellerCount>,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct SaleDetailItem {
+    pub dish_name: String,
+    pub quantity: i32,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub subtotal: f64,
+    pub prep_status: String,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct SaleHistoryEntry {
+    pub status: String,
+    pub changed_by: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct SaleDetail {
+    pub id: i32,
+    pub contact_phone: Option<String>,
+    pub seller_name: Option<String>,
+    pub payment_method: String,
+    pub payment_provider: Option<String>,
+    pub delivery_method: String,
+    pub delivery_address: Option<String>,
+    pub delivery_neighborhood: Option<String>,
+    pub delivery_city: Option<String>,
+    pub delivery_zone: Option<String>,
+    pub delivery_person_name: Option<String>,
+    pub time_slot: Option<String>,
+    pub promo_code: Option<String>,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub discount_amount: f64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub delivery_fee: f64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub extra_donation: f64,
+    pub pickup_code: Option<String>,
+    pub items: Vec<SaleDetailItem>,
+    pub history: Vec<SaleHistoryEntry>,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct DeliveryPerson {
+    pub user_id: i32,
+    pub full_name: String,
 }
 
 #[derive(Properties, PartialEq)]
@@ -25,6 +105,56 @@ pub enum OrderFilter {
     Delivered,
 }
 
+impl OrderFilter {
+    fn statuses(&self) -> &'static [&'static str] {
+        match self {
+            OrderFilter::All => &[],
+            OrderFilter::Pending => &["PENDING", "PENDING_PAYMENT"],
+            OrderFilter::Paid => &["PAID", "CONFIRMED"],
+            OrderFilter::Delivered => &["DELIVERED", "OUT_FOR_DELIVERY"],
+        }
+    }
+}
+
+/// Server-side filters and sort for the orders list; the status tab lives in `OrderFilter`.
+#[derive(Clone, PartialEq, Default)]
+pub struct OrdersQuery {
+    pub search: String,
+    pub payment_method: String,
+    pub delivery_method: String,
+    pub seller_id: String,
+    pub from: String,
+    pub to: String,
+    pub sort: String, // "field:order", empty for newest first
+}
+
+fn sales_url(kermesse_id: i32, filter: &OrderFilter, query: &OrdersQuery, cursor: Option<&str>) -> String {
+    let params = web_sys::UrlSearchParams::new().unwrap();
+    if !filter.statuses().is_empty() {
+        params.append("status", &filter.statuses().join(","));
+    }
+    for (key, value) in [
+        ("search", &query.search),
+        ("payment_method", &query.payment_method),
+        ("delivery_method", &query.delivery_method),
+        ("seller_id", &query.seller_id),
+        ("from", &query.from),
+        ("to", &query.to),
+    ] {
+        if !value.trim().is_empty() {
+            params.append(key, value.trim());
+        }
+    }
+    if let Some((sort, order)) = query.sort.split_once(':') {
+        params.append("sort", sort);
+        params.append("order", order);
+    }
+    if let Some(cursor) = cursor {
+        params.append("cursor", cursor);
+    }
+    format!("http://127.0.0.1:8080/kermesses/{}/sales?{}", kermesse_id, String::from(params.to_string()))
+}
+
 #[function_component(OrganizerOrders)]
 pub fn organizer_orders(props: &Props) -> Html {
     let user_ctx = use_context::<UserContext>().expect("No UserContext found");
@@ -32,30 +162,52 @@ pub fn organizer_orders(props: &Props) -> Html {
     let filter = use_state(|| OrderFilter::All);
     let kermesse_id = props.kermesse_id;
     let loading = use_state(|| true);
+    let query = use_state(OrdersQuery::default);
+    let next_cursor = use_state(|| None::<String>);
+    let total = use_state(|| 0u64);
+    let status_counts = use_state(Vec::<StatusCount>::new);
+    let sellers = use_state(Vec::<SellerCount>::new);
+    let loading_more = use_state(|| false);
+    
+    let delivery_people = use_state(Vec::<DeliveryPerson>::new);
+    // Row currently expanded and its full order, fetched on demand
+    let expanded = use_state(|| None::<i32>);
+    let detail = use_state(|| None::<SaleDetail>);
     
     // Refresh Trigger
     let refresh_trigger = use_state(|| 0);
+    // Refetch whenever the kermesse publishes an order event
+    let live = use_live_events(
+        Some(format!("http://127.0.0.1:8080/kermesses/{}/events", kermesse_id)),
+        user_ctx.user.as_ref().map(|u| u.token.clone()),
+    );
 
+    // First page; any filter change, refresh or live event starts over
     {
         let orders = orders.clone();
         let loading = loading.clone();
         let user_ctx = user_ctx.clone();
-        let refresh_trigger = refresh_trigger.clone();
-        use_effect_with(refresh_trigger, move |_| {
+        let next_cursor = next_cursor.clone();
+        let total = total.clone();
+        let status_counts = status_counts.clone();
+        let sellers = sellers.clone();
+        use_effect_with(((*filter).clone(), (*query).clone(), *refresh_trigger, live.version), move |(filter, query, _, _)| {
             if let Some(user) = &user_ctx.user {
                 let token = user.token.clone();
-                let orders = orders.clone();
-                let loading = loading.clone();
+                let url = sales_url(kermesse_id, filter, query, None);
                 wasm_bindgen_futures::spawn_local(async move {
-                    let url = format!("http://127.0.0.1:8080/kermesses/{}/sales", kermesse_id);
                     let resp = Request::get(&url)
                         .header("Authorization", &format!("Bearer {}", token))
                         .send()
                         .await;
 
                     if let Ok(resp) = resp {
-                         if let Ok(data) = resp.json::<Vec<SaleResponse>>().await {
-                             orders.set(data);
+                         if let Ok(page) = resp.json::<SalesPage>().await {
+                             orders.set(page.items);
+                             next_cursor.set(page.next_cursor);
+                             total.set(page.total);
+                             status_counts.set(page.status_counts);
+                             sellers.set(page.sellers);
                          }
                     }
                     loading.set(false);
@@ -65,6 +217,97 @@ pub fn organizer_orders(props: &Props) -> Html {
         });
     }
 
+    let load_more = {
+        let user_ctx = user_ctx.clone();
+        let orders = orders.clone();
+        let next_cursor = next_cursor.clone();
+        let loading_more = loading_more.clone();
+        let filter = filter.clone();
+        let query = query.clone();
+        Callback::from(move |_| {
+            let (Some(user), Some(cursor)) = (user_ctx.user.as_ref(), (*next_cursor).clone()) else { return };
+            let token = user.token.clone();
+            let url = sales_url(kermesse_id, &filter, &query, Some(&cursor));
+            let orders = orders.clone();
+            let next_cursor = next_cursor.clone();
+            let loading_more = loading_more.clone();
+            loading_more.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(resp) = Request::get(&url)
+                    .header("Authorization", &format!("Bearer {}", token))
+                    .send()
+                    .await
+                {
+                    if let Ok(page) = resp.json::<SalesPage>().await {
+                        let mut all = (*orders).clone();
+                        all.extend(page.items);
+                        orders.set(all);
+                        next_cursor.set(page.next_cursor);
+                    }
+                }
+                loading_more.set(false);
+            });
+        })
+    };
+
+    // Each control overwrites one field of the query
+    let set_query = |apply: fn(&mut OrdersQuery, String)| {
+        let query = query.clone();
+        Callback::from(move |e: Event| {
+            let value = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+            let mut next = (*query).clone();
+            apply(&mut next, value);
+            query.set(next);
+        })
+    };
+
+    // Only the organizer may list delivery staff; collaborators just get no selector
+    {
+        let delivery_people = delivery_people.clone();
+        let user_ctx = user_ctx.clone();
+        use_effect_with(kermesse_id, move |_| {
+            if let Some(user) = &user_ctx.user {
+                let token = user.token.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let url = format!("http://127.0.0.1:8080/kermesses/{}/delivery-people", kermesse_id);
+                    if let Ok(resp) = Request::get(&url)
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await
+                    {
+                        if let Ok(data) = resp.json::<Vec<DeliveryPerson>>().await {
+                            delivery_people.set(data);
+                        }
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let assign_delivery = {
+        let user_ctx = user_ctx.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        Callback::from(move |(sale_id, person_id): (i32, Option<i32>)| {
+             let token = user_ctx.user.as_ref().unwrap().token.clone();
+             let refresh_trigger = refresh_trigger.clone();
+             wasm_bindgen_futures::spawn_local(async move {
+                 let url = format!("http://127.0.0.1:8080/sales/{}/delivery-assignment", sale_id);
+                 let body = serde_json::json!({ "delivery_person_id": person_id });
+                 match Request::put(&url)
+                     .header("Authorization", &format!("Bearer {}", token))
+                     .header("Content-Type", "application/json")
+                     .body(body.to_string())
+                     .send()
+                     .await
+                 {
+                     Ok(resp) if resp.ok() => refresh_trigger.set(*refresh_trigger + 1),
+                     _ => gloo_dialogs::alert("No se pudo asignar el repartidor."),
+                 }
+             });
+        })
+    };
+
     let update_status = {
         let user_ctx = user_ctx.clone();
         let refresh_trigger = refresh_trigger.clone();
@@ -87,21 +330,166 @@ pub fn organizer_orders(props: &Props) -> Html {
         })
     };
 
-    let filtered_orders = orders.iter().filter(|o| {
-        match *filter {
-            OrderFilter::All => true,
-            OrderFilter::Pending => o.status == "PENDING" || o.status == "PENDING_PAYMENT",
-            OrderFilter::Paid => o.status == "PAID" || o.status == "CONFIRMED",
-            OrderFilter::Delivered => o.status == "DELIVERED",
+    let refund_order = {
+        let user_ctx = user_ctx.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        Callback::from(move |sale_id: i32| {
+             let reason = match gloo_dialogs::prompt("Motivo del reembolso", None) {
+                 Some(r) if !r.trim().is_empty() => r,
+                 _ => return,
+             };
+             let method = gloo_dialogs::prompt("Método de devolución (CASH, QR, TRANSFER)", Some("CASH")).unwrap_or_else(|| "CASH".to_string());
+             let restock = gloo_dialogs::confirm("¿Devolver los platos al stock disponible?");
+             let token = user_ctx.user.as_ref().unwrap().token.clone();
+             let refresh_trigger = refresh_trigger.clone();
+             wasm_bindgen_futures::spawn_local(async move {
+                 let url = format!("http://127.0.0.1:8080/sales/{}/refunds", sale_id);
+                 let body = serde_json::json!({ "reason": reason, "refund_method": method, "restock": restock });
+                 match Request::post(&url)
+                     .header("Authorization", &format!("Bearer {}", token))
+                     .header("Content-Type", "application/json")
+                     .body(body.to_string())
+                     .send()
+                     .await
+                 {
+                     Ok(resp) if resp.ok() => refresh_trigger.set(*refresh_trigger + 1),
+                     _ => gloo_dialogs::alert("No se pudo registrar el reembolso."),
+                 }
+             });
+        })
+    };
+
+    let toggle_detail = {
+        let user_ctx = user_ctx.clone();
+        let expanded = expanded.clone();
+        let detail = detail.clone();
+        Callback::from(move |sale_id: i32| {
+            if *expanded == Some(sale_id) {
+                expanded.set(None);
+                return;
+            }
+            expanded.set(Some(sale_id));
+            detail.set(None);
+            let token = user_ctx.user.as_ref().unwrap().token.clone();
+            let detail = detail.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let url = format!("http://127.0.0.1:8080/sales/{}", sale_id);
+                if let Ok(resp) = Request::get(&url)
+                    .header("Authorization", &format!("Bearer {}", token))
+                    .send()
+                    .await
+                {
+                    if let Ok(data) = resp.json::<SaleDetail>().await {
+                        detail.set(Some(data));
+                    }
+                }
+            });
+        })
+    };
+
+    let render_detail = |d: &SaleDetail| -> Html {
+        let delivery = if d.delivery_method == "DELIVERY" {
+            let place = [d.delivery_address.clone(), d.delivery_neighborhood.clone(), d.delivery_city.clone()]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("🛵 Delivery: {}", if place.is_empty() { "-".to_string() } else { place })
+        } else {
+            "🏪 Recojo en el lugar".to_string()
+        };
+        html! {
+            <div class="grid md:grid-cols-3 gap-4 text-xs text-gray-700">
+                <div>
+                    <h4 class="font-bold text-gray-500 uppercase mb-1">{ "Platos" }</h4>
+                    <ul class="space-y-1">
+                        { d.items.iter().map(|i| html! {
+                            <li class="flex justify-between gap-2">
+                                <span>{ format!("{} x {}", i.quantity, i.dish_name) }<span class="text-gray-400">{ format!(" ({})", i.prep_status) }</span></span>
+                                <span class="font-mono">{ format!("Bs. {:.2}", i.subtotal) }</span>
+                            </li>
+                        }).collect::<Html>() }
+                    </ul>
+                    if d.discount_amount > 0.0 {
+                        <p class="mt-1 text-green-700">{ format!("Descuento{}: -Bs. {:.2}", d.promo_code.as_ref().map(|c| format!(" ({})", c)).unwrap_or_default(), d.discount_amount) }</p>
+                    }
+                    if d.delivery_fee > 0.0 {
+                        <p>{ format!("Envío: Bs. {:.2}", d.delivery_fee) }</p>
+                    }
+                    if d.extra_donation > 0.0 {
+                        <p>{ format!("Donación extra: Bs. {:.2}", d.extra_donation) }</p>
+                    }
+                </div>
+                <div class="space-y-1">
+                    <h4 class="font-bold text-gray-500 uppercase mb-1">{ "Entrega y pago" }</h4>
+                    <p>{ format!("📞 {}", d.contact_phone.clone().unwrap_or_else(|| "-".to_string())) }</p>
+                    <p>{ delivery }</p>
+                    if let Some(zone) = &d.delivery_zone {
+                        <p>{ format!("Zona: {}", zone) }</p>
+                    }
+                    if let Some(person) = &d.delivery_person_name {
+                        <p>{ format!("Repartidor: {}", person) }</p>
+                    }
+                    if let Some(slot) = &d.time_slot {
+                        <p>{ format!("Horario: {}", slot) }</p>
+                    }
+                    <p>{ format!("💳 {}{}", d.payment_method, d.payment_provider.as_ref().map(|p| format!(" ({})", p)).unwrap_or_default()) }</p>
+                    if let Some(code) = &d.pickup_code {
+                        <p>{ "Código de recojo: " }<span class="font-mono font-bold">{ code }</span></p>
+                    }
+                    if let Some(seller) = &d.seller_name {
+                        <p>{ format!("Vendido por: {}", seller) }</p>
+                    }
+                </div>
+                <div>
+                    <h4 class="font-bold text-gray-500 uppercase mb-1">{ "Historial" }</h4>
+                    if d.history.is_empty() {
+                        <p class="text-gray-400">{ "Sin cambios registrados." }</p>
+                    } else {
+                        <ol class="space-y-1 border-l-2 border-gray-200 pl-2">
+                            { d.history.iter().map(|h| html! {
+                                <li>
+                                    <span class="font-bold">{ &h.status }</span>
+                                    <span class="text-gray-400">{ format!(" · {}", h.created_at.chars().take(16).collect::<String>()) }</span>
+                                    if let Some(by) = &h.changed_by {
+                                        <span class="text-gray-500">{ format!(" · {}", by) }</span>
+                                    }
+                                </li>
+                            }).collect::<Html>() }
+                        </ol>
+                    }
+                </div>
+            </div>
         }
-    }).collect::<Vec<_>>();
+    };
+
+    let tab_count = |f: &OrderFilter| -> i64 {
+        status_counts
+            .iter()
+            .filter(|c| f.statuses().is_empty() || f.statuses().contains(&c.status.as_str()))
+            .map(|c| c.count)
+            .sum()
+    };
+    let tab = |f: OrderFilter, label: &str| -> Html {
+        let active = *filter == f;
+        let count = tab_count(&f);
+        let onclick = { let filter = filter.clone(); Callback::from(move |_| filter.set(f.clone())) };
+        html! {
+            <button {onclick} class={format!("px-3 py-1 rounded-full transition whitespace-nowrap {}", if active { "bg-primary text-white" } else { "bg-white text-gray-600 hover:bg-gray-100" })}>
+                { format!("{} ({})", label, count) }
+            </button>
+        }
+    };
 
     let status_badge = |status: &str| -> Html {
         let (color, label) = match status {
             "PENDING" | "PENDING_PAYMENT" => ("bg-yellow-100 text-yellow-800", "Pendiente"),
             "PAID" | "CONFIRMED" => ("bg-green-100 text-green-800", "Pagado/Confirmado"),
+            "OUT_FOR_DELIVERY" => ("bg-indigo-100 text-indigo-800", "En camino"),
             "DELIVERED" => ("bg-blue-100 text-blue-800", "Entregado"),
             "CANCELLED" => ("bg-red-100 text-red-800", "Cancelado"),
+            "REFUNDED" => ("bg-gray-200 text-gray-700", "Reembolsado"),
+            "PARTIALLY_REFUNDED" => ("bg-orange-100 text-orange-800", "Reembolso parcial"),
              _ => ("bg-gray-100 text-gray-800", status),
         };
         html! { <span class={format!("px-2 py-1 rounded-full text-xs font-bold {}", color)}>{ label }</span> }
@@ -111,19 +499,50 @@ pub fn organizer_orders(props: &Props) -> Html {
         <div class="space-y-4">
             <div class="flex flex-col sm:flex-row justify-between items-center gap-4 bg-gray-50 p-4 rounded-lg">
                 <div class="flex gap-2 text-sm overflow-x-auto w-full sm:w-auto">
-                    <button onclick={let f = filter.clone(); Callback::from(move |_| f.set(OrderFilter::All))} class={format!("px-3 py-1 rounded-full transition {}", if *filter == OrderFilter::All { "bg-primary text-white" } else { "bg-white text-gray-600 hover:bg-gray-100" })}>{ "Todos" }</button>
-                    <button onclick={let f = filter.clone(); Callback::from(move |_| f.set(OrderFilter::Pending))} class={format!("px-3 py-1 rounded-full transition {}", if *filter == OrderFilter::Pending { "bg-primary text-white" } else { "bg-white text-gray-600 hover:bg-gray-100" })}>{ "Pendientes" }</button>
-                    <button onclick={let f = filter.clone(); Callback::from(move |_| f.set(OrderFilter::Paid))} class={format!("px-3 py-1 rounded-full transition {}", if *filter == OrderFilter::Paid { "bg-primary text-white" } else { "bg-white text-gray-600 hover:bg-gray-100" })}>{ "Pagados" }</button>
-                    <button onclick={let f = filter.clone(); Callback::from(move |_| f.set(OrderFilter::Delivered))} class={format!("px-3 py-1 rounded-full transition {}", if *filter == OrderFilter::Delivered { "bg-primary text-white" } else { "bg-white text-gray-600 hover:bg-gray-100" })}>{ "Entregados" }</button>
+                    { tab(OrderFilter::All, "Todos") }
+                    { tab(OrderFilter::Pending, "Pendientes") }
+                    { tab(OrderFilter::Paid, "Pagados") }
+                    { tab(OrderFilter::Delivered, "Entregados") }
                 </div>
                 <button onclick={let rt = refresh_trigger.clone(); Callback::from(move |_| rt.set(*rt + 1))} class="text-secondary hover:text-teal-700 font-bold text-sm flex items-center gap-1">
                     <span>{"🔄"}</span> { "Actualizar" }
                 </button>
             </div>
 
+            <div class="grid grid-cols-2 md:grid-cols-4 lg:grid-cols-7 gap-2 text-xs">
+                <input type="search" placeholder="Cliente o # de orden" value={query.search.clone()} onchange={set_query(|q, v| q.search = v)} class="col-span-2 border rounded p-2" />
+                <select onchange={set_query(|q, v| q.payment_method = v)} class="border rounded p-2">
+                    <option value="" selected={query.payment_method.is_empty()}>{ "Todo pago" }</option>
+                    <option value="CASH" selected={query.payment_method == "CASH"}>{ "Efectivo" }</option>
+                    <option value="QR" selected={query.payment_method == "QR"}>{ "QR" }</option>
+                </select>
+                <select onchange={set_query(|q, v| q.delivery_method = v)} class="border rounded p-2">
+                    <option value="" selected={query.delivery_method.is_empty()}>{ "Toda entrega" }</option>
+                    <option value="PICKUP" selected={query.delivery_method == "PICKUP"}>{ "Recojo" }</option>
+                    <option value="DELIVERY" selected={query.delivery_method == "DELIVERY"}>{ "Delivery" }</option>
+                </select>
+                <select onchange={set_query(|q, v| q.seller_id = v)} class="border rounded p-2">
+                    <option value="" selected={query.seller_id.is_empty()}>{ "Todo vendedor" }</option>
+                    {
+                        sellers.iter().map(|s| html! {
+                            <option value={s.seller_id.to_string()} selected={query.seller_id == s.seller_id.to_string()}>{ format!("{} ({})", s.full_name, s.count) }</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+                <input type="date" title="Desde" value={query.from.clone()} onchange={set_query(|q, v| q.from = v)} class="border rounded p-2" />
+                <input type="date" title="Hasta" value={query.to.clone()} onchange={set_query(|q, v| q.to = v)} class="border rounded p-2" />
+                <select onchange={set_query(|q, v| q.sort = v)} class="border rounded p-2">
+                    <option value="" selected={query.sort.is_empty()}>{ "Más recientes" }</option>
+                    <option value="created_at:asc" selected={query.sort == "created_at:asc"}>{ "Más antiguos" }</option>
+                    <option value="total_amount:desc" selected={query.sort == "total_amount:desc"}>{ "Mayor total" }</option>
+                    <option value="total_amount:asc" selected={query.sort == "total_amount:asc"}>{ "Menor total" }</option>
+                    <option value="customer_name:asc" selected={query.sort == "customer_name:asc"}>{ "Cliente A-Z" }</option>
+                </select>
+            </div>
+
             if *loading {
                  <div class="text-center py-8">{ "Cargando pedidos..." }</div>
-            } else if filtered_orders.is_empty() {
+            } else if orders.is_empty() {
                  <div class="text-center py-8 text-gray-500 bg-white rounded-lg border border-dashed border-gray-300">
                     { "No hay pedidos en esta categoría." }
                  </div>
@@ -141,14 +560,39 @@ pub fn organizer_orders(props: &Props) -> Html {
                         </thead>
                         <tbody class="divide-y divide-gray-100">
                             {
-                                filtered_orders.iter().map(|o| {
+                                orders.iter().map(|o| {
                                     let id = o.id;
                                     let status = o.status.clone();
                                     let update_status = update_status.clone();
+                                    let refund_order = refund_order.clone();
+                                    let assign_delivery = assign_delivery.clone();
+                                    let on_assign = Callback::from(move |e: Event| {
+                                        let value = e.target_unchecked_into::<web_sys::HtmlSelectElement>().value();
+                                        assign_delivery.emit((id, value.parse::<i32>().ok()));
+                                    });
+                                    let is_expanded = *expanded == Some(id);
+                                    let on_toggle = { let t = toggle_detail.clone(); Callback::from(move |_| t.emit(id)) };
                                     html! {
+                                        <>
                                         <tr class="hover:bg-gray-50 transition">
-                                            <td class="p-3 font-mono text-gray-500">{ format!("#{}", o.id) }</td>
-                                            <td class="p-3 font-medium">{ &o.customer_name }</td>
+                                            <td class="p-3 font-mono text-gray-500">
+                                                <button onclick={on_toggle} class="hover:text-primary" title="Ver detalle">
+                                                    { format!("{} #{}", if is_expanded { "▾" } else { "▸" }, o.id) }
+                                                </button>
+                                            </td>
+                                            <td class="p-3 font-medium">
+                                                { &o.customer_name }
+                                                if o.delivery_method == "DELIVERY" && !delivery_people.is_empty() {
+                                                    <select onchange={on_assign} class="block mt-1 text-xs rounded border-gray-300 border p-1 text-gray-600">
+                                                        <option value="" selected={o.delivery_person_id.is_none()}>{ "🛵 Sin repartidor" }</option>
+                                                        {
+                                                            delivery_people.iter().map(|p| html! {
+                                                                <option value={p.user_id.to_string()} selected={o.delivery_person_id == Some(p.user_id)}>{ &p.full_name }</option>
+                                                            }).collect::<Html>()
+                                                        }
+                                                    </select>
+                                                }
+                                            </td>
                                             <td class="p-3 text-right font-bold">{ format!("Bs. {:.2}", o.total_amount) }</td>
                                             <td class="p-3 text-center">{ status_badge(&o.status) }</td>
                                             <td class="p-3 flex justify-center gap-2">
@@ -158,16 +602,40 @@ pub fn organizer_orders(props: &Props) -> Html {
                                                 if status == "PAID" || status == "CONFIRMED" {
                                                     <button onclick={let u = update_status.clone(); Callback::from(move |_| u.emit((id, "DELIVERED".to_string())))} class="bg-blue-100 text-blue-700 hover:bg-blue-200 px-2 py-1 rounded text-xs font-bold" title="Marcar como Entregado">{ "🚀 Enviar" }</button>
                                                 }
-                                                if status != "CANCELLED" && status != "DELIVERED" {
+                                                if status == "PAID" || status == "OUT_FOR_DELIVERY" || status == "DELIVERED" || status == "PARTIALLY_REFUNDED" {
+                                                    <button onclick={Callback::from(move |_| refund_order.emit(id))} class="bg-orange-50 text-orange-700 hover:bg-orange-100 px-2 py-1 rounded text-xs font-bold" title="Registrar Reembolso">{ "↩ Reembolsar" }</button>
+                                                }
+                                                if status != "CANCELLED" && status != "DELIVERED" && status != "REFUNDED" && status != "PARTIALLY_REFUNDED" {
                                                      <button onclick={let u = update_status.clone(); Callback::from(move |_| u.emit((id, "CANCELLED".to_string())))} class="bg-red-50 text-red-600 hover:bg-red-100 px-2 py-1 rounded text-xs" title="Cancelar Pedido">{ "✕" }</button>
                                                 }
                                             </td>
                                         </tr>
+                                        if is_expanded {
+                                            <tr class="bg-gray-50">
+                                                <td colspan="5" class="p-4">
+                                                    {
+                                                        match detail.as_ref() {
+                                                            Some(d) if d.id == id => render_detail(d),
+                                                            _ => html! { <p class="text-xs text-gray-400">{ "Cargando detalle..." }</p> },
+                                                        }
+                                                    }
+                                                </td>
+                                            </tr>
+                                        }
+                                        </>
                                     }
                                 }).collect::<Html>()
                             }
                         </tbody>
                     </table>
+                    <div class="flex justify-between items-center p-3 border-t text-xs text-gray-500">
+                        <span>{ format!("Mostrando {} de {} pedidos", orders.len(), *total) }</span>
+                        if next_cursor.is_some() {
+                            <button onclick={load_more} disabled={*loading_more} class="text-secondary hover:text-teal-700 font-bold disabled:opacity-50">
+                                { if *loading_more { "Cargando..." } else { "Cargar más" } }
+                            </button>
+                        }
+                    </div>
                 </div>
             }
         </div>
@@ -0,0 +1,199 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use web_sys::HtmlInputElement;
+use serde::Deserialize;
+use crate::context::UserContext;
+use crate::components::organizer_dashboard::deserialize_price;
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct OrganizerOverview {
+    pub kermesses: Vec<KermesseComparison>,
+    pub years: Vec<YearSummary>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct KermesseComparison {
+    pub id: i32,
+    pub name: String,
+    pub event_date: String,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub total_raised: f64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub donations_total: f64,
+    pub orders: i64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub average_ticket: f64,
+    pub ingredient_coverage_percentage: f64,
+    pub collaborators: i64,
+    pub top_dishes: Vec<TopDish>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct TopDish {
+    pub name: String,
+    pub units_sold: i64,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct YearSummary {
+    pub year: i32,
+    pub kermesses: i64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub total_raised: f64,
+    pub orders: i64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub average_ticket: f64,
+    pub total_raised_change_percentage: Option<f64>,
+}
+
+/// Side-by-side comparison of every kermesse the organizer ran, optionally limited to a date range.
+#[function_component(OrganizerOverviewPanel)]
+pub fn organizer_overview_panel() -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let overview = use_state(|| None::<OrganizerOverview>);
+    let range = use_state(|| (String::new(), String::new()));
+    let token = user_ctx.user.as_ref().map(|u| u.token.clone());
+
+    {
+        let overview = overview.clone();
+        use_effect_with((*range).clone(), move |(from, to)| {
+            let mut url = "http://127.0.0.1:8080/my-kermesses/overview".to_string();
+            let params: Vec<String> = [("from", from), ("to", to)]
+                .iter()
+                .filter(|(_, v)| !v.is_empty())
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect();
+            if !params.is_empty() {
+                url = format!("{}?{}", url, params.join("&"));
+            }
+            if let Some(token) = token {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(resp) = Request::get(&url)
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await
+                    {
+                        if resp.ok() {
+                            if let Ok(data) = resp.json::<OrganizerOverview>().await {
+                                overview.set(Some(data));
+                            }
+                        }
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_from = {
+        let range = range.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            range.set((input.value(), range.1.clone()));
+        })
+    };
+    let on_to = {
+        let range = range.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            range.set((range.0.clone(), input.value()));
+        })
+    };
+
+    let Some(data) = &*overview else {
+        return html! {};
+    };
+    let best_raised = data.kermesses.iter().map(|k| k.total_raised).fold(0.0, f64::max);
+
+    html! {
+        <div class="bg-white rounded-2xl shadow-md border border-gray-100 p-6 mb-10">
+            <div class="flex flex-wrap justify-between items-center gap-4 mb-6">
+                <h3 class="text-2xl font-bold text-gray-800">{ "Comparativa de Eventos" }</h3>
+                <div class="flex items-center gap-2 text-sm">
+                    <input type="date" title="Desde" value={range.0.clone()} onchange={on_from} class="border rounded p-2" />
+                    <span class="text-gray-400">{ "—" }</span>
+                    <input type="date" title="Hasta" value={range.1.clone()} onchange={on_to} class="border rounded p-2" />
+                </div>
+            </div>
+
+            if data.kermesses.is_empty() {
+                <p class="text-gray-500 italic">{ "No hay eventos en el rango seleccionado." }</p>
+            } else {
+                // Year over year
+                <div class="grid grid-cols-2 md:grid-cols-4 gap-4 mb-6">
+                    {
+                        data.years.iter().map(|y| html! {
+                            <div class="bg-gray-50 rounded-xl p-4 border border-gray-100">
+                                <p class="text-xs text-gray-400 font-bold uppercase tracking-wider">{ format!("{} · {} eventos", y.year, y.kermesses) }</p>
+                                <p class="text-2xl font-display font-bold text-green-600">{ format!("Bs. {:.0}", y.total_raised) }</p>
+                                <p class="text-xs text-gray-500">{ format!("{} pedidos · ticket Bs. {:.2}", y.orders, y.average_ticket) }</p>
+                                {
+                                    match y.total_raised_change_percentage {
+                                        Some(change) => html! {
+                                            <p class={if change >= 0.0 { "text-xs font-bold text-green-700" } else { "text-xs font-bold text-red-600" }}>
+                                                { format!("{}{:.1}% vs. año anterior", if change >= 0.0 { "▲ +" } else { "▼ " }, change) }
+                                            </p>
+                                        },
+                                        None => html! {},
+                                    }
+                                }
+                            </div>
+                        }).collect::<Html>()
+                    }
+                </div>
+
+                <div class="overflow-x-auto">
+                    <table class="w-full text-sm">
+                        <thead class="text-gray-500 border-b">
+                            <tr>
+                                <th class="text-left py-2">{ "Evento" }</th>
+                                <th class="text-left py-2 w-1/4">{ "Recaudado" }</th>
+                                <th class="text-center py-2">{ "Pedidos" }</th>
+                                <th class="text-right py-2">{ "Ticket prom." }</th>
+                                <th class="text-right py-2">{ "Donaciones" }</th>
+                                <th class="text-center py-2">{ "Insumos" }</th>
+                                <th class="text-center py-2">{ "Equipo" }</th>
+                                <th class="text-left py-2 pl-4">{ "Más vendidos" }</th>
+                            </tr>
+                        </thead>
+                        <tbody class="divide-y divide-gray-100">
+                            {
+                                data.kermesses.iter().map(|k| html! {
+                                    <tr key={k.id}>
+                                        <td class="py-2">
+                                            <p class="font-medium text-gray-800">{ &k.name }</p>
+                                            <p class="text-xs text-gray-400">{ &k.event_date }</p>
+                                        </td>
+                                        <td class="py-2 pr-4">
+                                            <div class="flex items-center gap-2">
+                                                <div class="flex-grow bg-gray-100 rounded-full h-3 overflow-hidden">
+                                                    <div class="bg-green-500 h-3 rounded-full"
+                                                        style={format!("width: {:.1}%", if best_raised > 0.0 { k.total_raised / best_raised * 100.0 } else { 0.0 })}></div>
+                                                </div>
+                                                <span class="font-bold whitespace-nowrap">{ format!("Bs. {:.0}", k.total_raised) }</span>
+                                            </div>
+                                        </td>
+                                        <td class="py-2 text-center">{ k.orders }</td>
+                                        <td class="py-2 text-right">{ format!("Bs. {:.2}", k.average_ticket) }</td>
+                                        <td class="py-2 text-right">{ format!("Bs. {:.0}", k.donations_total) }</td>
+                                        <td class="py-2 text-center">{ format!("{:.0}%", k.ingredient_coverage_percentage) }</td>
+                                        <td class="py-2 text-center">{ k.collaborators }</td>
+                                        <td class="py-2 pl-4 text-xs text-gray-600">
+                                            {
+                                                if k.top_dishes.is_empty() {
+                                                    "—".to_string()
+                                                } else {
+                                                    k.top_dishes.iter().map(|d| format!("{} ({})", d.name, d.units_sold)).collect::<Vec<_>>().join(", ")
+                                                }
+                                            }
+                                        </td>
+                                    </tr>
+                                }).collect::<Html>()
+                            }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        </div>
+    }
+}
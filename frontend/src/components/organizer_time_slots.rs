@@ -0,0 +1,137 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use serde::{Deserialize, Serialize};
+use crate::context::UserContext;
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct TimeSlot {
+    pub id: i32,
+    pub slot_type: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub capacity: i32,
+    pub remaining: i32,
+}
+
+#[derive(Serialize)]
+struct CreateTimeSlotRequest {
+    slot_type: String,
+    starts_at: String,
+    ends_at: String,
+    capacity: i32,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(OrganizerTimeSlots)]
+pub fn organizer_time_slots(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let slots = use_state(Vec::<TimeSlot>::new);
+    let kermesse_id = props.kermesse_id;
+    let refresh_trigger = use_state(|| 0);
+
+    let type_ref = use_node_ref();
+    let start_ref = use_node_ref();
+    let end_ref = use_node_ref();
+    let capacity_ref = use_node_ref();
+
+    {
+        let slots = slots.clone();
+        use_effect_with(refresh_trigger.clone(), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                let url = format!("http://127.0.0.1:8080/kermesses/{}/time-slots", kermesse_id);
+                if let Ok(resp) = Request::get(&url).send().await {
+                    if let Ok(data) = resp.json::<Vec<TimeSlot>>().await {
+                        slots.set(data);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_create = {
+        let token = user_ctx.user.as_ref().map(|u| u.token.clone());
+        let refresh_trigger = refresh_trigger.clone();
+        let type_ref = type_ref.clone();
+        let start_ref = start_ref.clone();
+        let end_ref = end_ref.clone();
+        let capacity_ref = capacity_ref.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let request = CreateTimeSlotRequest {
+                slot_type: type_ref.cast::<HtmlSelectElement>().unwrap().value(),
+                starts_at: start_ref.cast::<HtmlInputElement>().unwrap().value(),
+                ends_at: end_ref.cast::<HtmlInputElement>().unwrap().value(),
+                capacity: capacity_ref.cast::<HtmlInputElement>().unwrap().value().parse().unwrap_or(0),
+            };
+            let token = token.clone();
+            let refresh_trigger = refresh_trigger.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(token) = token {
+                    let body = serde_json::to_string(&request).unwrap();
+                    let resp = Request::post(&format!("http://127.0.0.1:8080/kermesses/{}/time-slots", kermesse_id))
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    match resp {
+                        Ok(resp) if resp.ok() => refresh_trigger.set(*refresh_trigger + 1),
+                        Ok(resp) => gloo_dialogs::alert(&resp.text().await.unwrap_or_default()),
+                        Err(_) => gloo_dialogs::alert("Error de conexión."),
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="space-y-6">
+            <form onsubmit={on_create} class="grid grid-cols-2 md:grid-cols-5 gap-3 items-end bg-gray-50 p-4 rounded-lg">
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Tipo" }</label>
+                    <select ref={type_ref} class="w-full rounded-lg border-gray-300 p-2 border">
+                        <option value="PICKUP">{ "Recojo" }</option>
+                        <option value="DELIVERY">{ "Delivery" }</option>
+                    </select>
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Desde" }</label>
+                    <input ref={start_ref} type="time" required=true class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Hasta" }</label>
+                    <input ref={end_ref} type="time" required=true class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Cupo (pedidos)" }</label>
+                    <input ref={capacity_ref} type="number" min="1" required=true class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <button type="submit" class="bg-primary text-white font-bold py-2 rounded-lg hover:bg-red-600 transition">{ "Agregar Horario" }</button>
+            </form>
+
+            if slots.is_empty() {
+                <div class="text-center py-8 text-gray-500 bg-white rounded-lg border border-dashed border-gray-300">
+                    { "No hay horarios definidos. Los pedidos no tendrán hora de recojo." }
+                </div>
+            } else {
+                <div class="grid grid-cols-1 md:grid-cols-3 gap-3">
+                    {
+                        slots.iter().map(|slot| html! {
+                            <div class="p-4 bg-white rounded-lg border border-gray-100 shadow-sm">
+                                <p class="font-bold text-gray-800">{ format!("{} - {}", slot.starts_at, slot.ends_at) }</p>
+                                <p class="text-xs text-gray-500">{ if slot.slot_type == "DELIVERY" { "Delivery" } else { "Recojo" } }</p>
+                                <p class="text-sm mt-2">{ format!("{} / {} pedidos", slot.capacity - slot.remaining, slot.capacity) }</p>
+                            </div>
+                        }).collect::<Html>()
+                    }
+                </div>
+            }
+        </div>
+    }
+}
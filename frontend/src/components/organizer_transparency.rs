@@ -0,0 +1,274 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use reqwasm::http::Request;
+use wasm_bindgen::JsValue;
+use web_sys::HtmlInputElement;
+use crate::context::UserContext;
+use crate::router::Route;
+use crate::pages::transparency::{ProofLink, TransparencyReport};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(OrganizerTransparency)]
+pub fn organizer_transparency(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let report = use_state(|| None::<TransparencyReport>);
+    let kermesse_id = props.kermesse_id;
+    let refresh_trigger = use_state(|| 0);
+    let uploading = use_state(|| false);
+    let token = user_ctx.user.as_ref().map(|u| u.token.clone());
+
+    let amount_ref = use_node_ref();
+    let date_ref = use_node_ref();
+    let note_ref = use_node_ref();
+    let file_ref = use_node_ref();
+
+    {
+        let report = report.clone();
+        let token = token.clone();
+        use_effect_with(refresh_trigger.clone(), move |_| {
+            if let Some(token) = token {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let url = format!("http://127.0.0.1:8080/kermesses/{}/transparency", kermesse_id);
+                    if let Ok(resp) = Request::get(&url)
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await
+                    {
+                        if let Ok(data) = resp.json::<TransparencyReport>().await {
+                            report.set(Some(data));
+                        }
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_toggle = {
+        let token = token.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        let published = report.as_ref().map(|r| r.published).unwrap_or(false);
+        Callback::from(move |_| {
+            let token = token.clone();
+            let refresh_trigger = refresh_trigger.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(token) = token {
+                    let body = serde_json::json!({ "transparency_enabled": !published }).to_string();
+                    let resp = Request::put(&format!("http://127.0.0.1:8080/kermesses/{}", kermesse_id))
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    match resp {
+                        Ok(resp) if resp.ok() => refresh_trigger.set(*refresh_trigger + 1),
+                        _ => gloo_dialogs::alert("No se pudo actualizar la publicación."),
+                    }
+                }
+            });
+        })
+    };
+
+    // The file goes as the raw request body; the other fields in the query string
+    let on_upload = {
+        let token = token.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        let uploading = uploading.clone();
+        let amount_ref = amount_ref.clone();
+        let date_ref = date_ref.clone();
+        let note_ref = note_ref.clone();
+        let file_ref = file_ref.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let file_input = file_ref.cast::<HtmlInputElement>().unwrap();
+            let Some(file) = file_input.files().and_then(|files| files.get(0)) else {
+                gloo_dialogs::alert("Selecciona el comprobante (foto o PDF).");
+                return;
+            };
+
+            let params = web_sys::UrlSearchParams::new().unwrap();
+            params.append("amount", &amount_ref.cast::<HtmlInputElement>().unwrap().value());
+            params.append("file_name", &file.name());
+            let date = date_ref.cast::<HtmlInputElement>().unwrap().value();
+            if !date.is_empty() {
+                params.append("delivered_on", &date);
+            }
+            let note = note_ref.cast::<HtmlInputElement>().unwrap().value();
+            if !note.is_empty() {
+                params.append("note", &note);
+            }
+            let url = format!(
+                "http://127.0.0.1:8080/kermesses/{}/deliveries?{}",
+                kermesse_id,
+                String::from(params.to_string())
+            );
+
+            let token = token.clone();
+            let refresh_trigger = refresh_trigger.clone();
+            let uploading = uploading.clone();
+            let amount_ref = amount_ref.clone();
+            let note_ref = note_ref.clone();
+            uploading.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(token) = token {
+                    let content_type = file.type_();
+                    let resp = Request::post(&url)
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .header("Content-Type", &content_type)
+                        .body(JsValue::from(file))
+                        .send()
+                        .await;
+                    match resp {
+                        Ok(resp) if resp.ok() => {
+                            amount_ref.cast::<HtmlInputElement>().unwrap().set_value("");
+                            note_ref.cast::<HtmlInputElement>().unwrap().set_value("");
+                            file_input.set_value("");
+                            refresh_trigger.set(*refresh_trigger + 1);
+                        }
+                        Ok(resp) if resp.status() == 413 => gloo_dialogs::alert("El archivo supera los 5 MB."),
+                        Ok(resp) => gloo_dialogs::alert(&resp.text().await.unwrap_or_default()),
+                        Err(_) => gloo_dialogs::alert("Error de conexión."),
+                    }
+                }
+                uploading.set(false);
+            });
+        })
+    };
+
+    let on_remove = {
+        let token = token.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        Callback::from(move |delivery_id: i32| {
+            if !gloo_dialogs::confirm("¿Eliminar esta entrega y su comprobante?") {
+                return;
+            }
+            let token = token.clone();
+            let refresh_trigger = refresh_trigger.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(token) = token {
+                    let resp = Request::delete(&format!("http://127.0.0.1:8080/deliveries/{}", delivery_id))
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await;
+                    match resp {
+                        Ok(resp) if resp.ok() => refresh_trigger.set(*refresh_trigger + 1),
+                        _ => gloo_dialogs::alert("No se pudo eliminar la entrega."),
+                    }
+                }
+            });
+        })
+    };
+
+    let Some(data) = &*report else {
+        return html! { <div class="text-center py-10 text-gray-400 animate-pulse">{ "Cargando..." }</div> };
+    };
+    // Hidden pages only serve their proofs to the organizer's token
+    let proof_token = if data.published { None } else { token.clone() };
+
+    html! {
+        <div class="space-y-6">
+            <div class="flex flex-wrap items-center justify-between gap-4 bg-gray-50 p-4 rounded-lg">
+                <div>
+                    <p class="font-bold text-gray-800">
+                        { if data.published { "🟢 Página de transparencia pública" } else { "⚪ Página de transparencia oculta" } }
+                    </p>
+                    <p class="text-sm text-gray-500">{ "Muestra a donantes lo recaudado, los gastos y las entregas al beneficiario con sus comprobantes." }</p>
+                </div>
+                <div class="flex gap-2">
+                    <Link<Route> to={Route::Transparency { id: kermesse_id }} classes="px-4 py-2 bg-white border rounded-lg text-sm font-bold text-gray-700 hover:bg-gray-100">
+                        { "Ver página" }
+                    </Link<Route>>
+                    <button onclick={on_toggle} class={format!("px-4 py-2 rounded-lg text-sm font-bold text-white {}", if data.published { "bg-gray-500 hover:bg-gray-600" } else { "bg-green-600 hover:bg-green-700" })}>
+                        { if data.published { "Ocultar" } else { "Publicar" } }
+                    </button>
+                </div>
+            </div>
+
+            <div class="grid grid-cols-2 md:grid-cols-4 gap-4 text-sm">
+                <div class="bg-white rounded-lg p-3 border">
+                    <p class="text-gray-500">{ "Recaudado" }</p>
+                    <p class="font-bold text-green-700">{ format!("Bs. {:.2}", data.total_raised) }</p>
+                </div>
+                <div class="bg-white rounded-lg p-3 border">
+                    <p class="text-gray-500">{ "Gastos" }</p>
+                    <p class="font-bold text-red-600">{ format!("Bs. {:.2}", data.expenses_total) }</p>
+                </div>
+                <div class="bg-white rounded-lg p-3 border">
+                    <p class="text-gray-500">{ "Entregado" }</p>
+                    <p class="font-bold text-purple-700">{ format!("Bs. {:.2}", data.delivered_total) }</p>
+                </div>
+                <div class="bg-white rounded-lg p-3 border">
+                    <p class="text-gray-500">{ "Pendiente de entrega" }</p>
+                    <p class="font-bold text-gray-800">{ format!("Bs. {:.2}", data.pending_delivery) }</p>
+                </div>
+            </div>
+
+            <form onsubmit={on_upload} class="grid grid-cols-2 md:grid-cols-4 gap-3 items-end bg-gray-50 p-4 rounded-lg">
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Monto entregado (Bs.)" }</label>
+                    <input ref={amount_ref} type="number" min="0.01" step="0.01" required=true class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <div>
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Fecha" }</label>
+                    <input ref={date_ref} type="date" class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <div class="col-span-2">
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Nota" }</label>
+                    <input ref={note_ref} type="text" placeholder="Depósito a la cuenta de la familia" class="w-full rounded-lg border-gray-300 p-2 border" />
+                </div>
+                <div class="col-span-2 md:col-span-3">
+                    <label class="block text-xs font-medium text-gray-600 mb-1">{ "Comprobante (JPG, PNG, WebP o PDF, máx. 5 MB)" }</label>
+                    <input ref={file_ref} type="file" accept="image/jpeg,image/png,image/webp,application/pdf" required=true class="w-full text-sm" />
+                </div>
+                <button type="submit" disabled={*uploading} class="col-span-2 md:col-span-1 bg-primary text-white font-bold py-2 rounded-lg hover:bg-red-600 transition disabled:opacity-50">
+                    { if *uploading { "Subiendo..." } else { "Registrar Entrega" } }
+                </button>
+            </form>
+
+            if data.deliveries.is_empty() {
+                <div class="text-center py-8 text-gray-500 bg-white rounded-lg border border-dashed border-gray-300">
+                    { "Aún no se registraron entregas al beneficiario." }
+                </div>
+            } else {
+                <div class="bg-white rounded-lg shadow overflow-hidden">
+                    <table class="w-full text-left text-sm">
+                        <thead class="bg-gray-50 text-gray-600 border-b">
+                            <tr>
+                                <th class="p-3 font-semibold">{ "Fecha" }</th>
+                                <th class="p-3 font-semibold">{ "Nota" }</th>
+                                <th class="p-3 font-semibold">{ "Comprobante" }</th>
+                                <th class="p-3 font-semibold text-right">{ "Monto" }</th>
+                                <th class="p-3"></th>
+                            </tr>
+                        </thead>
+                        <tbody class="divide-y divide-gray-100">
+                            {
+                                data.deliveries.iter().map(|d| {
+                                    let id = d.id;
+                                    let on_remove = on_remove.clone();
+                                    html! {
+                                        <tr>
+                                            <td class="p-3 text-gray-500">{ &d.delivered_on }</td>
+                                            <td class="p-3">{ d.note.clone().unwrap_or_default() }</td>
+                                            <td class="p-3">
+                                                <ProofLink delivery_id={d.id} file_name={d.proof_file_name.clone()} token={proof_token.clone()} class="text-primary hover:underline">{ &d.proof_file_name }</ProofLink>
+                                            </td>
+                                            <td class="p-3 text-right font-bold">{ format!("Bs. {:.2}", d.amount) }</td>
+                                            <td class="p-3 text-center">
+                                                <button onclick={Callback::from(move |_| on_remove.emit(id))} class="text-red-500 hover:text-red-700" title="Eliminar entrega">{ "✕" }</button>
+                                            </td>
+                                        </tr>
+                                    }
+                                }).collect::<Html>()
+                            }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        </div>
+    }
+}
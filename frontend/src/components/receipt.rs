@@ -1,11 +1,19 @@
 use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::router::Route;
+use crate::context::UserContext;
 use serde::{Deserialize, Serialize};
+use qrcode::{render::svg, QrCode};
+use crate::components::organizer_dashboard::deserialize_price;
+use crate::components::file_download::download;
 
 #[derive(Clone, PartialEq, Deserialize, Serialize, Debug)]
 pub struct SaleItemReceipt {
     pub dish_name: String,
     pub quantity: i32,
+    #[serde(deserialize_with = "deserialize_price")]
     pub unit_price: f64,
+    #[serde(deserialize_with = "deserialize_price")]
     pub subtotal: f64,
 }
 
@@ -15,14 +23,44 @@ pub struct SaleReceipt {
     pub kermesse_name: String,
     pub event_date: String,
     pub customer_name: String,
+    #[serde(default, deserialize_with = "deserialize_price")]
+    pub subtotal: f64,
+    #[serde(default)]
+    pub promo_code: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_price")]
+    pub discount_amount: f64,
+    #[serde(default)]
+    pub delivery_zone: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_price")]
+    pub delivery_fee: f64,
+    #[serde(default, deserialize_with = "deserialize_price")]
+    pub extra_donation: f64,
+    #[serde(deserialize_with = "deserialize_price")]
     pub total_amount: f64,
     pub status: String,
     pub payment_method: String,
     pub delivery_method: String,
     pub created_at: String,
+    #[serde(default)]
+    pub time_slot: Option<String>,
+    #[serde(default)]
+    pub pickup_code: Option<String>,
+    #[serde(default)]
+    pub tracking_token: Option<String>,
     pub items: Vec<SaleItemReceipt>,
 }
 
+/// QR of the pickup code as inline SVG, for the staff to scan at the stand.
+fn pickup_qr(code: &str) -> Html {
+    match QrCode::new(code.as_bytes()) {
+        Ok(qr) => {
+            let image = qr.render::<svg::Color>().min_dimensions(160, 160).build();
+            Html::from_html_unchecked(AttrValue::from(image))
+        }
+        Err(_) => html! {},
+    }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct Props {
     pub receipt: SaleReceipt,
@@ -31,10 +69,27 @@ pub struct Props {
 
 #[function_component(Receipt)]
 pub fn receipt(props: &Props) -> Html {
-    let on_print = Callback::from(|_| {
-        let window = web_sys::window().unwrap();
-        window.print().unwrap();
-    });
+    // Guests open the PDF with their tracking token; signed-in users download it with their session
+    let user_token = use_context::<UserContext>().and_then(|ctx| ctx.user.map(|u| u.token));
+    let pdf_url = format!("http://127.0.0.1:8080/sales/{}/receipt.pdf", props.receipt.id);
+    let pdf_class = "flex-1 bg-blue-600 text-white font-bold py-3 rounded-lg hover:bg-blue-700 transition shadow-md flex items-center justify-center gap-2";
+    let pdf_button = match (&props.receipt.tracking_token, user_token) {
+        (Some(tracking_token), _) => html! {
+            <a href={format!("{}?token={}", pdf_url, tracking_token)} target="_blank" class={pdf_class}>
+                <span>{"📄"}</span> { "Descargar PDF" }
+            </a>
+        },
+        (None, Some(token)) => {
+            let file_name = format!("pedido-{}.pdf", props.receipt.id);
+            let onclick = Callback::from(move |_| download(pdf_url.clone(), token.clone(), file_name.clone()));
+            html! {
+                <button {onclick} class={pdf_class}>
+                    <span>{"📄"}</span> { "Descargar PDF" }
+                </button>
+            }
+        }
+        (None, None) => html! {},
+    };
 
     html! {
         <div class="bg-white p-8 rounded-xl shadow-lg max-w-2xl mx-auto border border-gray-200" id="printable-receipt">
@@ -85,11 +140,43 @@ pub fn receipt(props: &Props) -> Html {
 
             <div class="flex justify-end border-t pt-4 mb-8">
                 <div class="text-right">
+                    if props.receipt.discount_amount > 0.0 || props.receipt.delivery_fee > 0.0 || props.receipt.extra_donation > 0.0 {
+                        <div class="text-sm text-gray-600 mb-3 space-y-1">
+                            <p>{ format!("Subtotal: Bs. {:.2}", props.receipt.subtotal) }</p>
+                            if props.receipt.discount_amount > 0.0 {
+                                <p class="text-green-700">{ format!("Descuento {}: - Bs. {:.2}", props.receipt.promo_code.clone().unwrap_or_default(), props.receipt.discount_amount) }</p>
+                            }
+                            if props.receipt.delivery_fee > 0.0 {
+                                <p>{ format!("Envío {}: Bs. {:.2}", props.receipt.delivery_zone.clone().unwrap_or_default(), props.receipt.delivery_fee) }</p>
+                            }
+                            if props.receipt.extra_donation > 0.0 {
+                                <p class="text-purple-700">{ format!("Donación extra: Bs. {:.2}", props.receipt.extra_donation) }</p>
+                            }
+                        </div>
+                    }
                     <p class="text-sm text-gray-500 mb-1">{ "Total a Pagar:" }</p>
                     <p class="text-3xl font-bold text-primary">{ format!("Bs. {:.2}", props.receipt.total_amount) }</p>
                 </div>
             </div>
             
+            if let Some(code) = &props.receipt.pickup_code {
+                <div class="flex flex-col items-center mb-8">
+                    <p class="text-sm font-bold text-gray-600 mb-2">{ "Código de Recojo" }</p>
+                    { pickup_qr(code) }
+                    <p class="mt-2 text-2xl font-mono font-bold tracking-widest text-gray-900">{ code }</p>
+                    <p class="text-xs text-gray-500">{ "Muestra este código en el puesto para recoger tu pedido" }</p>
+                </div>
+            }
+
+            if let Some(token) = &props.receipt.tracking_token {
+                <div class="text-center mb-8 print:hidden">
+                    <Link<Route> to={Route::OrderTracking { token: token.clone() }} classes="inline-block bg-gray-800 text-white font-bold px-6 py-3 rounded-lg hover:bg-gray-700 transition">
+                        { "📍 Seguir mi pedido" }
+                    </Link<Route>>
+                    <p class="text-xs text-gray-500 mt-2">{ "Guarda este enlace: con él puedes ver el estado de tu pedido sin iniciar sesión." }</p>
+                </div>
+            }
+
             <div class="bg-yellow-50 p-4 rounded-lg mb-8 text-sm text-yellow-800 border-l-4 border-yellow-400">
                 <p class="font-bold">{ match props.receipt.payment_method.as_str() {
                     "QR" => "Pago QR - PENDIENTE",
@@ -102,15 +189,16 @@ pub fn receipt(props: &Props) -> Html {
                     "EAT_HERE" => "Método de entrega: Comer en el evento",
                     _ => ""
                 } }</p>
+                if let Some(slot) = &props.receipt.time_slot {
+                    <p>{ format!("Horario: {}", slot) }</p>
+                }
             </div>
 
             <div class="flex gap-4 print:hidden">
                 <button onclick={let on_close = props.on_close.clone(); move |_| on_close.emit(())} class="flex-1 bg-gray-100 text-gray-800 font-bold py-3 rounded-lg hover:bg-gray-200 transition">
                     { "Cerrar / Ir al Inicio" }
                 </button>
-                <button onclick={on_print} class="flex-1 bg-blue-600 text-white font-bold py-3 rounded-lg hover:bg-blue-700 transition shadow-md flex items-center justify-center gap-2">
-                    <span>{"🖨️"}</span> { "Imprimir Comprobante" }
-                </button>
+                { pdf_button }
             </div>
         </div>
     }
@@ -35,6 +35,7 @@ pub fn user_context_provider(props: &UserContextProviderProps) -> Html {
                 let _ = LocalStorage::set("user", u);
             } else {
                 let _ = LocalStorage::delete("user");
+                crate::components::claim_orders::forget_guest_orders();
             }
             user_state.set(user);
         })
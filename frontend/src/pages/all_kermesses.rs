@@ -5,6 +5,7 @@ use web_sys::HtmlInputElement;
 use serde::Deserialize;
 use crate::router::Route;
 use crate::pages::home::Kermesse;
+use crate::components::fundraising_progress::FundraisingProgressSummary;
 
 #[function_component(AllKermesses)]
 pub fn all_kermesses() -> Html {
@@ -167,6 +168,11 @@ pub fn all_kermesses() -> Html {
                                             </div>
                                         }
                                         <p class="text-gray-600 mb-4 line-clamp-2 text-sm flex-grow">{ &k.description }</p>
+                                        <FundraisingProgressSummary
+                                            total_raised={k.total_raised}
+                                            financial_goal={k.financial_goal.as_deref().and_then(|g| g.parse::<f64>().ok())}
+                                            progress_percentage={k.progress_percentage}
+                                        />
                                         <div class="pt-4 border-t border-gray-100 mt-auto">
                                             <button onclick={on_click_detail(id)} class="w-full bg-gray-50 text-gray-800 font-bold py-3 rounded-xl hover:bg-orange-50 hover:text-orange-600 transition-colors flex items-center justify-center gap-2">
                                                 { "Colaborar →" }
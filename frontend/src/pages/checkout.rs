@@ -2,8 +2,13 @@ use yew::prelude::*;
 use yew_router::prelude::*;
 use crate::context::{UserContext, CartContext, CartAction};
 use crate::router::Route;
+use crate::components::organizer_time_slots::TimeSlot;
+use crate::components::organizer_delivery_zones::DeliveryZone;
+use crate::pages::pos::new_client_key;
+use crate::components::receipt::{Receipt, SaleReceipt};
+use crate::components::claim_orders::remember_guest_order;
 use reqwasm::http::Request;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use gloo_console::log;
 
 #[derive(Serialize)]
@@ -13,8 +18,29 @@ struct CreateSaleRequest {
     items: Vec<SaleItemRequest>,
     delivery_method: String,
     delivery_address: Option<String>,
+    delivery_neighborhood: Option<String>,
+    delivery_city: Option<String>,
     contact_phone: Option<String>,
     payment_method: String,
+    promo_code: Option<String>,
+    extra_donation: Option<f64>,
+    time_slot_id: Option<i32>,
+    delivery_zone_id: Option<i32>,
+}
+
+#[derive(Deserialize, Clone, PartialEq)]
+struct PromoCodeValidation {
+    code: String,
+    discount_type: String,
+    #[serde(deserialize_with = "crate::components::organizer_dashboard::deserialize_price")]
+    value: f64,
+}
+
+impl PromoCodeValidation {
+    fn discount_for(&self, subtotal: f64) -> f64 {
+        let discount = if self.discount_type == "PERCENTAGE" { subtotal * self.value / 100.0 } else { self.value };
+        discount.min(subtotal)
+    }
 }
 
 #[derive(Serialize)]
@@ -32,10 +58,69 @@ pub fn checkout() -> Html {
     let name_ref = use_node_ref();
     let phone_ref = use_node_ref();
     let address_ref = use_node_ref();
+    let neighborhood_ref = use_node_ref();
+    let city_ref = use_node_ref();
 
     let delivery_method = use_state(|| "PICKUP".to_string());
     let payment_method = use_state(|| "QR".to_string());
     let is_submitting = use_state(|| false);
+    // Idempotency-Key of the last attempt and the body it was sent with
+    let last_attempt = use_mut_ref(|| None::<(String, String)>);
+    let promo_ref = use_node_ref();
+    let promo = use_state(|| None::<PromoCodeValidation>);
+    let extra_donation = use_state(|| 0.0_f64);
+    let time_slots = use_state(Vec::<TimeSlot>::new);
+    let selected_slot = use_state(|| None::<i32>);
+    let delivery_zones = use_state(Vec::<DeliveryZone>::new);
+    let zones_configured = use_state(|| false);
+    let delivery_enabled = use_state(|| false);
+    let selected_zone = use_state(|| None::<i32>);
+    let placed_receipt = use_state(|| None::<SaleReceipt>);
+
+    {
+        let time_slots = time_slots.clone();
+        let delivery_zones = delivery_zones.clone();
+        let zones_configured = zones_configured.clone();
+        let delivery_enabled = delivery_enabled.clone();
+        let kermesse_id = cart_ctx.state.items.first().map(|i| i.kermesse_id);
+        use_effect_with(kermesse_id, move |kermesse_id| {
+            if let Some(id) = *kermesse_id {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let url = format!("http://127.0.0.1:8080/kermesses/{}", id);
+                    if let Ok(resp) = Request::get(&url).send().await {
+                        if let Ok(data) = resp.json::<serde_json::Value>().await {
+                            delivery_enabled.set(data["delivery_enabled"].as_bool().unwrap_or(false));
+                        }
+                    }
+                    let url = format!("http://127.0.0.1:8080/kermesses/{}/time-slots", id);
+                    if let Ok(resp) = Request::get(&url).send().await {
+                        if let Ok(data) = resp.json::<Vec<TimeSlot>>().await {
+                            time_slots.set(data);
+                        }
+                    }
+                    // Kermesses offering delivery without zones deliver anywhere, without a fee
+                    let url = format!("http://127.0.0.1:8080/kermesses/{}/delivery-zones?include_inactive=true", id);
+                    if let Ok(resp) = Request::get(&url).send().await {
+                        if let Ok(data) = resp.json::<Vec<DeliveryZone>>().await {
+                            zones_configured.set(!data.is_empty());
+                            delivery_zones.set(data.into_iter().filter(|z| z.active).collect());
+                        }
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    // The cart is already cleared once the order is placed
+    if let Some(receipt) = (*placed_receipt).clone() {
+        let navigator = navigator.clone();
+        return html! {
+            <div class="min-h-screen bg-gray-50 py-12 px-4">
+                <Receipt receipt={receipt} on_close={Callback::from(move |_| navigator.push(&Route::Home))} />
+            </div>
+        };
+    }
 
     if cart_ctx.state.items.is_empty() {
         return html! {
@@ -48,6 +133,52 @@ pub fn checkout() -> Html {
         };
     }
 
+    let subtotal = cart_ctx.state.total();
+    let discount = promo.as_ref().map(|p| p.discount_for(subtotal)).unwrap_or(0.0);
+    let delivery_fee = if *delivery_method == "DELIVERY" {
+        selected_zone.and_then(|id| delivery_zones.iter().find(|z| z.id == id)).map(|z| z.fee).unwrap_or(0.0)
+    } else {
+        0.0
+    };
+    let offers_delivery = *delivery_enabled && (!*zones_configured || !delivery_zones.is_empty());
+    let grand_total = subtotal - discount + delivery_fee + *extra_donation;
+
+    let on_apply_promo = {
+        let promo_ref = promo_ref.clone();
+        let promo = promo.clone();
+        let kermesse_id = cart_ctx.state.items[0].kermesse_id;
+        Callback::from(move |_| {
+            let code = promo_ref.cast::<web_sys::HtmlInputElement>().unwrap().value();
+            if code.trim().is_empty() {
+                promo.set(None);
+                return;
+            }
+            let promo = promo.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let url = format!("http://127.0.0.1:8080/kermesses/{}/promo-codes/validate?code={}", kermesse_id, code.trim());
+                match Request::get(&url).send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(validation) = resp.json::<PromoCodeValidation>().await {
+                            promo.set(Some(validation));
+                        }
+                    }
+                    _ => {
+                        promo.set(None);
+                        gloo_dialogs::alert("Código inválido o expirado.");
+                    }
+                }
+            });
+        })
+    };
+
+    let on_donation_input = {
+        let extra_donation = extra_donation.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            extra_donation.set(input.value().parse::<f64>().unwrap_or(0.0).max(0.0));
+        })
+    };
+
     let on_submit = {
         let cart_ctx = cart_ctx.clone();
         let user_ctx = user_ctx.clone();
@@ -55,12 +186,28 @@ pub fn checkout() -> Html {
         let name_ref = name_ref.clone();
         let phone_ref = phone_ref.clone();
         let address_ref = address_ref.clone();
+        let neighborhood_ref = neighborhood_ref.clone();
+        let city_ref = city_ref.clone();
         let delivery_method = delivery_method.clone();
         let payment_method = payment_method.clone();
         let is_submitting = is_submitting.clone();
+        let promo = promo.clone();
+        let extra_donation = extra_donation.clone();
+        let selected_slot = selected_slot.clone();
+        let selected_zone = selected_zone.clone();
+        let zones_configured = zones_configured.clone();
+        let last_attempt = last_attempt.clone();
+        let placed_receipt = placed_receipt.clone();
 
         Callback::from(move |e: SubmitEvent| {
             e.prevent_default();
+            if *is_submitting {
+                return;
+            }
+            if *delivery_method == "DELIVERY" && *zones_configured && selected_zone.is_none() {
+                gloo_dialogs::alert("Selecciona tu zona de entrega.");
+                return;
+            }
             is_submitting.set(true);
 
             let name = name_ref.cast::<web_sys::HtmlInputElement>().unwrap().value();
@@ -70,6 +217,14 @@ pub fn checkout() -> Html {
             } else {
                 None
             };
+            let (neighborhood, city) = if *delivery_method == "DELIVERY" {
+                (
+                    Some(neighborhood_ref.cast::<web_sys::HtmlInputElement>().unwrap().value()),
+                    Some(city_ref.cast::<web_sys::HtmlInputElement>().unwrap().value()),
+                )
+            } else {
+                (None, None)
+            };
             
             let items: Vec<SaleItemRequest> = cart_ctx.state.items.iter().map(|i| SaleItemRequest {
                 dish_id: i.dish_id,
@@ -84,19 +239,40 @@ pub fn checkout() -> Html {
                 items,
                 delivery_method: (*delivery_method).clone(),
                 delivery_address: address,
+                delivery_neighborhood: neighborhood,
+                delivery_city: city,
                 contact_phone: Some(phone),
                 payment_method: (*payment_method).clone(),
+                promo_code: promo.as_ref().map(|p| p.code.clone()),
+                extra_donation: if *extra_donation > 0.0 { Some(*extra_donation) } else { None },
+                time_slot_id: *selected_slot,
+                delivery_zone_id: if *delivery_method == "DELIVERY" { *selected_zone } else { None },
             };
 
             let cart_ctx = cart_ctx.clone();
             let user_ctx = user_ctx.clone();
             let navigator = navigator.clone();
             let is_submitting = is_submitting.clone();
+            let placed_receipt = placed_receipt.clone();
+
+            // Retrying the same order reuses its key so it is never placed twice
+            let body = serde_json::to_string(&request).unwrap();
+            let idempotency_key = {
+                let mut last = last_attempt.borrow_mut();
+                match last.as_ref() {
+                    Some((key, sent)) if *sent == body => key.clone(),
+                    _ => {
+                        let key = new_client_key();
+                        *last = Some((key.clone(), body.clone()));
+                        key
+                    }
+                }
+            };
 
             wasm_bindgen_futures::spawn_local(async move {
-                let body = serde_json::to_string(&request).unwrap();
                 let mut req = Request::post("http://127.0.0.1:8080/sales")
                     .header("Content-Type", "application/json")
+                    .header("Idempotency-Key", &idempotency_key)
                     .body(body);
 
                 if let Some(user) = &user_ctx.user {
@@ -106,11 +282,23 @@ pub fn checkout() -> Html {
                 match req.send().await {
                    Ok(resp) => {
                        if resp.ok() {
-                           gloo_dialogs::alert("¡Pedido Realizado con Éxito!");
                            cart_ctx.dispatch.emit(CartAction::Clear);
-                           navigator.push(&Route::Home); // Or Order Success Page
+                           match resp.json::<SaleReceipt>().await {
+                               Ok(receipt) => {
+                                   if user_ctx.user.is_none() {
+                                       if let Some(token) = &receipt.tracking_token {
+                                           remember_guest_order(token);
+                                       }
+                                   }
+                                   placed_receipt.set(Some(receipt));
+                               }
+                               Err(_) => {
+                                   gloo_dialogs::alert("¡Pedido Realizado con Éxito!");
+                                   navigator.push(&Route::Home);
+                               }
+                           }
                        } else {
-                           gloo_dialogs::alert("Error al procesar el pedido.");
+                           gloo_dialogs::alert(&format!("Error al procesar el pedido: {}", resp.text().await.unwrap_or_default()));
                        }
                    },
                    Err(_) => gloo_dialogs::alert("Error de conexión."),
@@ -162,21 +350,94 @@ pub fn checkout() -> Html {
                                 <div class="text-2xl mb-2">{"🍽️"}</div>
                                 { "Comer Aquí" }
                             </button>
-                            <button type="button" 
+                            <button type="button" disabled={!offers_delivery}
                                 onclick={let dm = delivery_method.clone(); Callback::from(move |_| dm.set("DELIVERY".to_string()))}
-                                class={format!("p-4 rounded-xl border-2 text-center transition {}", if *delivery_method == "DELIVERY" { "border-primary bg-red-50 text-primary font-bold" } else { "border-gray-200 text-gray-500 hover:border-gray-300" })}
+                                class={format!("p-4 rounded-xl border-2 text-center transition disabled:opacity-40 disabled:cursor-not-allowed {}", if *delivery_method == "DELIVERY" { "border-primary bg-red-50 text-primary font-bold" } else { "border-gray-200 text-gray-500 hover:border-gray-300" })}
                             >
                                 <div class="text-2xl mb-2">{"🛵"}</div>
                                 { "Delivery" }
+                                if !offers_delivery {
+                                    <div class="text-xs">{ "No disponible" }</div>
+                                }
                             </button>
                         </div>
 
                         if *delivery_method == "DELIVERY" {
                             <div class="animate-fade-in-down">
+                                if *zones_configured {
+                                    <label class="block text-sm font-medium text-gray-700 mb-2">{ "Zona de Entrega" }</label>
+                                    <div class="grid grid-cols-2 md:grid-cols-3 gap-2 mb-4">
+                                        {
+                                            delivery_zones.iter().map(|zone| {
+                                                let id = zone.id;
+                                                let below_min = zone.min_order.map(|m| subtotal - discount < m).unwrap_or(false);
+                                                let selected = *selected_zone == Some(id);
+                                                let sz = selected_zone.clone();
+                                                html! {
+                                                    <button type="button" disabled={below_min}
+                                                        onclick={Callback::from(move |_| sz.set(Some(id)))}
+                                                        class={format!("p-2 rounded-lg border-2 text-sm transition disabled:opacity-40 {}", if selected { "border-primary bg-red-50 text-primary font-bold" } else { "border-gray-200 text-gray-600 hover:border-gray-300" })}
+                                                    >
+                                                        <div>{ &zone.name }</div>
+                                                        <div class="text-xs">{ format!("Envío Bs. {:.2}", zone.fee) }</div>
+                                                        if let Some(min) = zone.min_order {
+                                                            <div class="text-xs text-gray-400">{ format!("Pedido mínimo Bs. {:.2}", min) }</div>
+                                                        }
+                                                    </button>
+                                                }
+                                            }).collect::<Html>()
+                                        }
+                                    </div>
+                                }
                                 <label class="block text-sm font-medium text-gray-700 mb-1">{ "Dirección de Entrega" }</label>
-                                <textarea ref={address_ref} required=true rows="2" class="w-full rounded-lg border-gray-300 shadow-sm focus:ring-primary focus:border-primary p-2 border" placeholder="Barrio X, Calle Y #123 (Referencia: Frente a la plaza)"></textarea>
+                                <textarea ref={address_ref} required=true rows="2" class="w-full rounded-lg border-gray-300 shadow-sm focus:ring-primary focus:border-primary p-2 border" placeholder="Calle Y #123 (Referencia: Frente a la plaza)"></textarea>
+                                <div class="grid grid-cols-2 gap-3 mt-3">
+                                    <div>
+                                        <label class="block text-sm font-medium text-gray-700 mb-1">{ "Barrio / Zona" }</label>
+                                        <input ref={neighborhood_ref} type="text" required=true class="w-full rounded-lg border-gray-300 shadow-sm focus:ring-primary focus:border-primary p-2 border" placeholder="Barrio X" />
+                                    </div>
+                                    <div>
+                                        <label class="block text-sm font-medium text-gray-700 mb-1">{ "Ciudad" }</label>
+                                        <input ref={city_ref} type="text" required=true class="w-full rounded-lg border-gray-300 shadow-sm focus:ring-primary focus:border-primary p-2 border" placeholder="Cochabamba" />
+                                    </div>
+                                </div>
                             </div>
                         }
+
+                        {
+                            {
+                                let wanted = if *delivery_method == "DELIVERY" { "DELIVERY" } else { "PICKUP" };
+                                let available: Vec<&TimeSlot> = time_slots.iter().filter(|s| s.slot_type == wanted).collect();
+                                if available.is_empty() {
+                                    html! {}
+                                } else {
+                                    html! {
+                                        <div class="mt-4">
+                                            <label class="block text-sm font-medium text-gray-700 mb-2">{ "Horario" }</label>
+                                            <div class="grid grid-cols-2 md:grid-cols-4 gap-2">
+                                                {
+                                                    available.into_iter().map(|slot| {
+                                                        let id = slot.id;
+                                                        let full = slot.remaining <= 0;
+                                                        let selected = *selected_slot == Some(id);
+                                                        let ss = selected_slot.clone();
+                                                        html! {
+                                                            <button type="button" disabled={full}
+                                                                onclick={Callback::from(move |_| ss.set(Some(id)))}
+                                                                class={format!("p-2 rounded-lg border-2 text-sm transition disabled:opacity-40 {}", if selected { "border-primary bg-red-50 text-primary font-bold" } else { "border-gray-200 text-gray-600 hover:border-gray-300" })}
+                                                            >
+                                                                <div>{ format!("{} - {}", slot.starts_at, slot.ends_at) }</div>
+                                                                <div class="text-xs">{ if full { "Lleno".to_string() } else { format!("{} cupos", slot.remaining) } }</div>
+                                                            </button>
+                                                        }
+                                                    }).collect::<Html>()
+                                                }
+                                            </div>
+                                        </div>
+                                    }
+                                }
+                            }
+                        }
                     </section>
                     
                     // 3. Método de Pago
@@ -219,11 +480,45 @@ pub fn checkout() -> Html {
                         }
                     </section>
 
+                    // 4. Descuentos y Donación
+                    <section>
+                        <h2 class="text-xl font-bold text-gray-800 mb-4 border-b pb-2">{ "4. Descuentos y Donación" }</h2>
+                        <div class="grid grid-cols-1 md:grid-cols-2 gap-6">
+                            <div>
+                                <label class="block text-sm font-medium text-gray-700 mb-1">{ "Código de Descuento" }</label>
+                                <div class="flex gap-2">
+                                    <input ref={promo_ref} type="text" class="flex-grow rounded-lg border-gray-300 shadow-sm focus:ring-primary focus:border-primary p-2 border uppercase" placeholder="PROFES10" />
+                                    <button type="button" onclick={on_apply_promo} class="bg-gray-100 text-gray-700 hover:bg-gray-200 px-4 rounded-lg font-bold">{ "Aplicar" }</button>
+                                </div>
+                                if let Some(p) = &*promo {
+                                    <p class="text-sm text-green-700 mt-1">{ format!("Código {} aplicado", p.code) }</p>
+                                }
+                            </div>
+                            <div>
+                                <label class="block text-sm font-medium text-gray-700 mb-1">{ "Donación Extra (Bs.)" }</label>
+                                <input type="number" min="0" step="0.5" oninput={on_donation_input} class="w-full rounded-lg border-gray-300 shadow-sm focus:ring-primary focus:border-primary p-2 border" placeholder="0" />
+                                <p class="text-xs text-gray-500 mt-1">{ "Redondea tu pedido y apoya directamente al beneficiario." }</p>
+                            </div>
+                        </div>
+                    </section>
+
                     // Resumen
                     <div class="border-t pt-6">
+                        <div class="space-y-1 text-sm text-gray-600 mb-4">
+                            <div class="flex justify-between"><span>{ "Subtotal" }</span><span>{ format!("Bs. {:.2}", subtotal) }</span></div>
+                            if discount > 0.0 {
+                                <div class="flex justify-between text-green-700"><span>{ "Descuento" }</span><span>{ format!("- Bs. {:.2}", discount) }</span></div>
+                            }
+                            if delivery_fee > 0.0 {
+                                <div class="flex justify-between"><span>{ "Costo de Envío" }</span><span>{ format!("Bs. {:.2}", delivery_fee) }</span></div>
+                            }
+                            if *extra_donation > 0.0 {
+                                <div class="flex justify-between text-purple-700"><span>{ "Donación Extra" }</span><span>{ format!("Bs. {:.2}", *extra_donation) }</span></div>
+                            }
+                        </div>
                         <div class="flex justify-between items-center text-2xl font-bold text-gray-800 mb-6">
                             <span>{ "Total a Pagar" }</span>
-                            <span>{ format!("Bs. {:.2}", cart_ctx.state.total()) }</span>
+                            <span>{ format!("Bs. {:.2}", grand_total) }</span>
                         </div>
                         <button type="submit" disabled={*is_submitting} class="w-full bg-green-600 text-white font-bold py-4 rounded-xl shadow-lg hover:bg-green-700 transition transform hover:scale-[1.02] disabled:opacity-50 disabled:scale-100">
                             { if *is_submitting { "Procesando..." } else { "Confirmar Pedido" } }
@@ -148,6 +148,36 @@ pub fn collaborator_dashboard() -> Html {
                                                     <span class="group-btn-hover:-rotate-12 transition-transform">{"📋"}</span> 
                                                     { "Gestionar Pedidos" }
                                                 </button>
+                                                if k.role == "KITCHEN" {
+                                                    <button 
+                                                        onclick={let kitchen_navigator = navigator.clone(); Callback::from(move |_| kitchen_navigator.push(&Route::KitchenDisplay { id }))}
+                                                        class="w-full mt-2 bg-gray-900 text-white font-bold py-3 rounded-xl hover:bg-gray-700 transition flex items-center justify-center gap-2"
+                                                    >
+                                                        { "👨‍🍳 Pantalla de Cocina" }
+                                                    </button>
+                                                }
+                                                if k.role == "SELLER" {
+                                                    <button 
+                                                        onclick={let pos_navigator = navigator.clone(); Callback::from(move |_| pos_navigator.push(&Route::PointOfSale { id }))}
+                                                        class="w-full mt-2 bg-primary text-white font-bold py-3 rounded-xl hover:bg-red-600 transition flex items-center justify-center gap-2"
+                                                    >
+                                                        { "🧾 Punto de Venta" }
+                                                    </button>
+                                                    <button 
+                                                        onclick={let pickup_navigator = navigator.clone(); Callback::from(move |_| pickup_navigator.push(&Route::PickupDesk { id }))}
+                                                        class="w-full mt-2 bg-green-600 text-white font-bold py-3 rounded-xl hover:bg-green-700 transition flex items-center justify-center gap-2"
+                                                    >
+                                                        { "📦 Entrega de Pedidos" }
+                                                    </button>
+                                                }
+                                                if k.role == "DELIVERY" {
+                                                    <button 
+                                                        onclick={let delivery_navigator = navigator.clone(); Callback::from(move |_| delivery_navigator.push(&Route::MyDeliveries))}
+                                                        class="w-full mt-2 bg-indigo-600 text-white font-bold py-3 rounded-xl hover:bg-indigo-700 transition flex items-center justify-center gap-2"
+                                                    >
+                                                        { "🛵 Mis Entregas" }
+                                                    </button>
+                                                }
                                             </div>
                                         </div>
                                     </div>
@@ -20,6 +20,7 @@ struct CreateKermesseRequest {
     pub qr_code_url: Option<String>,
     pub department: Option<String>,
     pub city: Option<String>,
+    pub delivery_enabled: bool,
 }
 
 #[function_component(CreateKermesse)]
@@ -36,6 +37,7 @@ pub fn create_kermesse() -> Html {
     let qr_code_ref = use_node_ref();
     let dept_ref = use_node_ref();
     let city_ref = use_node_ref();
+    let delivery_ref = use_node_ref();
     
     let navigator = use_navigator().unwrap();
     let user_ctx = use_context::<UserContext>().expect("No UserContext found");
@@ -60,6 +62,7 @@ pub fn create_kermesse() -> Html {
         let qr_code_ref = qr_code_ref.clone();
         let dept_ref = dept_ref.clone();
         let city_ref = city_ref.clone();
+        let delivery_ref = delivery_ref.clone();
         let token = token.clone();
         let navigator = navigator.clone();
 
@@ -77,6 +80,7 @@ pub fn create_kermesse() -> Html {
             let qr_code_url = qr_code_ref.cast::<HtmlInputElement>().unwrap().value();
             let department_val = dept_ref.cast::<HtmlInputElement>().unwrap().value();
             let city_val = city_ref.cast::<HtmlInputElement>().unwrap().value();
+            let delivery_enabled = delivery_ref.cast::<HtmlInputElement>().unwrap().checked();
 
             let start_time = if start_time.is_empty() { None } else { Some(start_time) };
             let end_time = if end_time.is_empty() { None } else { Some(end_time) };
@@ -103,6 +107,7 @@ pub fn create_kermesse() -> Html {
                     qr_code_url,
                     department,
                     city,
+                    delivery_enabled,
                 };
                 let body = serde_json::to_string(&request).unwrap();
                 let resp = Request::post("http://127.0.0.1:8080/kermesses")
@@ -242,6 +247,17 @@ pub fn create_kermesse() -> Html {
                                 </div>
                             </div>
                         </div>
+
+                        // Section: Delivery
+                        <div class="space-y-6">
+                            <h3 class="text-lg font-bold text-gray-800 border-b pb-2 flex items-center gap-2">
+                                <span class="text-orange-500">{"🛵"}</span> { "Entregas" }
+                            </h3>
+                            <label class="flex items-center gap-3 text-gray-700">
+                                <input ref={delivery_ref} type="checkbox" />
+                                { "Ofrecer entrega a domicilio" }
+                            </label>
+                        </div>
     
                         <div class="pt-6">
                             <button type="submit" class="w-full bg-gradient-to-r from-orange-500 to-red-600 text-white font-bold text-lg py-4 px-6 rounded-xl shadow-lg hover:shadow-orange-500/30 hover:to-red-700 transition transform hover:-translate-y-1 active:scale-95">
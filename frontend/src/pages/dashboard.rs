@@ -3,6 +3,7 @@ use yew_router::prelude::*;
 use reqwasm::http::Request;
 use crate::router::Route;
 use crate::context::UserContext;
+use crate::components::organizer_overview::OrganizerOverviewPanel;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, PartialEq, Deserialize)]
@@ -135,6 +136,8 @@ pub fn dashboard() -> Html {
                         </button>
                      </div>
                 } else {
+                    <OrganizerOverviewPanel />
+
                     <div class="mb-6 flex justify-between items-end">
                         <h3 class="text-2xl font-bold text-gray-800">{ "Historial de Eventos" }</h3>
                         <span class="bg-orange-100 text-orange-800 font-bold px-3 py-1 rounded-full text-sm">{ format!("{} Eventos", kermesses.len()) }</span>
@@ -19,6 +19,7 @@ struct EditKermesseForm {
     qr_code_url: String,
     department: String,
     city: String,
+    delivery_enabled: bool,
 }
 
 #[derive(Properties, PartialEq)]
@@ -66,6 +67,7 @@ pub fn edit_kermesse(props: &Props) -> Html {
                        form.qr_code_url = k["qr_code_url"].as_str().unwrap_or("").to_string();
                        form.department = k["department"].as_str().unwrap_or("").to_string();
                        form.city = k["city"].as_str().unwrap_or("").to_string();
+                       form.delivery_enabled = k["delivery_enabled"].as_bool().unwrap_or(false);
                        
                        form_data.set(form);
                    }
@@ -125,6 +127,7 @@ pub fn edit_kermesse(props: &Props) -> Html {
                         "qr_code_url": if form.qr_code_url.is_empty() { None } else { Some(form.qr_code_url) },
                         "department": if form.department.is_empty() { None } else { Some(form.department) },
                         "city": if form.city.is_empty() { None } else { Some(form.city) },
+                        "delivery_enabled": form.delivery_enabled,
                     });
 
                     gloo_console::log!("Sending body:", body.to_string());
@@ -275,6 +278,23 @@ pub fn edit_kermesse(props: &Props) -> Html {
                         </div>
                     </div>
 
+                    <div class="border-t pt-4 mt-4">
+                        <h3 class="text-lg font-semibold mb-3 text-gray-600">{ "Entregas" }</h3>
+                        <label class="flex items-center gap-2 text-gray-700">
+                            <input
+                                type="checkbox"
+                                checked={form_data.delivery_enabled}
+                                onchange={let form_data = form_data.clone(); Callback::from(move |_| {
+                                    let mut new_data = (*form_data).clone();
+                                    new_data.delivery_enabled = !new_data.delivery_enabled;
+                                    form_data.set(new_data);
+                                })}
+                            />
+                            { "Ofrecer entrega a domicilio" }
+                        </label>
+                        <p class="text-sm text-gray-500 mt-1">{ "Sin zonas de entrega configuradas, el envío es gratis a cualquier dirección." }</p>
+                    </div>
+
                     <div class="pt-6">
                         <button type="submit" class="w-full bg-primary text-white font-bold py-3 rounded-lg hover:bg-orange-600 transition shadow-lg">
                             { "Guardar Cambios" }
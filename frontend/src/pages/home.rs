@@ -3,6 +3,7 @@ use yew_router::prelude::*;
 use reqwasm::http::Request;
 use serde::Deserialize;
 use crate::router::Route;
+use crate::components::organizer_dashboard::deserialize_price;
 
 #[derive(Clone, PartialEq, Deserialize)]
 pub struct Kermesse {
@@ -21,6 +22,12 @@ pub struct Kermesse {
     pub qr_code_url: Option<String>,
     pub department: Option<String>,
     pub city: Option<String>,
+    #[serde(default)]
+    pub transparency_enabled: bool,
+    #[serde(default, deserialize_with = "deserialize_price")]
+    pub total_raised: f64,
+    #[serde(default)]
+    pub progress_percentage: Option<f64>,
 }
 
 #[function_component(Home)]
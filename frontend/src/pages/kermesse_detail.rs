@@ -10,6 +10,9 @@ use crate::components::collaboration_form::CollaborationRequestForm;
 use crate::components::ingredient_donations::IngredientDonationsList;
 use crate::context::{CartContext, CartAction, CartItem};
 use crate::components::cart_drawer::CartDrawer;
+use crate::components::donor_wall::DonorWall;
+use crate::components::fundraising_progress::FundraisingProgressBar;
+use crate::components::live_events::use_live_events;
 // use gloo_console;
 
 #[derive(Clone, PartialEq, Deserialize)]
@@ -100,6 +103,23 @@ pub fn kermesse_detail(props: &Props) -> Html {
         });
     }
 
+    // Keep stock counters live while people browse the menu
+    let live = use_live_events(Some(format!("http://127.0.0.1:8080/kermesses/{}/events", id)), None);
+    {
+        let detail = detail.clone();
+        use_effect_with(live.last.clone(), move |event| {
+            if let Some(event) = event.as_ref().filter(|e| e.kind == "stock_changed") {
+                if let (Some(dish_id), Some(quantity), Some(mut data)) = (event.dish_id, event.quantity_available, (*detail).clone()) {
+                    if let Some(dish) = data.dishes.iter_mut().find(|d| d.id == dish_id) {
+                        dish.quantity_available = quantity;
+                        detail.set(Some(data));
+                    }
+                }
+            }
+            || ()
+        });
+    }
+
     // State now stores: (id, name, price, quantity_available)
     let selected_dish = use_state(|| None::<(i32, String, f64, i32)>);
     let modal_quantity = use_state(|| 1);
@@ -318,6 +338,8 @@ pub fn kermesse_detail(props: &Props) -> Html {
 
                         // --- SIDEBAR ---
                         <div class="space-y-6">
+                            <FundraisingProgressBar kermesse_id={id} />
+
                             // Share Card
                             <div class="bg-gradient-to-br from-indigo-500 to-purple-600 rounded-3xl shadow-xl p-8 text-white text-center">
                                 <h3 class="text-2xl font-bold mb-2">{ "¡Comparte y Ayuda!" }</h3>
@@ -340,6 +362,19 @@ pub fn kermesse_detail(props: &Props) -> Html {
                                 </div>
                             </div>
 
+                            // Accountability, once the organizer publishes it
+                            if kermesse.transparency_enabled {
+                                <Link<Route> to={Route::Transparency { id }} classes="block bg-white rounded-3xl shadow-xl p-8 border border-gray-100 hover:shadow-2xl transition">
+                                    <h3 class="text-xl font-bold mb-2 text-gray-800 flex items-center gap-2">
+                                        <span>{"🔍"}</span> { "Transparencia" }
+                                    </h3>
+                                    <p class="text-sm text-gray-500">{ "Mira cuánto se recaudó, en qué se gastó y los comprobantes de entrega al beneficiario." }</p>
+                                </Link<Route>>
+                            }
+
+                            // Donor Wall
+                            <DonorWall kermesse_id={id} />
+
                             // Collaborators
                             <div class="bg-white rounded-3xl shadow-xl p-8">
                                 <h3 class="text-xl font-bold mb-6 text-gray-800 flex items-center gap-2">
@@ -2,6 +2,7 @@ use yew::prelude::*;
 use yew_router::prelude::*;
 use crate::router::Route;
 use crate::components::organizer_orders::OrganizerOrders;
+use crate::components::kitchen_slots::KitchenSlots;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
@@ -15,13 +16,45 @@ pub fn kermesse_orders(props: &Props) -> Html {
     html! {
         <div class="min-h-screen bg-gray-50 p-4 md:p-8">
             <div class="container mx-auto max-w-5xl">
-                <button 
-                    onclick={Callback::from(move |_| navigator.back())}
-                    class="mb-6 text-gray-600 hover:text-gray-900 font-medium flex items-center gap-2"
-                >
-                    <span>{"←"}</span> { "Volver al Panel" }
-                </button>
+                <div class="flex justify-between items-center mb-6">
+                    <button 
+                        onclick={let navigator = navigator.clone(); Callback::from(move |_| navigator.back())}
+                        class="text-gray-600 hover:text-gray-900 font-medium flex items-center gap-2"
+                    >
+                        <span>{"←"}</span> { "Volver al Panel" }
+                    </button>
+                    <div class="flex gap-2">
+                        <button 
+                            onclick={let navigator = navigator.clone(); let id = props.kermesse_id; Callback::from(move |_| navigator.push(&Route::PointOfSale { id }))}
+                            class="bg-primary text-white font-bold px-4 py-2 rounded-lg hover:bg-red-600 transition"
+                        >
+                            { "🧾 Punto de Venta" }
+                        </button>
+                        <button 
+                            onclick={let navigator = navigator.clone(); let id = props.kermesse_id; Callback::from(move |_| navigator.push(&Route::PickupDesk { id }))}
+                            class="bg-green-600 text-white font-bold px-4 py-2 rounded-lg hover:bg-green-700 transition"
+                        >
+                            { "📦 Entregas" }
+                        </button>
+                        <button 
+                            onclick={let navigator = navigator.clone(); let id = props.kermesse_id; Callback::from(move |_| navigator.push(&Route::DeliveryRouteSheet { id }))}
+                            class="bg-white border border-gray-300 text-gray-700 font-bold px-4 py-2 rounded-lg hover:bg-gray-100 transition"
+                        >
+                            { "🗺 Hoja de Ruta" }
+                        </button>
+                        <button 
+                            onclick={let id = props.kermesse_id; Callback::from(move |_| navigator.push(&Route::KitchenDisplay { id }))}
+                            class="bg-gray-900 text-white font-bold px-4 py-2 rounded-lg hover:bg-gray-700 transition"
+                        >
+                            { "👨‍🍳 Pantalla de Cocina" }
+                        </button>
+                    </div>
+                </div>
                 
+                <div class="bg-white rounded-xl shadow-lg p-6 mb-6">
+                    <KitchenSlots kermesse_id={props.kermesse_id} />
+                </div>
+
                 <div class="bg-white rounded-xl shadow-lg p-6">
                     <h1 class="text-2xl font-bold text-gray-800 mb-6 border-b pb-4">{ "Gestión de Pedidos" }</h1>
                     <OrganizerOrders kermesse_id={props.kermesse_id} />
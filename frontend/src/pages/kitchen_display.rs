@@ -0,0 +1,218 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use reqwasm::http::Request;
+use serde::{Deserialize, Serialize};
+use gloo_timers::callback::Interval;
+use crate::context::UserContext;
+use crate::components::live_events::use_live_events;
+
+const REFRESH_MS: u32 = 15_000;
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct QueueItem {
+    pub id: i32,
+    pub dish_id: i32,
+    pub dish_name: String,
+    pub quantity: i32,
+    pub prep_status: String,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct QueueOrder {
+    pub sale_id: i32,
+    pub customer_name: String,
+    pub delivery_method: String,
+    pub time_slot: Option<String>,
+    pub elapsed_minutes: i64,
+    pub items: Vec<QueueItem>,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct DishQueue {
+    pub dish_id: i32,
+    pub dish_name: String,
+    pub pending: i32,
+    pub preparing: i32,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Debug, Default)]
+pub struct KitchenQueue {
+    pub dishes: Vec<DishQueue>,
+    pub orders: Vec<QueueOrder>,
+}
+
+#[derive(Serialize)]
+struct UpdatePrepStatusRequest {
+    prep_status: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(KitchenDisplay)]
+pub fn kitchen_display(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let navigator = use_navigator().unwrap();
+    let queue = use_state(KitchenQueue::default);
+    let error = use_state(|| None::<String>);
+    let refresh_trigger = use_state(|| 0);
+    let tick = use_state(|| 0_u32);
+    let kermesse_id = props.kermesse_id;
+    let token = user_ctx.user.as_ref().map(|u| u.token.clone());
+    // New paid orders show up immediately; the timer keeps elapsed minutes current
+    let live = use_live_events(
+        token.as_ref().map(|_| format!("http://127.0.0.1:8080/kermesses/{}/events", kermesse_id)),
+        token.clone(),
+    );
+
+    // Auto-refresh for the kitchen tablet
+    {
+        let tick = tick.clone();
+        use_effect_with((), move |_| {
+            let mut count = 0_u32;
+            let interval = Interval::new(REFRESH_MS, move || {
+                count = count.wrapping_add(1);
+                tick.set(count);
+            });
+            move || drop(interval)
+        });
+    }
+
+    {
+        let queue = queue.clone();
+        let error = error.clone();
+        let token = token.clone();
+        use_effect_with((refresh_trigger.clone(), *tick, live.version), move |_| {
+            if let Some(token) = token {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let url = format!("http://127.0.0.1:8080/kermesses/{}/kitchen/queue", kermesse_id);
+                    match Request::get(&url)
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await
+                    {
+                        Ok(resp) if resp.ok() => {
+                            if let Ok(data) = resp.json::<KitchenQueue>().await {
+                                queue.set(data);
+                                error.set(None);
+                            }
+                        }
+                        Ok(resp) => error.set(Some(resp.text().await.unwrap_or_default())),
+                        Err(_) => error.set(Some("Sin conexión con el servidor".to_string())),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let set_status = {
+        let token = token.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        Callback::from(move |(item_id, status): (i32, &'static str)| {
+            let token = token.clone();
+            let refresh_trigger = refresh_trigger.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(token) = token {
+                    let body = serde_json::to_string(&UpdatePrepStatusRequest { prep_status: status.to_string() }).unwrap();
+                    let resp = Request::put(&format!("http://127.0.0.1:8080/sale-items/{}/prep-status", item_id))
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    match resp {
+                        Ok(resp) if resp.ok() => refresh_trigger.set(*refresh_trigger + 1),
+                        Ok(resp) => gloo_dialogs::alert(&resp.text().await.unwrap_or_default()),
+                        Err(_) => gloo_dialogs::alert("Error de conexión."),
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="fixed inset-0 z-50 bg-gray-900 text-white overflow-y-auto p-6">
+            <div class="flex justify-between items-center mb-6">
+                <h1 class="text-3xl font-bold">{ "👨‍🍳 Cocina" }</h1>
+                <div class="flex items-center gap-4">
+                    if let Some(err) = &*error {
+                        <span class="text-red-400 text-sm">{ err }</span>
+                    }
+                    <span class="text-gray-400 text-sm">{ format!("{} pedidos en cola", queue.orders.len()) }</span>
+                    <button onclick={Callback::from(move |_| navigator.back())} class="bg-gray-700 hover:bg-gray-600 px-4 py-2 rounded-lg font-bold">
+                        { "Salir" }
+                    </button>
+                </div>
+            </div>
+
+            <div class="flex flex-wrap gap-3 mb-8">
+                {
+                    queue.dishes.iter().map(|d| html! {
+                        <div class="bg-gray-800 rounded-xl px-5 py-3">
+                            <p class="text-lg font-bold">{ &d.dish_name }</p>
+                            <p class="text-sm">
+                                <span class="text-yellow-400 font-bold">{ format!("{} por hacer", d.pending) }</span>
+                                <span class="text-gray-500">{ " · " }</span>
+                                <span class="text-blue-400 font-bold">{ format!("{} en preparación", d.preparing) }</span>
+                            </p>
+                        </div>
+                    }).collect::<Html>()
+                }
+            </div>
+
+            if queue.orders.is_empty() {
+                <div class="text-center text-gray-500 text-2xl py-20">{ "No hay pedidos pendientes 🎉" }</div>
+            } else {
+                <div class="grid grid-cols-1 md:grid-cols-2 xl:grid-cols-4 gap-4">
+                    {
+                        queue.orders.iter().map(|order| {
+                            let late = order.elapsed_minutes >= 20;
+                            html! {
+                                <div class={format!("rounded-xl p-4 border-t-4 bg-gray-800 {}", if late { "border-red-500" } else { "border-green-500" })}>
+                                    <div class="flex justify-between items-start mb-3">
+                                        <div>
+                                            <p class="font-mono text-gray-400">{ format!("#{}", order.sale_id) }</p>
+                                            <p class="text-xl font-bold">{ &order.customer_name }</p>
+                                            <p class="text-xs text-gray-400">
+                                                { if order.delivery_method == "DELIVERY" { "🛵 Delivery" } else { "🏃 Recojo" } }
+                                                { order.time_slot.as_ref().map(|s| format!(" · {}", s)).unwrap_or_default() }
+                                            </p>
+                                        </div>
+                                        <span class={format!("text-2xl font-bold {}", if late { "text-red-400" } else { "text-green-400" })}>
+                                            { format!("{}'", order.elapsed_minutes) }
+                                        </span>
+                                    </div>
+                                    <ul class="space-y-2">
+                                        {
+                                            order.items.iter().map(|item| {
+                                                let id = item.id;
+                                                let preparing = item.prep_status == "PREPARING";
+                                                let on_click = {
+                                                    let set_status = set_status.clone();
+                                                    Callback::from(move |_| set_status.emit((id, if preparing { "READY" } else { "PREPARING" })))
+                                                };
+                                                html! {
+                                                    <li class="flex justify-between items-center bg-gray-700 rounded-lg px-3 py-2">
+                                                        <span class="text-lg">{ format!("{} × {}", item.quantity, item.dish_name) }</span>
+                                                        <button onclick={on_click}
+                                                            class={format!("px-3 py-1 rounded-lg font-bold text-sm {}", if preparing { "bg-green-600 hover:bg-green-500" } else { "bg-blue-600 hover:bg-blue-500" })}
+                                                        >
+                                                            { if preparing { "✔ Listo" } else { "🔥 Preparar" } }
+                                                        </button>
+                                                    </li>
+                                                }
+                                            }).collect::<Html>()
+                                        }
+                                    </ul>
+                                </div>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+            }
+        </div>
+    }
+}
@@ -10,3 +10,10 @@ pub mod collaborator_dashboard;
 pub mod kermesse_orders;
 pub mod create_kermesse;
 pub mod all_kermesses;
+pub mod kitchen_display;
+pub mod my_deliveries;
+pub mod route_sheet;
+pub mod pos;
+pub mod pickup_desk;
+pub mod order_tracking;
+pub mod transparency;
@@ -0,0 +1,166 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use serde::Deserialize;
+use crate::context::UserContext;
+use crate::components::organizer_dashboard::deserialize_price;
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct DeliveryStop {
+    pub sale_id: i32,
+    pub kermesse_id: i32,
+    pub kermesse_name: String,
+    pub customer_name: String,
+    pub contact_phone: Option<String>,
+    pub delivery_address: Option<String>,
+    pub delivery_neighborhood: Option<String>,
+    pub delivery_city: Option<String>,
+    pub status: String,
+    pub payment_method: String,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub total_amount: f64,
+    pub out_for_delivery_at: Option<String>,
+    pub delivered_at: Option<String>,
+    pub items: Vec<String>,
+}
+
+#[function_component(MyDeliveries)]
+pub fn my_deliveries() -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let deliveries = use_state(Vec::<DeliveryStop>::new);
+    let loading = use_state(|| true);
+    let refresh_trigger = use_state(|| 0);
+
+    {
+        let deliveries = deliveries.clone();
+        let loading = loading.clone();
+        let user_ctx = user_ctx.clone();
+        use_effect_with(refresh_trigger.clone(), move |_| {
+            if let Some(user) = &user_ctx.user {
+                let token = user.token.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let resp = Request::get("http://127.0.0.1:8080/my-deliveries")
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await;
+                    if let Ok(resp) = resp {
+                        if let Ok(data) = resp.json::<Vec<DeliveryStop>>().await {
+                            deliveries.set(data);
+                        }
+                    }
+                    loading.set(false);
+                });
+            } else {
+                loading.set(false);
+            }
+            || ()
+        });
+    }
+
+    let update_status = {
+        let user_ctx = user_ctx.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        Callback::from(move |(sale_id, status): (i32, &'static str)| {
+            let token = user_ctx.user.as_ref().map(|u| u.token.clone());
+            let refresh_trigger = refresh_trigger.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(token) = token {
+                    let url = format!("http://127.0.0.1:8080/sales/{}/delivery-status", sale_id);
+                    let body = serde_json::json!({ "status": status });
+                    match Request::put(&url)
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .header("Content-Type", "application/json")
+                        .body(body.to_string())
+                        .send()
+                        .await
+                    {
+                        Ok(resp) if resp.ok() => refresh_trigger.set(*refresh_trigger + 1),
+                        Ok(resp) => gloo_dialogs::alert(&resp.text().await.unwrap_or_default()),
+                        Err(_) => gloo_dialogs::alert("Error de conexión."),
+                    }
+                }
+            });
+        })
+    };
+
+    let (pending, done): (Vec<&DeliveryStop>, Vec<&DeliveryStop>) = deliveries.iter().partition(|d| d.status != "DELIVERED" && d.delivered_at.is_none());
+
+    html! {
+        <div class="min-h-screen bg-gray-50 p-4 md:p-8">
+            <div class="container mx-auto max-w-3xl">
+                <h1 class="text-3xl font-bold mb-8 text-primary border-b border-gray-200 pb-4">{ "🛵 Mis Entregas" }</h1>
+
+                if *loading {
+                    <div class="text-center py-8">{ "Cargando entregas..." }</div>
+                } else if deliveries.is_empty() {
+                    <div class="text-center py-12 bg-white rounded-xl shadow-md text-gray-500">
+                        { "No tienes entregas asignadas." }
+                    </div>
+                } else {
+                    <div class="space-y-4">
+                        {
+                            pending.iter().map(|d| {
+                                let id = d.sale_id;
+                                // A partially refunded order keeps its status; the timestamp says it left
+                                let out = d.status == "OUT_FOR_DELIVERY" || (d.status == "PARTIALLY_REFUNDED" && d.out_for_delivery_at.is_some());
+                                let unpaid = d.status == "PENDING";
+                                let cash_on_delivery = unpaid && d.payment_method == "CASH";
+                                let update_status = update_status.clone();
+                                html! {
+                                    <div class="bg-white rounded-xl shadow p-5 border-l-4 border-primary">
+                                        <div class="flex justify-between items-start">
+                                            <div>
+                                                <p class="text-xs text-gray-400">{ format!("#{} · {}", d.sale_id, d.kermesse_name) }</p>
+                                                <p class="text-lg font-bold">{ &d.customer_name }</p>
+                                                <p class="text-gray-700">{ d.delivery_address.clone().unwrap_or_default() }</p>
+                                                <p class="text-sm text-gray-500">
+                                                    { [d.delivery_neighborhood.clone(), d.delivery_city.clone()].into_iter().flatten().collect::<Vec<_>>().join(", ") }
+                                                </p>
+                                                if let Some(phone) = &d.contact_phone {
+                                                    <a href={format!("tel:{}", phone)} class="text-secondary font-bold text-sm">{ format!("📞 {}", phone) }</a>
+                                                }
+                                            </div>
+                                            <div class="text-right">
+                                                <p class="font-bold">{ format!("Bs. {:.2}", d.total_amount) }</p>
+                                                <p class="text-xs text-gray-500">{ if d.payment_method == "CASH" { "Cobrar en efectivo" } else { "Pagado con QR" } }</p>
+                                            </div>
+                                        </div>
+                                        <p class="text-sm text-gray-600 mt-2">{ d.items.join(", ") }</p>
+                                        <div class="mt-4">
+                                            if out {
+                                                <button onclick={Callback::from(move |_| update_status.emit((id, "DELIVERED")))} class="w-full bg-green-600 text-white font-bold py-2 rounded-lg hover:bg-green-700 transition">
+                                                    { "✔ Marcar Entregado" }
+                                                </button>
+                                            } else if cash_on_delivery {
+                                                <button onclick={Callback::from(move |_| update_status.emit((id, "DELIVERED")))} class="w-full bg-green-600 text-white font-bold py-2 rounded-lg hover:bg-green-700 transition">
+                                                    { "✔ Entregado y Cobrado" }
+                                                </button>
+                                            } else if unpaid {
+                                                <p class="text-center text-sm text-gray-500 bg-gray-50 py-2 rounded-lg">{ "Esperando confirmación del pago" }</p>
+                                            } else {
+                                                <button onclick={Callback::from(move |_| update_status.emit((id, "OUT_FOR_DELIVERY")))} class="w-full bg-indigo-600 text-white font-bold py-2 rounded-lg hover:bg-indigo-700 transition">
+                                                    { "🛵 Salir a Entregar" }
+                                                </button>
+                                            }
+                                        </div>
+                                    </div>
+                                }
+                            }).collect::<Html>()
+                        }
+
+                        if !done.is_empty() {
+                            <h2 class="text-lg font-bold text-gray-600 pt-4">{ "Entregados" }</h2>
+                            {
+                                done.iter().map(|d| html! {
+                                    <div class="bg-white rounded-lg p-4 text-sm text-gray-500 flex justify-between">
+                                        <span>{ format!("#{} · {}", d.sale_id, d.customer_name) }</span>
+                                        <span>{ d.delivered_at.clone().unwrap_or_default() }</span>
+                                    </div>
+                                }).collect::<Html>()
+                            }
+                        }
+                    </div>
+                }
+            </div>
+        </div>
+    }
+}
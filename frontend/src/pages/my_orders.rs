@@ -4,6 +4,9 @@ use crate::context::UserContext;
 use reqwasm::http::Request;
 use serde::{Deserialize, Serialize};
 use crate::router::Route;
+use crate::components::live_events::use_live_events;
+use crate::components::claim_orders::ClaimOrders;
+use crate::components::file_download::download;
 
 #[derive(Clone, PartialEq, Deserialize, Serialize, Debug)]
 pub struct MySaleResponse {
@@ -14,6 +17,8 @@ pub struct MySaleResponse {
     pub status: String,
     pub payment_method: String,
     pub created_at: String,
+    #[serde(default)]
+    pub pickup_code: Option<String>,
 }
 
 #[function_component(MyOrders)]
@@ -22,12 +27,17 @@ pub fn my_orders() -> Html {
     let orders = use_state(|| Vec::<MySaleResponse>::new());
     let loading = use_state(|| true);
     let navigator = use_navigator().unwrap();
+    let live = use_live_events(
+        user_ctx.user.as_ref().map(|_| "http://127.0.0.1:8080/my-orders/events".to_string()),
+        user_ctx.user.as_ref().map(|u| u.token.clone()),
+    );
+    let reload = use_state(|| 0_u32);
 
     {
         let orders = orders.clone();
         let loading = loading.clone();
         let user_ctx = user_ctx.clone();
-        use_effect_with((), move |_| {
+        use_effect_with((live.version, *reload), move |_| {
             if let Some(user) = &user_ctx.user {
                 let token = user.token.clone();
                 let orders = orders.clone();
@@ -64,6 +74,7 @@ pub fn my_orders() -> Html {
             "PENDING" | "PENDING_PAYMENT" => "bg-yellow-100 text-yellow-800",
             "DELIVERED" => "bg-blue-100 text-blue-800",
             "CANCELLED" => "bg-red-100 text-red-800",
+            "REFUNDED" | "PARTIALLY_REFUNDED" => "bg-orange-100 text-orange-800",
             _ => "bg-gray-100 text-gray-800",
         };
         html! {
@@ -86,7 +97,15 @@ pub fn my_orders() -> Html {
     html! {
         <div class="min-h-screen bg-gray-50 text-gray-800 font-sans p-6 sm:p-10">
             <h1 class="text-3xl font-bold mb-8 text-primary border-b border-gray-200 pb-4">{ "Mis Pedidos" }</h1>
+
+            if let Some(sale_id) = live.last.as_ref().filter(|e| e.kind == "order_ready").and_then(|e| e.sale_id) {
+                <div class="mb-6 p-4 bg-green-50 border border-green-200 text-green-800 rounded-xl font-bold animate-pulse">
+                    { format!("🍽️ ¡Tu pedido #{} está listo!", sale_id) }
+                </div>
+            }
             
+            <ClaimOrders on_claimed={let reload = reload.clone(); Callback::from(move |_| reload.set(*reload + 1))} />
+
             if *loading {
                 <div class="flex justify-center py-10">
                     <div class="animate-spin rounded-full h-12 w-12 border-b-2 border-primary"></div>
@@ -111,12 +130,20 @@ pub fn my_orders() -> Html {
                                     <th class="p-4 font-semibold">{ "Fecha" }</th>
                                     <th class="p-4 font-semibold text-right">{ "Total" }</th>
                                     <th class="p-4 font-semibold text-center">{ "Estado" }</th>
+                                    <th class="p-4 font-semibold text-center">{ "Código de Recojo" }</th>
+                                    <th class="p-4 font-semibold text-center">{ "Comprobante" }</th>
                                     // <th class="p-4 font-semibold text-center">{ "Detalles" }</th>
                                 </tr>
                             </thead>
                             <tbody class="divide-y divide-gray-100">
                                 {
                                     orders.iter().map(|order| {
+                                        let on_pdf = {
+                                            let url = format!("http://127.0.0.1:8080/sales/{}/receipt.pdf", order.id);
+                                            let token = user_ctx.user.as_ref().map(|u| u.token.clone()).unwrap_or_default();
+                                            let file_name = format!("pedido-{}.pdf", order.id);
+                                            Callback::from(move |_| download(url.clone(), token.clone(), file_name.clone()))
+                                        };
                                         html! {
                                             <tr class="hover:bg-gray-50 transition">
                                                 <td class="p-4 font-mono text-gray-500 font-bold">{ format!("#{}", order.id) }</td>
@@ -124,6 +151,10 @@ pub fn my_orders() -> Html {
                                                 <td class="p-4 text-gray-500 text-sm">{ &order.event_date }</td>
                                                 <td class="p-4 text-right font-bold text-gray-800">{ format!("Bs. {:.2}", order.total_amount) }</td>
                                                 <td class="p-4 text-center">{ status_badge(&order.status) }</td>
+                                                <td class="p-4 text-center font-mono font-bold tracking-widest text-gray-700">{ order.pickup_code.clone().unwrap_or_default() }</td>
+                                                <td class="p-4 text-center">
+                                                    <button onclick={on_pdf} class="text-blue-600 hover:text-blue-800 font-bold text-sm">{ "📄 PDF" }</button>
+                                                </td>
                                                 // <td class="p-4 text-center">
                                                 //     <button class="text-blue-600 hover:text-blue-800 font-bold text-sm">{ "Ver" }</button>
                                                 // </td>
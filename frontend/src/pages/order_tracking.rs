@@ -0,0 +1,181 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use serde::Deserialize;
+use gloo_timers::callback::Interval;
+use crate::components::organizer_dashboard::deserialize_price;
+
+const REFRESH_MS: u32 = 30_000;
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct TrackedItem {
+    pub dish_name: String,
+    pub quantity: i32,
+    pub prep_status: String,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct TrackedOrder {
+    pub sale_id: i32,
+    pub kermesse_name: String,
+    pub event_date: String,
+    pub kermesse_city: Option<String>,
+    pub status: String,
+    pub ready: bool,
+    pub payment_method: String,
+    pub delivery_method: String,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub total_amount: f64,
+    pub time_slot: Option<String>,
+    pub pickup_code: Option<String>,
+    pub created_at: String,
+    pub paid_at: Option<String>,
+    pub out_for_delivery_at: Option<String>,
+    pub delivered_at: Option<String>,
+    pub items: Vec<TrackedItem>,
+}
+
+/// Progress steps shown to the buyer, with whether each one was reached.
+fn steps(order: &TrackedOrder) -> Vec<(&'static str, bool)> {
+    let paid = order.paid_at.is_some() || !matches!(order.status.as_str(), "PENDING" | "CANCELLED");
+    let delivered = order.delivered_at.is_some() || order.status == "DELIVERED";
+    let mut steps = vec![
+        ("Recibido", true),
+        ("Pagado", paid),
+        ("Listo", order.ready || delivered),
+    ];
+    if order.delivery_method == "DELIVERY" {
+        steps.push(("En camino", order.out_for_delivery_at.is_some() || delivered));
+    }
+    steps.push(("Entregado", delivered));
+    steps
+}
+
+fn prep_label(status: &str) -> &'static str {
+    match status {
+        "READY" => "Listo",
+        "PREPARING" => "En preparación",
+        _ => "En espera",
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub token: String,
+}
+
+#[function_component(OrderTracking)]
+pub fn order_tracking(props: &Props) -> Html {
+    let order = use_state(|| None::<TrackedOrder>);
+    let not_found = use_state(|| false);
+    let tick = use_state(|| 0_u32);
+
+    {
+        let tick = tick.clone();
+        use_effect_with((), move |_| {
+            let mut count = 0_u32;
+            let interval = Interval::new(REFRESH_MS, move || {
+                count = count.wrapping_add(1);
+                tick.set(count);
+            });
+            move || drop(interval)
+        });
+    }
+
+    {
+        let order = order.clone();
+        let not_found = not_found.clone();
+        let token = props.token.clone();
+        use_effect_with((token, *tick), move |(token, _)| {
+            let url = format!("http://127.0.0.1:8080/orders/track/{}", token);
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(resp) = Request::get(&url).send().await {
+                    if resp.status() == 404 {
+                        not_found.set(true);
+                    } else if let Ok(data) = resp.json::<TrackedOrder>().await {
+                        order.set(Some(data));
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    if *not_found {
+        return html! {
+            <div class="container mx-auto px-4 py-16 text-center">
+                <h1 class="text-2xl font-bold text-gray-800 mb-2">{ "Pedido no encontrado" }</h1>
+                <p class="text-gray-500">{ "Revisa que el enlace de seguimiento esté completo." }</p>
+            </div>
+        };
+    }
+
+    let Some(o) = (*order).clone() else {
+        return html! { <div class="text-center py-16 text-gray-500">{ "Cargando pedido..." }</div> };
+    };
+
+    html! {
+        <div class="container mx-auto px-4 py-8 max-w-2xl">
+            <div class="bg-white rounded-2xl shadow-lg p-6 space-y-6">
+                <div>
+                    <p class="text-sm text-gray-500">{ format!("Pedido #{}", o.sale_id) }</p>
+                    <h1 class="text-2xl font-bold text-gray-900">{ &o.kermesse_name }</h1>
+                    <p class="text-gray-500">{ format!("{}{}", o.event_date, o.kermesse_city.as_ref().map(|c| format!(" · {}", c)).unwrap_or_default()) }</p>
+                </div>
+
+                if o.status == "CANCELLED" || o.status == "REFUNDED" {
+                    <div class="p-4 bg-red-50 border border-red-200 rounded-lg text-red-700 font-bold">
+                        { if o.status == "CANCELLED" { "Este pedido fue cancelado." } else { "Este pedido fue reembolsado." } }
+                    </div>
+                } else {
+                    <ol class="flex justify-between">
+                        {
+                            steps(&o).into_iter().map(|(label, done)| html! {
+                                <li class="flex flex-col items-center flex-1">
+                                    <span class={format!("w-8 h-8 rounded-full flex items-center justify-center font-bold {}", if done { "bg-green-600 text-white" } else { "bg-gray-200 text-gray-400" })}>
+                                        { if done { "✓" } else { "•" } }
+                                    </span>
+                                    <span class={format!("mt-1 text-xs font-medium {}", if done { "text-green-700" } else { "text-gray-400" })}>{ label }</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                    </ol>
+                }
+
+                if o.ready && o.delivered_at.is_none() && o.delivery_method != "DELIVERY" {
+                    <div class="p-4 bg-green-50 border border-green-200 rounded-lg text-green-800 font-bold text-center">
+                        { "🔔 ¡Tu pedido está listo! Acércate al puesto de entrega." }
+                    </div>
+                }
+
+                <ul class="divide-y divide-gray-100">
+                    {
+                        o.items.iter().map(|item| html! {
+                            <li class="py-2 flex justify-between">
+                                <span>{ format!("{} × {}", item.quantity, item.dish_name) }</span>
+                                <span class="text-sm text-gray-500">{ prep_label(&item.prep_status) }</span>
+                            </li>
+                        }).collect::<Html>()
+                    }
+                </ul>
+
+                <div class="flex justify-between border-t pt-4">
+                    <span class="text-gray-600">{ if o.payment_method == "CASH" { "Total (efectivo)" } else { "Total (QR)" } }</span>
+                    <span class="text-xl font-bold">{ format!("Bs. {:.2}", o.total_amount) }</span>
+                </div>
+
+                if let Some(slot) = &o.time_slot {
+                    <p class="text-sm text-gray-600">{ format!("Horario: {}", slot) }</p>
+                }
+
+                if let Some(code) = &o.pickup_code {
+                    <div class="text-center bg-gray-50 rounded-lg p-4">
+                        <p class="text-sm text-gray-500">{ "Código de Recojo" }</p>
+                        <p class="text-3xl font-mono font-bold tracking-widest text-gray-900">{ code }</p>
+                    </div>
+                }
+
+                <p class="text-xs text-gray-400 text-center">{ "Esta página se actualiza automáticamente." }</p>
+            </div>
+        </div>
+    }
+}
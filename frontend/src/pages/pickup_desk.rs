@@ -0,0 +1,178 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use reqwasm::http::Request;
+use serde::{Deserialize, Serialize};
+use crate::context::UserContext;
+use crate::components::organizer_dashboard::deserialize_price;
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct PickupItem {
+    pub dish_name: String,
+    pub quantity: i32,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct PickupOrder {
+    pub sale_id: i32,
+    pub customer_name: String,
+    pub status: String,
+    pub payment_method: String,
+    pub delivery_method: String,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub total_amount: f64,
+    pub delivered_at: Option<String>,
+    pub items: Vec<PickupItem>,
+}
+
+#[derive(Serialize)]
+struct VerifyPickupRequest {
+    code: String,
+    deliver: bool,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(PickupDesk)]
+pub fn pickup_desk(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let navigator = use_navigator().unwrap();
+    let kermesse_id = props.kermesse_id;
+    let code_ref = use_node_ref();
+    let order = use_state(|| None::<PickupOrder>);
+    let message = use_state(|| None::<String>);
+
+    // Handheld scanners type the code and press Enter, so one form serves both
+    let verify = {
+        let user_ctx = user_ctx.clone();
+        let order = order.clone();
+        let message = message.clone();
+        let code_ref = code_ref.clone();
+        Callback::from(move |deliver: bool| {
+            let input = code_ref.cast::<web_sys::HtmlInputElement>().unwrap();
+            let code = input.value();
+            if code.trim().is_empty() {
+                return;
+            }
+            let token = match user_ctx.user.as_ref() {
+                Some(u) => u.token.clone(),
+                None => return,
+            };
+            let order = order.clone();
+            let message = message.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let body = serde_json::to_string(&VerifyPickupRequest { code, deliver }).unwrap();
+                let url = format!("http://127.0.0.1:8080/kermesses/{}/pickup/verify", kermesse_id);
+                let resp = Request::post(&url)
+                    .header("Authorization", &format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .body(body)
+                    .send()
+                    .await;
+                match resp {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(found) = resp.json::<PickupOrder>().await {
+                            message.set(if deliver { Some(format!("Pedido #{} entregado ✅", found.sale_id)) } else { None });
+                            order.set(Some(found));
+                        }
+                    }
+                    // Not deliverable: the body still carries the order to show why
+                    Ok(resp) if resp.status() == 409 => {
+                        let text = resp.text().await.unwrap_or_default();
+                        match serde_json::from_str::<PickupOrder>(&text) {
+                            Ok(found) => {
+                                message.set(Some(format!("No se puede entregar: estado {}", found.status)));
+                                order.set(Some(found));
+                            }
+                            Err(_) => message.set(Some(text)),
+                        }
+                    }
+                    Ok(resp) if resp.status() == 404 => {
+                        order.set(None);
+                        message.set(Some("Código no encontrado.".to_string()));
+                    }
+                    Ok(resp) => message.set(Some(resp.text().await.unwrap_or_default())),
+                    Err(_) => message.set(Some("Error de conexión.".to_string())),
+                }
+            });
+        })
+    };
+
+    let on_submit = {
+        let verify = verify.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            verify.emit(false);
+        })
+    };
+
+    let on_next = {
+        let order = order.clone();
+        let message = message.clone();
+        let code_ref = code_ref.clone();
+        Callback::from(move |_| {
+            order.set(None);
+            message.set(None);
+            if let Some(input) = code_ref.cast::<web_sys::HtmlInputElement>() {
+                input.set_value("");
+                let _ = input.focus();
+            }
+        })
+    };
+
+    html! {
+        <div class="container mx-auto px-4 py-8 max-w-2xl">
+            <button onclick={Callback::from(move |_| navigator.back())} class="mb-6 text-gray-600 hover:text-gray-900 font-medium">{ "← Volver" }</button>
+            <h1 class="text-3xl font-bold text-gray-800 mb-6">{ "📦 Entrega de Pedidos" }</h1>
+
+            <form onsubmit={on_submit} class="flex gap-3 mb-6">
+                <input ref={code_ref} type="text" autofocus=true placeholder="Escanea o escribe el código"
+                    class="flex-1 rounded-lg border border-gray-300 p-3 text-xl font-mono uppercase tracking-widest" />
+                <button type="submit" class="bg-primary text-white font-bold px-6 rounded-lg hover:bg-red-600 transition">{ "Buscar" }</button>
+            </form>
+
+            if let Some(msg) = (*message).clone() {
+                <div class="mb-4 p-3 rounded-lg bg-gray-100 text-gray-800 font-medium">{ msg }</div>
+            }
+
+            if let Some(o) = (*order).clone() {
+                <div class="bg-white rounded-xl shadow-lg p-6 space-y-4">
+                    <div class="flex justify-between items-start">
+                        <div>
+                            <p class="text-sm text-gray-500">{ format!("Pedido #{}", o.sale_id) }</p>
+                            <p class="text-2xl font-bold text-gray-900">{ &o.customer_name }</p>
+                        </div>
+                        <span class="px-3 py-1 rounded-full bg-gray-100 text-gray-700 text-sm font-bold">{ &o.status }</span>
+                    </div>
+                    <ul class="divide-y divide-gray-100">
+                        {
+                            o.items.iter().map(|item| html! {
+                                <li class="py-2 flex justify-between">
+                                    <span>{ &item.dish_name }</span>
+                                    <span class="font-bold">{ format!("× {}", item.quantity) }</span>
+                                </li>
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                    if o.status == "PENDING" && o.payment_method == "CASH" {
+                        <p class="p-3 bg-yellow-50 border border-yellow-200 rounded-lg text-yellow-800 font-bold">
+                            { format!("Cobrar Bs. {:.2} en efectivo antes de entregar", o.total_amount) }
+                        </p>
+                    }
+                    if let Some(at) = &o.delivered_at {
+                        <p class="text-sm text-gray-500">{ format!("Entregado: {}", at) }</p>
+                    }
+                    <div class="flex gap-3">
+                        if o.delivered_at.is_none() {
+                            <button onclick={let verify = verify.clone(); Callback::from(move |_| verify.emit(true))}
+                                class="flex-1 bg-green-600 text-white font-bold py-3 rounded-lg hover:bg-green-700 transition">{ "Entregar" }</button>
+                        }
+                        <button onclick={on_next} class="flex-1 bg-gray-100 text-gray-800 font-bold py-3 rounded-lg hover:bg-gray-200 transition">{ "Siguiente" }</button>
+                    </div>
+                </div>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,516 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use reqwasm::http::Request;
+use serde::{Deserialize, Serialize};
+use gloo_storage::{LocalStorage, Storage};
+use gloo_timers::callback::Interval;
+use std::rc::Rc;
+use crate::context::UserContext;
+use crate::pages::kermesse_detail::{Dish, KermesseDetailData};
+use crate::components::organizer_dashboard::deserialize_option_price;
+
+const KEYPAD: [&str; 12] = ["1", "2", "3", "4", "5", "6", "7", "8", "9", ".", "0", "⌫"];
+/// How often sales queued while offline are sent again.
+const SYNC_INTERVAL_MS: u32 = 30_000;
+/// Matches the backend limit for `POST /sales/batch`.
+const MAX_BATCH_SIZE: usize = 100;
+
+#[derive(Clone, PartialEq)]
+struct TicketLine {
+    dish_id: i32,
+    name: String,
+    price: f64,
+    quantity: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+struct PosSaleItem {
+    dish_id: i32,
+    quantity: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+struct PosSaleRequest {
+    kermesse_id: i32,
+    customer_name: String,
+    items: Vec<PosSaleItem>,
+    delivery_method: String,
+    payment_method: String,
+    pos_mode: bool,
+    amount_tendered: Option<f64>,
+    client_key: String,
+}
+
+/// A sale rung up while the API was unreachable, kept in local storage until synced.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+struct QueuedSale {
+    request: PosSaleRequest,
+    total: f64,
+    // Set when the server refused the sale; the seller has to resolve it
+    conflict: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BatchResult {
+    client_key: Option<String>,
+    result: String,
+    message: Option<String>,
+}
+
+/// Counter bumped by the sync timer and the "Sincronizar" button.
+#[derive(Default, PartialEq)]
+struct SyncTick(u32);
+
+impl Reducible for SyncTick {
+    type Action = ();
+
+    fn reduce(self: Rc<Self>, _: ()) -> Rc<Self> {
+        Rc::new(SyncTick(self.0 + 1))
+    }
+}
+
+fn queue_key(kermesse_id: i32) -> String {
+    format!("pos_queue_{}", kermesse_id)
+}
+
+fn load_queue(kermesse_id: i32) -> Vec<QueuedSale> {
+    LocalStorage::get(queue_key(kermesse_id)).unwrap_or_default()
+}
+
+fn save_queue(kermesse_id: i32, queue: &[QueuedSale]) {
+    let _ = LocalStorage::set(queue_key(kermesse_id), queue);
+}
+
+/// Random key so a sale sent twice (retry, resync) is only recorded once.
+pub fn new_client_key() -> String {
+    let mut bytes = [0u8; 16];
+    if let Some(crypto) = web_sys::window().and_then(|w| w.crypto().ok()) {
+        let _ = crypto.get_random_values_with_u8_array(&mut bytes);
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Sends the queued sales that have no conflict. Returns the queue left afterwards,
+/// or None while the API is still unreachable.
+async fn sync_queue(kermesse_id: i32, token: &str) -> Option<Vec<QueuedSale>> {
+    let pending: Vec<PosSaleRequest> = load_queue(kermesse_id)
+        .into_iter()
+        .filter(|q| q.conflict.is_none())
+        .map(|q| q.request)
+        .take(MAX_BATCH_SIZE)
+        .collect();
+    if pending.is_empty() {
+        return Some(load_queue(kermesse_id));
+    }
+
+    let body = serde_json::json!({ "sales": pending }).to_string();
+    let resp = Request::post("http://127.0.0.1:8080/sales/batch")
+        .header("Authorization", &format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await
+        .ok()?;
+    if !resp.ok() {
+        return None;
+    }
+    let results = resp.json::<Vec<BatchResult>>().await.ok()?;
+
+    // Re-read: more sales may have been queued while the request was in flight
+    let mut queue = load_queue(kermesse_id);
+    for r in results {
+        let Some(key) = r.client_key else { continue };
+        match r.result.as_str() {
+            "CREATED" | "DUPLICATE" => queue.retain(|q| q.request.client_key != key),
+            "CONFLICT" | "REJECTED" => {
+                if let Some(q) = queue.iter_mut().find(|q| q.request.client_key == key) {
+                    q.conflict = Some(r.message.unwrap_or_default());
+                }
+            }
+            // FAILED: leave it for the next round
+            _ => {}
+        }
+    }
+    save_queue(kermesse_id, &queue);
+    Some(queue)
+}
+
+#[derive(Deserialize)]
+struct PosSaleReceipt {
+    id: i32,
+    #[serde(default, deserialize_with = "deserialize_option_price")]
+    change_due: Option<f64>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(PointOfSale)]
+pub fn point_of_sale(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let navigator = use_navigator().unwrap();
+    let kermesse_id = props.kermesse_id;
+    let dishes = use_state(Vec::<Dish>::new);
+    let ticket = use_state(Vec::<TicketLine>::new);
+    let tendered = use_state(String::new);
+    let payment_method = use_state(|| "CASH".to_string());
+    let eat_here = use_state(|| false);
+    let last_sale = use_state(|| None::<(String, Option<f64>)>);
+    let is_submitting = use_state(|| false);
+    let refresh_trigger = use_state(|| 0);
+    let queue = use_state(move || load_queue(kermesse_id));
+    let online = use_state(|| true);
+    let sync_tick = use_reducer(SyncTick::default);
+
+    {
+        let dispatcher = sync_tick.dispatcher();
+        use_effect_with((), move |_| {
+            let interval = Interval::new(SYNC_INTERVAL_MS, move || dispatcher.dispatch(()));
+            move || drop(interval)
+        });
+    }
+
+    {
+        let queue = queue.clone();
+        let online = online.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        let token = user_ctx.user.as_ref().map(|u| u.token.clone());
+        use_effect_with(sync_tick.0, move |_| {
+            if let Some(token) = token {
+                if !load_queue(kermesse_id).is_empty() {
+                    wasm_bindgen_futures::spawn_local(async move {
+                        match sync_queue(kermesse_id, &token).await {
+                            Some(left) => {
+                                queue.set(left);
+                                online.set(true);
+                                refresh_trigger.set(*refresh_trigger + 1);
+                            }
+                            None => online.set(false),
+                        }
+                    });
+                }
+            }
+            || ()
+        });
+    }
+
+    {
+        let dishes = dishes.clone();
+        use_effect_with(refresh_trigger.clone(), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                let url = format!("http://127.0.0.1:8080/kermesses/{}", kermesse_id);
+                if let Ok(resp) = Request::get(&url).send().await {
+                    if let Ok(detail) = resp.json::<KermesseDetailData>().await {
+                        dishes.set(detail.dishes);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let total: f64 = ticket.iter().map(|l| l.price * l.quantity as f64).sum();
+    let tendered_amount = tendered.parse::<f64>().ok();
+    let change = tendered_amount.map(|t| t - total);
+
+    let add_dish = {
+        let ticket = ticket.clone();
+        Callback::from(move |dish: Dish| {
+            let mut lines = (*ticket).clone();
+            match lines.iter_mut().find(|l| l.dish_id == dish.id) {
+                Some(line) if line.quantity < dish.quantity_available => line.quantity += 1,
+                Some(_) => return,
+                None if dish.quantity_available > 0 => lines.push(TicketLine {
+                    dish_id: dish.id,
+                    name: dish.name,
+                    price: dish.price,
+                    quantity: 1,
+                }),
+                None => return,
+            }
+            ticket.set(lines);
+        })
+    };
+
+    let remove_one = {
+        let ticket = ticket.clone();
+        Callback::from(move |dish_id: i32| {
+            let mut lines = (*ticket).clone();
+            if let Some(line) = lines.iter_mut().find(|l| l.dish_id == dish_id) {
+                line.quantity -= 1;
+            }
+            lines.retain(|l| l.quantity > 0);
+            ticket.set(lines);
+        })
+    };
+
+    let on_key = {
+        let tendered = tendered.clone();
+        Callback::from(move |key: &'static str| {
+            let mut value = (*tendered).clone();
+            match key {
+                "⌫" => {
+                    value.pop();
+                }
+                "." if value.contains('.') => {}
+                _ => value.push_str(key),
+            }
+            tendered.set(value);
+        })
+    };
+
+    let on_charge = {
+        let user_ctx = user_ctx.clone();
+        let ticket = ticket.clone();
+        let tendered = tendered.clone();
+        let payment_method = payment_method.clone();
+        let eat_here = eat_here.clone();
+        let last_sale = last_sale.clone();
+        let is_submitting = is_submitting.clone();
+        let refresh_trigger = refresh_trigger.clone();
+        let dishes = dishes.clone();
+        let queue = queue.clone();
+        let online = online.clone();
+        Callback::from(move |_| {
+            if ticket.is_empty() {
+                return;
+            }
+            if *payment_method == "CASH" && change.map(|c| c < 0.0).unwrap_or(false) {
+                gloo_dialogs::alert("El monto recibido no alcanza.");
+                return;
+            }
+            let token = match user_ctx.user.as_ref() {
+                Some(u) => u.token.clone(),
+                None => return,
+            };
+            let request = PosSaleRequest {
+                kermesse_id,
+                customer_name: "Cliente en puesto".to_string(),
+                items: ticket.iter().map(|l| PosSaleItem { dish_id: l.dish_id, quantity: l.quantity }).collect(),
+                delivery_method: if *eat_here { "EAT_HERE" } else { "PICKUP" }.to_string(),
+                payment_method: (*payment_method).clone(),
+                pos_mode: true,
+                amount_tendered: if *payment_method == "CASH" { tendered_amount } else { None },
+                client_key: new_client_key(),
+            };
+            let local_change = if *payment_method == "CASH" { change } else { None };
+            let ticket = ticket.clone();
+            let tendered = tendered.clone();
+            let last_sale = last_sale.clone();
+            let is_submitting = is_submitting.clone();
+            let refresh_trigger = refresh_trigger.clone();
+            let dishes = dishes.clone();
+            let queue = queue.clone();
+            let online = online.clone();
+            is_submitting.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                let body = serde_json::to_string(&request).unwrap();
+                let resp = Request::post("http://127.0.0.1:8080/sales")
+                    .header("Authorization", &format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .body(body)
+                    .send()
+                    .await;
+                match resp {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(receipt) = resp.json::<PosSaleReceipt>().await {
+                            last_sale.set(Some((format!("Venta #{} registrada", receipt.id), receipt.change_due)));
+                        }
+                        ticket.set(Vec::new());
+                        tendered.set(String::new());
+                        online.set(true);
+                        refresh_trigger.set(*refresh_trigger + 1);
+                    }
+                    Ok(resp) if resp.status() < 500 => gloo_dialogs::alert(&resp.text().await.unwrap_or_default()),
+                    // API unreachable: keep selling and sync later
+                    _ => {
+                        let mut pending = load_queue(kermesse_id);
+                        pending.push(QueuedSale { request: request.clone(), total, conflict: None });
+                        save_queue(kermesse_id, &pending);
+                        queue.set(pending);
+                        online.set(false);
+
+                        // Reflect the stock the queued sale will take
+                        let mut left = (*dishes).clone();
+                        for item in &request.items {
+                            if let Some(d) = left.iter_mut().find(|d| d.id == item.dish_id) {
+                                d.quantity_available -= item.quantity;
+                            }
+                        }
+                        dishes.set(left);
+
+                        last_sale.set(Some(("Venta guardada sin conexión".to_string(), local_change)));
+                        ticket.set(Vec::new());
+                        tendered.set(String::new());
+                    }
+                }
+                is_submitting.set(false);
+            });
+        })
+    };
+
+    html! {
+        <div class="min-h-screen bg-gray-100 p-4">
+            <div class="flex justify-between items-center mb-4">
+                <button onclick={Callback::from(move |_| navigator.back())} class="text-gray-600 hover:text-gray-900 font-medium">{ "← Salir" }</button>
+                <h1 class="text-2xl font-bold text-gray-800">{ "🧾 Punto de Venta" }</h1>
+                <div class="flex items-center gap-2">
+                    if !*online {
+                        <span class="px-3 py-1 rounded-full bg-red-100 text-red-700 text-sm font-bold">{ "Sin conexión" }</span>
+                    }
+                    if !queue.is_empty() {
+                        <span class="text-sm text-gray-600">{ format!("{} pendientes", queue.len()) }</span>
+                        <button onclick={let dispatcher = sync_tick.dispatcher(); Callback::from(move |_| dispatcher.dispatch(()))}
+                            class="px-3 py-1 rounded-lg bg-gray-800 text-white text-sm font-bold hover:bg-gray-700">{ "Sincronizar" }</button>
+                    }
+                </div>
+            </div>
+
+            if queue.iter().any(|q| q.conflict.is_some()) {
+                <div class="mb-4 bg-yellow-50 border border-yellow-200 rounded-xl p-4">
+                    <h2 class="font-bold text-yellow-800 mb-2">{ "⚠️ Ventas sin conexión rechazadas" }</h2>
+                    <ul class="space-y-2">
+                        {
+                            queue.iter().filter(|q| q.conflict.is_some()).map(|q| {
+                                let key = q.request.client_key.clone();
+                                let resolve = {
+                                    let queue = queue.clone();
+                                    move |retry: bool| {
+                                        let key = key.clone();
+                                        let queue = queue.clone();
+                                        Callback::from(move |_| {
+                                            let mut pending = load_queue(kermesse_id);
+                                            if retry {
+                                                if let Some(q) = pending.iter_mut().find(|q| q.request.client_key == key) {
+                                                    q.conflict = None;
+                                                }
+                                            } else {
+                                                if !gloo_dialogs::confirm("¿Descartar esta venta? Recuerde devolver el dinero al cliente.") {
+                                                    return;
+                                                }
+                                                pending.retain(|q| q.request.client_key != key);
+                                            }
+                                            save_queue(kermesse_id, &pending);
+                                            queue.set(pending);
+                                        })
+                                    }
+                                };
+                                html! {
+                                    <li class="flex justify-between items-center bg-white rounded-lg p-2">
+                                        <span class="text-sm">
+                                            <span class="font-bold">{ format!("Bs. {:.2}", q.total) }</span>
+                                            { format!(" — {}", q.conflict.clone().unwrap_or_default()) }
+                                        </span>
+                                        <span class="flex gap-2">
+                                            <button onclick={resolve(true)} class="text-sm px-2 py-1 rounded bg-gray-100 hover:bg-gray-200">{ "Reintentar" }</button>
+                                            <button onclick={resolve(false)} class="text-sm px-2 py-1 rounded bg-red-100 text-red-700 hover:bg-red-200">{ "Descartar" }</button>
+                                        </span>
+                                    </li>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                </div>
+            }
+
+            <div class="grid grid-cols-1 lg:grid-cols-3 gap-4">
+                // Dish buttons
+                <div class="lg:col-span-2 grid grid-cols-2 md:grid-cols-3 gap-3 content-start">
+                    {
+                        dishes.iter().map(|dish| {
+                            let sold_out = dish.quantity_available <= 0;
+                            let add_dish = add_dish.clone();
+                            let d = dish.clone();
+                            html! {
+                                <button disabled={sold_out} onclick={Callback::from(move |_| add_dish.emit(d.clone()))}
+                                    class="bg-white rounded-xl shadow p-4 text-left hover:bg-red-50 active:scale-95 transition disabled:opacity-40"
+                                >
+                                    <p class="font-bold text-lg text-gray-800">{ &dish.name }</p>
+                                    <p class="text-primary font-bold">{ format!("Bs. {:.2}", dish.price) }</p>
+                                    <p class="text-xs text-gray-400">{ format!("Quedan {}", dish.quantity_available) }</p>
+                                </button>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+
+                // Ticket + keypad
+                <div class="bg-white rounded-xl shadow p-4 space-y-4">
+                    <ul class="divide-y divide-gray-100 min-h-[6rem]">
+                        {
+                            ticket.iter().map(|line| {
+                                let id = line.dish_id;
+                                let remove_one = remove_one.clone();
+                                html! {
+                                    <li class="flex justify-between items-center py-2">
+                                        <span>{ format!("{} × {}", line.quantity, line.name) }</span>
+                                        <span class="flex items-center gap-3">
+                                            <span class="font-bold">{ format!("Bs. {:.2}", line.price * line.quantity as f64) }</span>
+                                            <button onclick={Callback::from(move |_| remove_one.emit(id))} class="text-red-500 font-bold px-2">{ "−" }</button>
+                                        </span>
+                                    </li>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                    <div class="flex justify-between text-2xl font-bold border-t pt-3">
+                        <span>{ "Total" }</span>
+                        <span>{ format!("Bs. {:.2}", total) }</span>
+                    </div>
+
+                    <div class="grid grid-cols-2 gap-2">
+                        <button onclick={let pm = payment_method.clone(); Callback::from(move |_| pm.set("CASH".to_string()))}
+                            class={format!("py-2 rounded-lg font-bold border-2 {}", if *payment_method == "CASH" { "border-primary text-primary bg-red-50" } else { "border-gray-200 text-gray-500" })}>{ "💵 Efectivo" }</button>
+                        <button onclick={let pm = payment_method.clone(); Callback::from(move |_| pm.set("QR".to_string()))}
+                            class={format!("py-2 rounded-lg font-bold border-2 {}", if *payment_method == "QR" { "border-primary text-primary bg-red-50" } else { "border-gray-200 text-gray-500" })}>{ "📱 QR" }</button>
+                    </div>
+                    <label class="flex items-center gap-2 text-sm text-gray-600">
+                        <input type="checkbox" checked={*eat_here} onchange={let eh = eat_here.clone(); Callback::from(move |_| eh.set(!*eh))} />
+                        { "Comer aquí" }
+                    </label>
+
+                    if *payment_method == "CASH" {
+                        <div>
+                            <div class="flex justify-between items-center bg-gray-50 rounded-lg px-3 py-2 mb-2">
+                                <span class="text-gray-500">{ "Recibido" }</span>
+                                <span class="text-xl font-mono font-bold">{ if tendered.is_empty() { "0".to_string() } else { (*tendered).clone() } }</span>
+                            </div>
+                            <div class="grid grid-cols-3 gap-2">
+                                {
+                                    KEYPAD.iter().map(|key| {
+                                        let on_key = on_key.clone();
+                                        let key: &'static str = key;
+                                        html! {
+                                            <button onclick={Callback::from(move |_| on_key.emit(key))} class="bg-gray-100 hover:bg-gray-200 active:bg-gray-300 rounded-lg py-3 text-xl font-bold">{ key }</button>
+                                        }
+                                    }).collect::<Html>()
+                                }
+                            </div>
+                            if let Some(change) = change {
+                                <p class={format!("mt-2 text-lg font-bold text-right {}", if change < 0.0 { "text-red-600" } else { "text-green-700" })}>
+                                    { if change < 0.0 { format!("Faltan Bs. {:.2}", -change) } else { format!("Cambio: Bs. {:.2}", change) } }
+                                </p>
+                            }
+                        </div>
+                    }
+
+                    <button onclick={on_charge} disabled={*is_submitting || ticket.is_empty()}
+                        class="w-full bg-primary text-white text-xl font-bold py-4 rounded-xl hover:bg-red-600 transition disabled:opacity-50">
+                        { "Cobrar" }
+                    </button>
+
+                    if let Some((label, change_due)) = (*last_sale).clone() {
+                        <div class="p-3 bg-green-50 border border-green-200 rounded-lg text-green-800 text-center">
+                            <p class="font-bold">{ label }</p>
+                            if let Some(c) = change_due {
+                                <p>{ format!("Entregar cambio: Bs. {:.2}", c) }</p>
+                            }
+                        </div>
+                    }
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,103 @@
+use yew::prelude::*;
+use reqwasm::http::Request;
+use serde::Deserialize;
+use crate::context::UserContext;
+use crate::pages::my_deliveries::DeliveryStop;
+
+#[derive(Clone, PartialEq, Deserialize, Debug)]
+pub struct RouteSheetGroup {
+    pub city: String,
+    pub neighborhood: String,
+    pub stops: Vec<DeliveryStop>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(RouteSheet)]
+pub fn route_sheet(props: &Props) -> Html {
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let groups = use_state(Vec::<RouteSheetGroup>::new);
+    let kermesse_id = props.kermesse_id;
+
+    {
+        let groups = groups.clone();
+        let user_ctx = user_ctx.clone();
+        use_effect_with(kermesse_id, move |_| {
+            if let Some(user) = &user_ctx.user {
+                let token = user.token.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let url = format!("http://127.0.0.1:8080/kermesses/{}/route-sheet", kermesse_id);
+                    if let Ok(resp) = Request::get(&url)
+                        .header("Authorization", &format!("Bearer {}", token))
+                        .send()
+                        .await
+                    {
+                        if let Ok(data) = resp.json::<Vec<RouteSheetGroup>>().await {
+                            groups.set(data);
+                        }
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_print = Callback::from(|_| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.print();
+        }
+    });
+
+    html! {
+        <div class="bg-white min-h-screen p-8 text-gray-900">
+            <div class="flex justify-between items-center mb-6 border-b pb-4">
+                <h1 class="text-2xl font-bold">{ "Hoja de Ruta - Delivery" }</h1>
+                <button onclick={on_print} class="print:hidden bg-gray-900 text-white px-4 py-2 rounded-lg font-bold">{ "🖨 Imprimir" }</button>
+            </div>
+
+            if groups.is_empty() {
+                <p class="text-gray-500">{ "No hay entregas pendientes." }</p>
+            }
+            {
+                groups.iter().map(|group| html! {
+                    <section class="mb-8 break-inside-avoid">
+                        <h2 class="text-lg font-bold bg-gray-100 px-3 py-2">{ format!("{} — {}", group.city, group.neighborhood) }</h2>
+                        <table class="w-full text-sm border-collapse">
+                            <thead>
+                                <tr class="border-b text-left">
+                                    <th class="p-2">{ "#" }</th>
+                                    <th class="p-2">{ "Cliente" }</th>
+                                    <th class="p-2">{ "Dirección" }</th>
+                                    <th class="p-2">{ "Teléfono" }</th>
+                                    <th class="p-2">{ "Pedido" }</th>
+                                    <th class="p-2 text-right">{ "Cobrar" }</th>
+                                    <th class="p-2">{ "✔" }</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {
+                                    group.stops.iter().map(|stop| html! {
+                                        <tr class="border-b align-top">
+                                            <td class="p-2 font-mono">{ stop.sale_id }</td>
+                                            <td class="p-2">{ &stop.customer_name }</td>
+                                            <td class="p-2">{ stop.delivery_address.clone().unwrap_or_default() }</td>
+                                            <td class="p-2">{ stop.contact_phone.clone().unwrap_or_default() }</td>
+                                            <td class="p-2">{ stop.items.join(", ") }</td>
+                                            <td class="p-2 text-right">
+                                                { if stop.payment_method == "CASH" && stop.status == "PENDING" { format!("Bs. {:.2}", stop.total_amount) } else { "—".to_string() } }
+                                            </td>
+                                            <td class="p-2">{ "☐" }</td>
+                                        </tr>
+                                    }).collect::<Html>()
+                                }
+                            </tbody>
+                        </table>
+                    </section>
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
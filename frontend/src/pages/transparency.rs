@@ -0,0 +1,282 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use reqwasm::http::Request;
+use serde::Deserialize;
+use crate::router::Route;
+use crate::context::UserContext;
+use crate::components::organizer_dashboard::deserialize_price;
+use crate::components::organizer_expenses::category_label;
+use crate::components::file_download::{download, fetch_object_url};
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct TransparencyReport {
+    pub kermesse_name: String,
+    pub beneficiary_name: String,
+    pub beneficiary_reason: String,
+    pub event_date: String,
+    pub published: bool,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub total_raised: f64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub sales_revenue: f64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub donations_total: f64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub expenses_total: f64,
+    pub expenses_by_category: Vec<CategoryTotal>,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub net_amount: f64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub delivered_total: f64,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub pending_delivery: f64,
+    pub deliveries: Vec<Delivery>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct CategoryTotal {
+    pub category: String,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub total: f64,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct Delivery {
+    pub id: i32,
+    #[serde(deserialize_with = "deserialize_price")]
+    pub amount: f64,
+    pub delivered_on: String,
+    pub note: Option<String>,
+    pub proof_file_name: String,
+    pub proof_content_type: String,
+}
+
+pub fn proof_url(delivery_id: i32) -> String {
+    format!("http://127.0.0.1:8080/deliveries/{}/proof", delivery_id)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ProofProps {
+    pub delivery_id: i32,
+    pub file_name: String,
+    /// Needed while the page is unpublished, when only the organizer can see the proofs.
+    pub token: Option<String>,
+    #[prop_or_default]
+    pub class: Classes,
+    #[prop_or_default]
+    pub children: Html,
+}
+
+/// Opens a proof: a plain link once published, a download with the organizer's token before.
+#[function_component(ProofLink)]
+pub fn proof_link(props: &ProofProps) -> Html {
+    let url = proof_url(props.delivery_id);
+    match props.token.clone() {
+        None => html! {
+            <a href={url} target="_blank" class={props.class.clone()}>{ props.children.clone() }</a>
+        },
+        Some(token) => {
+            let file_name = props.file_name.clone();
+            let onclick = Callback::from(move |_| download(url.clone(), token.clone(), file_name.clone()));
+            html! {
+                <button {onclick} class={props.class.clone()}>{ props.children.clone() }</button>
+            }
+        }
+    }
+}
+
+/// Thumbnail of an image proof, fetched with the token while the page is unpublished.
+#[function_component(ProofImage)]
+pub fn proof_image(props: &ProofProps) -> Html {
+    let src = use_state(|| None::<String>);
+
+    {
+        let src = src.clone();
+        use_effect_with((props.delivery_id, props.token.clone()), move |(delivery_id, token)| {
+            let url = proof_url(*delivery_id);
+            let object_url = std::rc::Rc::new(std::cell::RefCell::new(None::<String>));
+            match token.clone() {
+                None => src.set(Some(url)),
+                Some(token) => {
+                    let object_url = object_url.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        if let Some(fetched) = fetch_object_url(&url, &token).await {
+                            *object_url.borrow_mut() = Some(fetched.clone());
+                            src.set(Some(fetched));
+                        }
+                    });
+                }
+            }
+            move || {
+                if let Some(url) = object_url.borrow_mut().take() {
+                    let _ = web_sys::Url::revoke_object_url(&url);
+                }
+            }
+        });
+    }
+
+    match &*src {
+        Some(src) => html! { <img src={src.clone()} alt={props.file_name.clone()} class={props.class.clone()} /> },
+        None => html! { <div class={classes!(props.class.clone(), "bg-gray-100")}></div> },
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub kermesse_id: i32,
+}
+
+#[function_component(TransparencyPage)]
+pub fn transparency_page(props: &Props) -> Html {
+    let navigator = use_navigator().unwrap();
+    let user_ctx = use_context::<UserContext>().expect("No UserContext found");
+    let report = use_state(|| None::<TransparencyReport>);
+    let not_found = use_state(|| false);
+    let kermesse_id = props.kermesse_id;
+    let token = user_ctx.user.as_ref().map(|u| u.token.clone());
+
+    {
+        let report = report.clone();
+        let not_found = not_found.clone();
+        let token = token.clone();
+        use_effect_with(kermesse_id, move |id| {
+            let url = format!("http://127.0.0.1:8080/kermesses/{}/transparency", id);
+            wasm_bindgen_futures::spawn_local(async move {
+                // Signed-in organizers can preview the page before publishing it
+                let mut request = Request::get(&url);
+                if let Some(token) = &token {
+                    request = request.header("Authorization", &format!("Bearer {}", token));
+                }
+                match request.send().await {
+                    Ok(resp) if resp.ok() => {
+                        if let Ok(data) = resp.json::<TransparencyReport>().await {
+                            report.set(Some(data));
+                        }
+                    }
+                    _ => not_found.set(true),
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_back = Callback::from(move |_| navigator.push(&Route::KermesseDetail { id: kermesse_id }));
+
+    let Some(data) = &*report else {
+        return html! {
+            <div class="min-h-screen bg-gray-50 flex items-center justify-center">
+                if *not_found {
+                    <p class="text-gray-500">{ "Esta kermesse no publicó su rendición de cuentas." }</p>
+                } else {
+                    <div class="animate-spin rounded-full h-12 w-12 border-t-4 border-b-4 border-orange-500"></div>
+                }
+            </div>
+        };
+    };
+
+    let delivered_percentage = if data.net_amount > 0.0 { (data.delivered_total / data.net_amount * 100.0).min(100.0) } else { 0.0 };
+    // Unpublished pages are only visible to the organizer, whose proofs need the token
+    let proof_token = if data.published { None } else { token.clone() };
+
+    html! {
+        <div class="min-h-screen bg-gray-50 font-sans pb-12">
+            <div class="bg-gradient-to-br from-gray-900 to-gray-800 text-white pt-16 pb-24">
+                <div class="container mx-auto px-6 max-w-4xl">
+                    <button onclick={on_back} class="flex items-center text-white/70 hover:text-white font-medium transition mb-6">
+                        <span class="mr-2">{"←"}</span> { "Volver a la Kermesse" }
+                    </button>
+                    <p class="text-sm uppercase tracking-widest text-white/60 font-bold mb-2">{ "Rendición de cuentas" }</p>
+                    <h1 class="text-4xl font-display font-extrabold mb-3">{ &data.kermesse_name }</h1>
+                    <p class="text-lg text-gray-300">{ format!("A beneficio de {} · {}", data.beneficiary_name, data.event_date) }</p>
+                    <p class="text-gray-400 mt-2">{ &data.beneficiary_reason }</p>
+                    if !data.published {
+                        <p class="mt-4 inline-block bg-yellow-400 text-yellow-900 text-sm font-bold px-4 py-2 rounded-full">
+                            { "Vista previa: esta página aún no es pública." }
+                        </p>
+                    }
+                </div>
+            </div>
+
+            <div class="container mx-auto px-6 max-w-4xl -mt-12 space-y-8">
+                <div class="grid grid-cols-1 md:grid-cols-3 gap-4">
+                    <div class="bg-white rounded-2xl shadow-md p-6 border border-gray-100">
+                        <p class="text-xs text-gray-400 font-bold uppercase tracking-wider mb-1">{ "Recaudado" }</p>
+                        <p class="text-3xl font-display font-bold text-green-600">{ format!("Bs. {:.2}", data.total_raised) }</p>
+                        <p class="text-xs text-gray-500 mt-2">{ format!("Ventas Bs. {:.2} · Donaciones Bs. {:.2}", data.sales_revenue, data.donations_total) }</p>
+                    </div>
+                    <div class="bg-white rounded-2xl shadow-md p-6 border border-gray-100">
+                        <p class="text-xs text-gray-400 font-bold uppercase tracking-wider mb-1">{ "Gastos" }</p>
+                        <p class="text-3xl font-display font-bold text-red-500">{ format!("Bs. {:.2}", data.expenses_total) }</p>
+                        <p class="text-xs text-gray-500 mt-2">{ format!("Monto neto: Bs. {:.2}", data.net_amount) }</p>
+                    </div>
+                    <div class="bg-white rounded-2xl shadow-md p-6 border border-gray-100">
+                        <p class="text-xs text-gray-400 font-bold uppercase tracking-wider mb-1">{ "Entregado al beneficiario" }</p>
+                        <p class="text-3xl font-display font-bold text-purple-600">{ format!("Bs. {:.2}", data.delivered_total) }</p>
+                        <p class="text-xs text-gray-500 mt-2">{ format!("Pendiente: Bs. {:.2}", data.pending_delivery) }</p>
+                    </div>
+                </div>
+
+                <div class="bg-white rounded-2xl shadow-md p-6 border border-gray-100">
+                    <div class="flex justify-between mb-2 text-sm font-medium text-gray-600">
+                        <span>{ "Entregado del monto neto" }</span>
+                        <span>{ format!("{:.0}%", delivered_percentage) }</span>
+                    </div>
+                    <div class="w-full bg-gray-200 rounded-full h-4 overflow-hidden">
+                        <div class="bg-gradient-to-r from-purple-400 to-purple-600 h-4 rounded-full" style={format!("width: {:.1}%", delivered_percentage)}></div>
+                    </div>
+                </div>
+
+                <div class="bg-white rounded-2xl shadow-md p-6 border border-gray-100">
+                    <h2 class="text-xl font-bold text-gray-800 mb-4">{ "🧾 Resumen de Gastos" }</h2>
+                    if data.expenses_by_category.is_empty() {
+                        <p class="text-gray-500 italic">{ "No se registraron gastos." }</p>
+                    } else {
+                        <ul class="divide-y divide-gray-100 text-sm">
+                            {
+                                data.expenses_by_category.iter().map(|c| html! {
+                                    <li class="flex justify-between py-2">
+                                        <span>{ category_label(&c.category) }</span>
+                                        <span class="font-bold">{ format!("Bs. {:.2}", c.total) }</span>
+                                    </li>
+                                }).collect::<Html>()
+                            }
+                        </ul>
+                    }
+                </div>
+
+                <div class="bg-white rounded-2xl shadow-md p-6 border border-gray-100">
+                    <h2 class="text-xl font-bold text-gray-800 mb-4">{ "🤝 Entregas al Beneficiario" }</h2>
+                    if data.deliveries.is_empty() {
+                        <p class="text-gray-500 italic">{ "Aún no se registraron entregas." }</p>
+                    } else {
+                        <div class="space-y-4">
+                            {
+                                data.deliveries.iter().map(|d| {
+                                    html! {
+                                        <div class="flex flex-col md:flex-row gap-4 p-4 bg-gray-50 rounded-xl border border-gray-100">
+                                            if d.proof_content_type.starts_with("image/") {
+                                                <ProofLink delivery_id={d.id} file_name={d.proof_file_name.clone()} token={proof_token.clone()}>
+                                                    <ProofImage delivery_id={d.id} file_name={d.proof_file_name.clone()} token={proof_token.clone()} class="w-full md:w-40 h-40 object-cover rounded-lg border" />
+                                                </ProofLink>
+                                            }
+                                            <div class="flex-grow">
+                                                <p class="text-2xl font-bold text-gray-800">{ format!("Bs. {:.2}", d.amount) }</p>
+                                                <p class="text-sm text-gray-500">{ format!("Entregado el {}", d.delivered_on) }</p>
+                                                if let Some(note) = &d.note {
+                                                    <p class="text-sm text-gray-700 mt-2">{ note }</p>
+                                                }
+                                                <ProofLink delivery_id={d.id} file_name={d.proof_file_name.clone()} token={proof_token.clone()} class="inline-block mt-3 text-sm font-bold text-primary hover:underline">
+                                                    { format!("📎 Ver comprobante ({})", d.proof_file_name) }
+                                                </ProofLink>
+                                            </div>
+                                        </div>
+                                    }
+                                }).collect::<Html>()
+                            }
+                        </div>
+                    }
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -30,6 +30,20 @@ pub enum Route {
     CollaboratorDashboard,
     #[at("/kermesses/:id/orders")]
     KermesseOrders { id: i32 },
+    #[at("/kermesses/:id/kitchen")]
+    KitchenDisplay { id: i32 },
+    #[at("/kermesses/:id/route-sheet")]
+    DeliveryRouteSheet { id: i32 },
+    #[at("/my-deliveries")]
+    MyDeliveries,
+    #[at("/kermesses/:id/pos")]
+    PointOfSale { id: i32 },
+    #[at("/kermesses/:id/pickup")]
+    PickupDesk { id: i32 },
+    #[at("/orders/track/:token")]
+    OrderTracking { token: String },
+    #[at("/kermesses/:id/transparency")]
+    Transparency { id: i32 },
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -50,6 +64,13 @@ pub fn switch(routes: Route) -> Html {
         Route::EditKermesse { id } => html! { <crate::pages::edit_kermesse::EditKermesse kermesse_id={id} /> },
         Route::CollaboratorDashboard => html! { <crate::pages::collaborator_dashboard::CollaboratorDashboard /> },
         Route::KermesseOrders { id } => html! { <crate::pages::kermesse_orders::KermesseOrders kermesse_id={id} /> },
+        Route::KitchenDisplay { id } => html! { <crate::pages::kitchen_display::KitchenDisplay kermesse_id={id} /> },
+        Route::DeliveryRouteSheet { id } => html! { <crate::pages::route_sheet::RouteSheet kermesse_id={id} /> },
+        Route::MyDeliveries => html! { <crate::pages::my_deliveries::MyDeliveries /> },
+        Route::PointOfSale { id } => html! { <crate::pages::pos::PointOfSale kermesse_id={id} /> },
+        Route::PickupDesk { id } => html! { <crate::pages::pickup_desk::PickupDesk kermesse_id={id} /> },
+        Route::OrderTracking { token } => html! { <crate::pages::order_tracking::OrderTracking token={token} /> },
+        Route::Transparency { id } => html! { <crate::pages::transparency::TransparencyPage kermesse_id={id} /> },
         Route::NotFound => html! { <h1>{ "404 Not Found" }</h1> },
     }
 }
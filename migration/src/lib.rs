@@ -4,6 +4,23 @@ mod m20220101_000001_create_table;
 mod m20250109_000002_v2_schema_updates;
 mod m20250120_000003_add_payment_info;
 mod m20260127_012246_add_location_to_kermesse;
+mod m20260203_000005_add_payment_reference;
+mod m20260210_000006_create_refunds;
+mod m20260217_000007_promo_codes_and_donation_topups;
+mod m20260224_000008_create_donations;
+mod m20260303_000009_create_time_slots;
+mod m20260310_000010_add_sale_item_prep_status;
+mod m20260317_000011_add_delivery_assignment;
+mod m20260324_000012_create_delivery_zones;
+mod m20260331_000013_add_sale_client_key;
+mod m20260407_000014_create_idempotency_keys;
+mod m20260414_000015_add_sale_pickup_code;
+mod m20260421_000016_add_sale_tracking_token;
+mod m20260428_000017_create_phone_verifications;
+mod m20260505_000018_create_sale_status_history;
+mod m20260512_000019_create_expenses;
+mod m20260519_000020_create_beneficiary_deliveries;
+mod m20260526_000021_add_kermesse_delivery_enabled;
 
 pub struct Migrator;
 
@@ -15,6 +32,23 @@ impl MigratorTrait for Migrator {
             Box::new(m20250109_000002_v2_schema_updates::Migration),
             Box::new(m20250120_000003_add_payment_info::Migration),
             Box::new(m20260127_012246_add_location_to_kermesse::Migration),
+            Box::new(m20260203_000005_add_payment_reference::Migration),
+            Box::new(m20260210_000006_create_refunds::Migration),
+            Box::new(m20260217_000007_promo_codes_and_donation_topups::Migration),
+            Box::new(m20260224_000008_create_donations::Migration),
+            Box::new(m20260303_000009_create_time_slots::Migration),
+            Box::new(m20260310_000010_add_sale_item_prep_status::Migration),
+            Box::new(m20260317_000011_add_delivery_assignment::Migration),
+            Box::new(m20260324_000012_create_delivery_zones::Migration),
+            Box::new(m20260331_000013_add_sale_client_key::Migration),
+            Box::new(m20260407_000014_create_idempotency_keys::Migration),
+            Box::new(m20260414_000015_add_sale_pickup_code::Migration),
+            Box::new(m20260421_000016_add_sale_tracking_token::Migration),
+            Box::new(m20260428_000017_create_phone_verifications::Migration),
+            Box::new(m20260505_000018_create_sale_status_history::Migration),
+            Box::new(m20260512_000019_create_expenses::Migration),
+            Box::new(m20260519_000020_create_beneficiary_deliveries::Migration),
+            Box::new(m20260526_000021_add_kermesse_delivery_enabled::Migration),
         ]
     }
 }
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Sales::PaymentReference).string().null().unique_key())
+                    .add_column_if_not_exists(ColumnDef::new(Sales::PaymentProvider).string().null())
+                    .add_column_if_not_exists(ColumnDef::new(Sales::PaidAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .drop_column(Sales::PaymentReference)
+                    .drop_column(Sales::PaymentProvider)
+                    .drop_column(Sales::PaidAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    PaymentReference,
+    PaymentProvider,
+    PaidAt,
+}
@@ -0,0 +1,88 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Refunds::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Refunds::Id))
+                    .col(integer(Refunds::SaleId))
+                    .col(ColumnDef::new(Refunds::SaleItemId).integer().null()) // NULL = whole sale
+                    .col(ColumnDef::new(Refunds::Quantity).integer().null())
+                    .col(decimal(Refunds::Amount))
+                    .col(string(Refunds::Reason))
+                    .col(string(Refunds::RefundMethod)) // CASH, QR, TRANSFER
+                    .col(boolean(Refunds::Restocked).default(false))
+                    .col(integer(Refunds::CreatedBy))
+                    .col(timestamp_with_time_zone(Refunds::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-refunds-sale")
+                            .from(Refunds::Table, Refunds::SaleId)
+                            .to(Sales::Table, Sales::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-refunds-sale-item")
+                            .from(Refunds::Table, Refunds::SaleItemId)
+                            .to(SaleItems::Table, SaleItems::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-refunds-created-by")
+                            .from(Refunds::Table, Refunds::CreatedBy)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Refunds::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Refunds {
+    Table,
+    Id,
+    SaleId,
+    SaleItemId,
+    Quantity,
+    Amount,
+    Reason,
+    RefundMethod,
+    Restocked,
+    CreatedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum SaleItems {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,118 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Promo codes per kermesse
+        manager
+            .create_table(
+                Table::create()
+                    .table(PromoCodes::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PromoCodes::Id))
+                    .col(integer(PromoCodes::KermesseId))
+                    .col(string(PromoCodes::Code))
+                    .col(string(PromoCodes::DiscountType)) // PERCENTAGE, FIXED
+                    .col(decimal(PromoCodes::Value))
+                    .col(ColumnDef::new(PromoCodes::MaxUses).integer().null())
+                    .col(integer(PromoCodes::TimesUsed).default(0))
+                    .col(ColumnDef::new(PromoCodes::ExpiresAt).timestamp_with_time_zone().null())
+                    .col(boolean(PromoCodes::Active).default(true))
+                    .col(timestamp_with_time_zone(PromoCodes::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-promo-codes-kermesse")
+                            .from(PromoCodes::Table, PromoCodes::KermesseId)
+                            .to(Kermesses::Table, Kermesses::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-promo-codes-kermesse-code")
+                    .table(PromoCodes::Table)
+                    .col(PromoCodes::KermesseId)
+                    .col(PromoCodes::Code)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Sales: discount and extra donation kept apart from dish subtotals
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .add_column(ColumnDef::new(Sales::PromoCodeId).integer().null())
+                    .add_column(decimal(Sales::DiscountAmount).default(0))
+                    .add_column(decimal(Sales::ExtraDonation).default(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk-sales-promo-code")
+                    .from(Sales::Table, Sales::PromoCodeId)
+                    .to(PromoCodes::Table, PromoCodes::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .drop_foreign_key(Alias::new("fk-sales-promo-code"))
+                    .drop_column(Sales::PromoCodeId)
+                    .drop_column(Sales::DiscountAmount)
+                    .drop_column(Sales::ExtraDonation)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PromoCodes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PromoCodes {
+    Table,
+    Id,
+    KermesseId,
+    Code,
+    DiscountType,
+    Value,
+    MaxUses,
+    TimesUsed,
+    ExpiresAt,
+    Active,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Kermesses {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    PromoCodeId,
+    DiscountAmount,
+    ExtraDonation,
+}
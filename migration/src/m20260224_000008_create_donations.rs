@@ -0,0 +1,75 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Donations::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Donations::Id))
+                    .col(integer(Donations::KermesseId))
+                    .col(ColumnDef::new(Donations::UserId).integer().null())
+                    .col(decimal(Donations::Amount))
+                    .col(ColumnDef::new(Donations::DonorName).string().null())
+                    .col(boolean(Donations::IsAnonymous).default(false))
+                    .col(ColumnDef::new(Donations::Message).text().null())
+                    .col(string(Donations::PaymentMethod)) // QR, CASH, TRANSFER
+                    .col(string(Donations::Status).default("PENDING")) // PENDING, CONFIRMED, REJECTED
+                    .col(timestamp_with_time_zone(Donations::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-monetary-donations-kermesse")
+                            .from(Donations::Table, Donations::KermesseId)
+                            .to(Kermesses::Table, Kermesses::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-monetary-donations-user")
+                            .from(Donations::Table, Donations::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Donations::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Donations {
+    Table,
+    Id,
+    KermesseId,
+    UserId,
+    Amount,
+    DonorName,
+    IsAnonymous,
+    Message,
+    PaymentMethod,
+    Status,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Kermesses {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
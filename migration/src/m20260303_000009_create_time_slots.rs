@@ -0,0 +1,92 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TimeSlots::Table)
+                    .if_not_exists()
+                    .col(pk_auto(TimeSlots::Id))
+                    .col(integer(TimeSlots::KermesseId))
+                    .col(string(TimeSlots::SlotType).default("PICKUP")) // PICKUP, DELIVERY
+                    .col(string(TimeSlots::StartsAt)) // "HH:MM", same format as kermesses.start_time
+                    .col(string(TimeSlots::EndsAt))
+                    .col(integer(TimeSlots::Capacity))
+                    .col(integer(TimeSlots::Booked).default(0))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-time-slots-kermesse")
+                            .from(TimeSlots::Table, TimeSlots::KermesseId)
+                            .to(Kermesses::Table, Kermesses::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .add_column(ColumnDef::new(Sales::TimeSlotId).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk-sales-time-slot")
+                    .from(Sales::Table, Sales::TimeSlotId)
+                    .to(TimeSlots::Table, TimeSlots::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .drop_foreign_key(Alias::new("fk-sales-time-slot"))
+                    .drop_column(Sales::TimeSlotId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(TimeSlots::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TimeSlots {
+    Table,
+    Id,
+    KermesseId,
+    SlotType,
+    StartsAt,
+    EndsAt,
+    Capacity,
+    Booked,
+}
+
+#[derive(DeriveIden)]
+enum Kermesses {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    TimeSlotId,
+}
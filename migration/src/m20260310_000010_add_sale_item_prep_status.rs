@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SaleItems::Table)
+                    // PENDING, PREPARING, READY
+                    .add_column_if_not_exists(ColumnDef::new(SaleItems::PrepStatus).string().not_null().default("PENDING"))
+                    .add_column_if_not_exists(ColumnDef::new(SaleItems::PrepUpdatedAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SaleItems::Table)
+                    .drop_column(SaleItems::PrepStatus)
+                    .drop_column(SaleItems::PrepUpdatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SaleItems {
+    Table,
+    PrepStatus,
+    PrepUpdatedAt,
+}
@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Sales::DeliveryPersonId).integer().null())
+                    .add_column_if_not_exists(ColumnDef::new(Sales::DeliveryNeighborhood).string().null())
+                    .add_column_if_not_exists(ColumnDef::new(Sales::DeliveryCity).string().null())
+                    .add_column_if_not_exists(ColumnDef::new(Sales::OutForDeliveryAt).timestamp_with_time_zone().null())
+                    .add_column_if_not_exists(ColumnDef::new(Sales::DeliveredAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk-sales-delivery-person")
+                    .from(Sales::Table, Sales::DeliveryPersonId)
+                    .to(Users::Table, Users::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .drop_foreign_key(Alias::new("fk-sales-delivery-person"))
+                    .drop_column(Sales::DeliveryPersonId)
+                    .drop_column(Sales::DeliveryNeighborhood)
+                    .drop_column(Sales::DeliveryCity)
+                    .drop_column(Sales::OutForDeliveryAt)
+                    .drop_column(Sales::DeliveredAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    DeliveryPersonId,
+    DeliveryNeighborhood,
+    DeliveryCity,
+    OutForDeliveryAt,
+    DeliveredAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
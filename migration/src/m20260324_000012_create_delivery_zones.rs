@@ -0,0 +1,94 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeliveryZones::Table)
+                    .if_not_exists()
+                    .col(pk_auto(DeliveryZones::Id))
+                    .col(integer(DeliveryZones::KermesseId))
+                    .col(string(DeliveryZones::Name))
+                    .col(decimal(DeliveryZones::Fee).default(0))
+                    .col(ColumnDef::new(DeliveryZones::MinOrder).decimal().null())
+                    .col(boolean(DeliveryZones::Active).default(true))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-delivery-zones-kermesse")
+                            .from(DeliveryZones::Table, DeliveryZones::KermesseId)
+                            .to(Kermesses::Table, Kermesses::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Fee is kept apart from dish subtotals, like discount and extra donation
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .add_column(ColumnDef::new(Sales::DeliveryZoneId).integer().null())
+                    .add_column(decimal(Sales::DeliveryFee).default(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk-sales-delivery-zone")
+                    .from(Sales::Table, Sales::DeliveryZoneId)
+                    .to(DeliveryZones::Table, DeliveryZones::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sales::Table)
+                    .drop_foreign_key(Alias::new("fk-sales-delivery-zone"))
+                    .drop_column(Sales::DeliveryZoneId)
+                    .drop_column(Sales::DeliveryFee)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(DeliveryZones::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DeliveryZones {
+    Table,
+    Id,
+    KermesseId,
+    Name,
+    Fee,
+    MinOrder,
+    Active,
+}
+
+#[derive(DeriveIden)]
+enum Kermesses {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    DeliveryZoneId,
+    DeliveryFee,
+}
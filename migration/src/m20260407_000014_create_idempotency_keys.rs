@@ -0,0 +1,39 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IdempotencyKeys::Table)
+                    .if_not_exists()
+                    .col(pk_auto(IdempotencyKeys::Id))
+                    .col(string_uniq(IdempotencyKeys::Key))
+                    .col(string(IdempotencyKeys::RequestHash))
+                    .col(ColumnDef::new(IdempotencyKeys::ResponseBody).text().null())
+                    .col(timestamp_with_time_zone(IdempotencyKeys::CreatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IdempotencyKeys::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IdempotencyKeys {
+    Table,
+    Id,
+    Key,
+    RequestHash,
+    ResponseBody,
+    CreatedAt,
+}
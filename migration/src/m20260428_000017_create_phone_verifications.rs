@@ -0,0 +1,58 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PhoneVerifications::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PhoneVerifications::Id))
+                    .col(integer(PhoneVerifications::UserId))
+                    .col(string(PhoneVerifications::Phone))
+                    .col(string(PhoneVerifications::CodeHash))
+                    .col(integer(PhoneVerifications::Attempts).default(0))
+                    .col(timestamp_with_time_zone(PhoneVerifications::ExpiresAt))
+                    .col(ColumnDef::new(PhoneVerifications::VerifiedAt).timestamp_with_time_zone().null())
+                    .col(timestamp_with_time_zone(PhoneVerifications::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-phone-verifications-user")
+                            .from(PhoneVerifications::Table, PhoneVerifications::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PhoneVerifications::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PhoneVerifications {
+    Table,
+    Id,
+    UserId,
+    Phone,
+    CodeHash,
+    Attempts,
+    ExpiresAt,
+    VerifiedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
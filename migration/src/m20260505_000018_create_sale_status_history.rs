@@ -0,0 +1,65 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SaleStatusHistory::Table)
+                    .if_not_exists()
+                    .col(pk_auto(SaleStatusHistory::Id))
+                    .col(integer(SaleStatusHistory::SaleId))
+                    .col(string(SaleStatusHistory::Status))
+                    .col(ColumnDef::new(SaleStatusHistory::ChangedBy).integer().null())
+                    .col(timestamp_with_time_zone(SaleStatusHistory::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-sale-status-history-sale")
+                            .from(SaleStatusHistory::Table, SaleStatusHistory::SaleId)
+                            .to(Sales::Table, Sales::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-sale-status-history-user")
+                            .from(SaleStatusHistory::Table, SaleStatusHistory::ChangedBy)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SaleStatusHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SaleStatusHistory {
+    Table,
+    Id,
+    SaleId,
+    Status,
+    ChangedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Sales {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Expenses::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Expenses::Id))
+                    .col(integer(Expenses::KermesseId))
+                    .col(string(Expenses::Description))
+                    .col(string(Expenses::Category))
+                    .col(decimal(Expenses::Amount))
+                    .col(date(Expenses::SpentOn))
+                    .col(integer(Expenses::CreatedBy))
+                    .col(timestamp_with_time_zone(Expenses::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-expenses-kermesse")
+                            .from(Expenses::Table, Expenses::KermesseId)
+                            .to(Kermesses::Table, Kermesses::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-expenses-user")
+                            .from(Expenses::Table, Expenses::CreatedBy)
+                            .to(Users::Table, Users::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Expenses::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Expenses {
+    Table,
+    Id,
+    KermesseId,
+    Description,
+    Category,
+    Amount,
+    SpentOn,
+    CreatedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Kermesses {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,95 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Organizers opt in before the public transparency page is shown
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Kermesses::Table)
+                    .add_column_if_not_exists(boolean(Kermesses::TransparencyEnabled).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Money handed over to the beneficiary, with the receipt or photo proving it
+        manager
+            .create_table(
+                Table::create()
+                    .table(BeneficiaryDeliveries::Table)
+                    .if_not_exists()
+                    .col(pk_auto(BeneficiaryDeliveries::Id))
+                    .col(integer(BeneficiaryDeliveries::KermesseId))
+                    .col(decimal(BeneficiaryDeliveries::Amount))
+                    .col(date(BeneficiaryDeliveries::DeliveredOn))
+                    .col(ColumnDef::new(BeneficiaryDeliveries::Note).string().null())
+                    .col(string(BeneficiaryDeliveries::ProofFileName))
+                    .col(string(BeneficiaryDeliveries::ProofContentType))
+                    .col(blob(BeneficiaryDeliveries::ProofData))
+                    .col(integer(BeneficiaryDeliveries::CreatedBy))
+                    .col(timestamp_with_time_zone(BeneficiaryDeliveries::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-beneficiary-deliveries-kermesse")
+                            .from(BeneficiaryDeliveries::Table, BeneficiaryDeliveries::KermesseId)
+                            .to(Kermesses::Table, Kermesses::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-beneficiary-deliveries-user")
+                            .from(BeneficiaryDeliveries::Table, BeneficiaryDeliveries::CreatedBy)
+                            .to(Users::Table, Users::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BeneficiaryDeliveries::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Kermesses::Table)
+                    .drop_column(Kermesses::TransparencyEnabled)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BeneficiaryDeliveries {
+    Table,
+    Id,
+    KermesseId,
+    Amount,
+    DeliveredOn,
+    Note,
+    ProofFileName,
+    ProofContentType,
+    ProofData,
+    CreatedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Kermesses {
+    Table,
+    Id,
+    TransparencyEnabled,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
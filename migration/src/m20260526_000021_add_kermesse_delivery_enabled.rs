@@ -0,0 +1,63 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Delivery is opt-in, so a kermesse without zones is not free delivery by accident
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Kermesses::Table)
+                    .add_column_if_not_exists(boolean(Kermesses::DeliveryEnabled).default(false))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Kermesses that already set up zones keep delivering
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Kermesses::Table)
+                    .value(Kermesses::DeliveryEnabled, true)
+                    .and_where(
+                        Expr::col(Kermesses::Id).in_subquery(
+                            Query::select()
+                                .column(DeliveryZones::KermesseId)
+                                .from(DeliveryZones::Table)
+                                .and_where(Expr::col(DeliveryZones::Active).eq(true))
+                                .to_owned(),
+                        ),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Kermesses::Table)
+                    .drop_column(Kermesses::DeliveryEnabled)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Kermesses {
+    Table,
+    Id,
+    DeliveryEnabled,
+}
+
+#[derive(DeriveIden)]
+enum DeliveryZones {
+    Table,
+    KermesseId,
+    Active,
+}